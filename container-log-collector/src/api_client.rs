@@ -1,9 +1,11 @@
 use crate::config::Config;
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Certificate, Client, ClientBuilder};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use syslog_loose::{parse_message,Variant};
 
 /// JSON payload for sending a single log to the API
@@ -12,9 +14,55 @@ pub struct LogPayload {
     timestamp: DateTime<Utc>,
     container_name: String,
     log_message: String,
+    /// Structured content of JSON log messages, only set if PARSE_JSON_LOGS is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parsed: Option<Value>,
+    /// Original syslog line, only set if STORE_RAW_SYSLOG is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<String>,
+}
+
+/// Parses a log message as JSON object
+///
+/// # Arguments
+/// * `log_message` - Message part of the syslog line
+///
+/// # Returns
+/// * `Option<Value>` - Parsed object or None for plain text (and JSON scalars / arrays)
+pub fn parse_json_message(log_message: &str) -> Option<Value> {
+    match serde_json::from_str::<Value>(log_message.trim()) {
+        Ok(value) if value.is_object() => Some(value),
+        _ => None,
+    }
 }
 
 
+/// Configures the HTTP client with timeout, extra headers and TLS validation
+///
+/// # Arguments
+/// * `config` - Application configuration containing the TLS settings
+///
+/// # Returns
+/// * `Result<ClientBuilder>` - Configured builder or error if API_CA_CERT can't be loaded
+fn client_builder(config: &Config) -> Result<ClientBuilder> {
+    let mut builder = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .default_headers(config.extra_headers.clone())
+        .danger_accept_invalid_certs(!config.api_tls_verify);
+    if let Some(path) = &config.api_ca_cert {
+        let pem = std::fs::read(path)
+            .with_context(|| format!("Failed to read API_CA_CERT '{}'", path))?;
+        let certificate = Certificate::from_pem(&pem)
+            .with_context(|| format!("API_CA_CERT '{}' is not a valid PEM certificate", path))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    if !config.api_tls_verify {
+        log::warn!("API_TLS_VERIFY is disabled, the certificate of {} is not validated", config.api_url);
+    }
+
+    Ok(builder)
+}
+
 /// Simple HTTP client for forwarding syslog messages to the log forwarding API
 /// Provides direct, synchronous forwarding without batching or retry logic
 pub struct ApiClient {
@@ -22,28 +70,63 @@ pub struct ApiClient {
     client: Client,
     /// Application configuration containing API URL and credentials
     config: Arc<Config>,
+    /// Number of received messages without parseable timestamp (reported with each warning)
+    missing_timestamp_count: AtomicU64,
 }
 
 impl ApiClient {
-    /// Creates a new API client with HTTP timeout configured
+    /// Creates a new API client with HTTP timeout, extra headers and TLS validation configured
     /// 
     /// # Arguments
     /// * `config` - Application configuration containing API URL and secret
     /// 
     /// # Returns
-    /// * `Result<Self>` - New API client or error if HTTP client creation fails
+    /// * `Result<Self>` - New API client or error if HTTP client creation fails or API_CA_CERT can't be loaded
     pub async fn new(config: &Config) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+        let client = client_builder(config)?
             .build()
             .context("Failed to create HTTP client")?;
 
         Ok(Self {
             client,
             config: Arc::new(config.clone()),
+            missing_timestamp_count: AtomicU64::new(0),
         })
     }
 
+    /// Resolves the timestamp of a received message
+    ///
+    /// # Arguments
+    /// * `timestamp` - Timestamp parsed from the syslog line, None if it had no parseable timestamp
+    ///
+    /// # Returns
+    /// * `Option<DateTime<Utc>>` - Timestamp to forward, or None if the message is dropped
+    ///
+    /// # Behavior
+    /// - Parsed timestamps are kept
+    /// - Missing timestamps are counted and replaced by the receive time, or dropped if
+    ///   SYSLOG_TIMESTAMP_STRICT is enabled
+    fn resolve_timestamp(&self, timestamp: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match timestamp {
+            Some(timestamp) => Some(timestamp),
+            None => {
+                let count = self.missing_timestamp_count.fetch_add(1, Ordering::Relaxed) + 1;
+                if self.config.strict_timestamp {
+                    log::warn!(
+                        "Dropping message without parseable timestamp, {} timestamp-less messages so far",
+                        count
+                    );
+                    return None;
+                }
+                log::warn!(
+                    "Message without parseable timestamp, using current time, {} timestamp-less messages so far",
+                    count
+                );
+                Some(Utc::now())
+            }
+        }
+    }
+
     /// Sends a single syslog message directly to the log forwarding API
     /// 
     /// # Arguments
@@ -54,15 +137,29 @@ impl ApiClient {
     /// 
     /// # Behavior
     /// - Wraps syslog message in JSON payload 
+    /// - Attaches JSON messages as parsed object if PARSE_JSON_LOGS is enabled
+    /// - Attaches the unparsed syslog line as raw field if STORE_RAW_SYSLOG is enabled
+    /// - Messages without parseable timestamp are stamped with the current time, or dropped
+    ///   and counted if SYSLOG_TIMESTAMP_STRICT is enabled
     /// - Sends POST request to {api_url}/send_container_log endpoint
     /// - Includes X-Api-Key header for authentication
     /// - Logs errors but doesn't retry failed requests
     pub async fn send_log(&self, raw_syslog: &str) -> Result<()> {
         let syslog = parse_message(raw_syslog,Variant::RFC3164);
+        let Some(timestamp) = self.resolve_timestamp(syslog.timestamp.map(|timestamp| timestamp.to_utc())) else {
+            return Ok(());
+        };
+        let parsed = if self.config.parse_json_logs {
+            parse_json_message(syslog.msg)
+        } else {
+            None
+        };
         let payload = LogPayload {
-            timestamp :syslog.timestamp.unwrap().to_utc(),
+            timestamp,
             container_name: syslog.appname.expect("no hostname found").to_string(),
             log_message: syslog.msg.to_string(),
+            parsed,
+            raw: self.config.store_raw_syslog.then(|| raw_syslog.to_string()),
         };
         
         let url = format!("{}/send_container_log", self.config.api_url);
@@ -85,3 +182,181 @@ impl ApiClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Mutex;
+    use std::thread;
+
+    /// Minimal stand-in for the api answering every request with 201.
+    /// Returns the api url and the collected request bodies.
+    fn mock_api() -> (String, Arc<Mutex<Vec<Value>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_url = format!("http://{}", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+
+        let collected = bodies.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let collected = collected.clone();
+                thread::spawn(move || serve(stream, &collected));
+            }
+        });
+        (api_url, bodies)
+    }
+
+    /// Answers the requests of a single keep-alive connection until the client closes it.
+    fn serve(stream: TcpStream, bodies: &Mutex<Vec<Value>>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut stream = stream;
+        loop {
+            let (mut content_length, mut line) = (0, String::new());
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            bodies.lock().unwrap().push(serde_json::from_slice(&body).unwrap());
+            stream
+                .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        }
+    }
+
+    /// Sends a single syslog line with the given configuration, returns the posted payloads
+    async fn forward(config: impl FnOnce(Config) -> Config, raw_syslog: &str) -> Vec<Value> {
+        let (api_url, bodies) = mock_api();
+        let client = ApiClient::new(&config(Config::for_tests(&api_url))).await.unwrap();
+
+        client.send_log(raw_syslog).await.unwrap();
+
+        bodies.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn parse_json_message_returns_objects() {
+        assert_eq!(
+            parse_json_message(r#" {"level":"warn","ctx":{"user":42}} "#),
+            Some(json!({ "level": "warn", "ctx": { "user": 42 } }))
+        );
+    }
+
+    #[test]
+    fn parse_json_message_ignores_plain_text_and_non_objects() {
+        assert_eq!(parse_json_message("connection refused"), None);
+        assert_eq!(parse_json_message("[1, 2]"), None);
+        assert_eq!(parse_json_message("42"), None);
+        assert_eq!(parse_json_message("{\"level\":"), None);
+    }
+
+    #[tokio::test]
+    async fn send_log_keeps_original_line_as_raw() {
+        let raw_syslog = "<30>Oct 16 09:15:02 host api[12]:  Temperature  exceeded: 31.5°C\n";
+
+        let bodies = forward(|config| Config { store_raw_syslog: true, ..config }, raw_syslog).await;
+
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0]["raw"], raw_syslog);
+        assert_eq!(bodies[0]["container_name"], "api");
+    }
+
+    #[tokio::test]
+    async fn send_log_omits_raw_by_default() {
+        let bodies = forward(|config| config, "<30>Oct 16 09:15:02 host api[12]: started").await;
+
+        assert!(bodies[0].get("raw").is_none());
+    }
+
+    #[tokio::test]
+    async fn resolve_timestamp_drops_missing_timestamps_in_strict_mode() {
+        let config = Config {
+            strict_timestamp: true,
+            ..Config::for_tests("http://localhost:8080")
+        };
+        let client = ApiClient::new(&config).await.unwrap();
+        let timestamp = "2025-10-16T09:15:02Z".parse().unwrap();
+
+        assert_eq!(client.resolve_timestamp(None), None);
+        assert_eq!(client.resolve_timestamp(Some(timestamp)), Some(timestamp));
+        assert_eq!(client.missing_timestamp_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn resolve_timestamp_uses_receive_time_in_lenient_mode() {
+        let client = ApiClient::new(&Config::for_tests("http://localhost:8080")).await.unwrap();
+
+        let before = Utc::now();
+        let timestamp = client.resolve_timestamp(None).unwrap();
+
+        assert!(timestamp >= before && timestamp <= Utc::now());
+        assert_eq!(client.missing_timestamp_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn send_log_forwards_parsed_timestamp() {
+        let raw_syslog = "<30>Oct 16 09:15:02 host api[12]: started";
+
+        let bodies = forward(|config| Config { strict_timestamp: true, ..config }, raw_syslog).await;
+
+        let timestamp: DateTime<Utc> = serde_json::from_value(bodies[0]["timestamp"].clone()).unwrap();
+        let parsed = parse_message(raw_syslog, Variant::RFC3164).timestamp.unwrap();
+        assert_eq!(timestamp, parsed.to_utc());
+    }
+
+    #[test]
+    fn client_builder_applies_tls_verify_flag() {
+        let verifying = client_builder(&Config::for_tests("https://localhost:8080")).unwrap();
+        let config = Config {
+            api_tls_verify: false,
+            ..Config::for_tests("https://localhost:8080")
+        };
+        let not_verifying = client_builder(&config).unwrap();
+
+        assert!(!format!("{:?}", verifying).contains("danger_accept_invalid_certs"));
+        assert!(format!("{:?}", not_verifying).contains("danger_accept_invalid_certs: true"));
+    }
+
+    #[tokio::test]
+    async fn new_fails_on_missing_ca_cert() {
+        let config = Config {
+            api_ca_cert: Some("/nonexistent/ca.pem".to_string()),
+            ..Config::for_tests("https://localhost:8080")
+        };
+
+        let error = ApiClient::new(&config).await.err().unwrap();
+
+        assert_eq!(error.to_string(), "Failed to read API_CA_CERT '/nonexistent/ca.pem'");
+    }
+
+    #[tokio::test]
+    async fn new_fails_on_invalid_ca_cert() {
+        let path = std::env::temp_dir().join(format!("collector-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate").unwrap();
+        let config = Config {
+            api_ca_cert: Some(path.display().to_string()),
+            ..Config::for_tests("https://localhost:8080")
+        };
+
+        let error = ApiClient::new(&config).await.err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(error.to_string().ends_with("is not a valid PEM certificate"));
+    }
+}
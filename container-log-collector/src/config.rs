@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
 use dotenvy::dotenv;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::env;
 
 /// Configuration for the container log collector
@@ -14,6 +15,22 @@ pub struct Config {
     pub api_url: String,
     /// Secret API key for authentication
     pub secret: String,
+    /// Additional headers attached to every API request (default: none)
+    pub extra_headers: HeaderMap,
+    /// Whether JSON log messages are additionally forwarded as parsed object (default: false)
+    pub parse_json_logs: bool,
+    /// Maximum number of messages forwarded to the API concurrently (default: 64)
+    pub max_in_flight: usize,
+    /// Whether messages containing invalid UTF-8 are dropped instead of lossily converted (default: false)
+    pub strict_utf8: bool,
+    /// Whether the original syslog line is forwarded alongside the parsed fields (default: false)
+    pub store_raw_syslog: bool,
+    /// Whether messages without parseable timestamp are dropped instead of stamped with the current time (default: false)
+    pub strict_timestamp: bool,
+    /// Whether the TLS certificate of the API is validated (default: true)
+    pub api_tls_verify: bool,
+    /// Path to a PEM encoded CA certificate trusted in addition to the system roots (default: none)
+    pub api_ca_cert: Option<String>,
 }
 
 impl Config {
@@ -30,6 +47,15 @@ impl Config {
     /// * `SYSLOG_PORT` - UDP port for syslog server (default: 514)
     /// * `API_URL` - HTTP URL of log forwarding API (default: "http://localhost:8080")
     /// * `SECRET_API_KEY` - API authentication key (default: "123456")
+    /// * `SECRET_API_KEY_FILE` - File containing the API key, takes precedence over `SECRET_API_KEY` (e.g. docker secrets)
+    /// * `EXTRA_HEADERS` - Comma-separated extra headers, e.g. "X-Tenant: foo, X-Env: prod", values can't contain commas (default: none)
+    /// * `PARSE_JSON_LOGS` - Forward JSON log messages additionally as nested `parsed` object (default: false)
+    /// * `MAX_IN_FLIGHT` - Maximum number of concurrent forwards to the API (default: 64)
+    /// * `SYSLOG_STRICT_UTF8` - Drop messages with invalid UTF-8 instead of replacing the bytes (default: false)
+    /// * `STORE_RAW_SYSLOG` - Forward the original syslog line as `raw` field for debugging the parsing (default: false)
+    /// * `SYSLOG_TIMESTAMP_STRICT` - Drop messages without parseable timestamp instead of using the current time (default: false)
+    /// * `API_TLS_VERIFY` - Validate the TLS certificate of an https API_URL, only disable for local setups (default: true)
+    /// * `API_CA_CERT` - PEM file of a CA to trust additionally, e.g. for self-signed certificates (default: none)
     pub fn load(config_path: &str) -> Result<Self> {
         // Load the specified config file
         if std::path::Path::new(config_path).exists() {
@@ -43,7 +69,139 @@ impl Config {
             bind_address: env::var("BIND_ADDRESS").expect("BIND_ADDRESS must be set"),
             syslog_port: env::var("SYSLOG_PORT").unwrap().parse().expect("SYSLOG_PORT must be set and a number"),
             api_url: env::var("API_URL").expect("API_URL must be set"),
-            secret: env::var("SECRET_API_KEY").expect("SECRET_API_KEY must be set")
+            secret: load_secret()?,
+            extra_headers: parse_extra_headers(&env::var("EXTRA_HEADERS").unwrap_or_default())?,
+            parse_json_logs: env::var("PARSE_JSON_LOGS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            max_in_flight: env::var("MAX_IN_FLIGHT")
+                .unwrap_or_else(|_| "64".to_string())
+                .parse::<usize>()
+                .unwrap_or(64)
+                .max(1),
+            strict_utf8: env::var("SYSLOG_STRICT_UTF8")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            store_raw_syslog: env::var("STORE_RAW_SYSLOG")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            strict_timestamp: env::var("SYSLOG_TIMESTAMP_STRICT")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            api_tls_verify: env::var("API_TLS_VERIFY")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            api_ca_cert: env::var("API_CA_CERT").ok().filter(|path| !path.is_empty()),
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Config {
+    /// Configuration with the defaults of [`Config::load`] forwarding to the given API, independent of the environment
+    pub fn for_tests(api_url: &str) -> Self {
+        Self {
+            bind_address: "127.0.0.1".to_string(),
+            syslog_port: 0,
+            api_url: api_url.to_string(),
+            secret: "123456".to_string(),
+            extra_headers: HeaderMap::new(),
+            parse_json_logs: false,
+            max_in_flight: 64,
+            strict_utf8: false,
+            store_raw_syslog: false,
+            strict_timestamp: false,
+            api_tls_verify: true,
+            api_ca_cert: None,
+        }
+    }
+}
+
+/// Loads the API secret, preferring a mounted secret file over the inline variable
+///
+/// # Returns
+/// * `Result<String>` - Secret with surrounding whitespace of the file trimmed, or error if neither is available
+pub fn load_secret() -> Result<String> {
+    if let Ok(path) = env::var("SECRET_API_KEY_FILE") {
+        let secret = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read SECRET_API_KEY_FILE '{}'", path))?;
+        return Ok(secret.trim().to_string());
+    }
+    env::var("SECRET_API_KEY").context("SECRET_API_KEY or SECRET_API_KEY_FILE must be set")
+}
+
+/// Parses a comma-separated header list into a header map
+///
+/// # Arguments
+/// * `raw` - Header list in the format "Name: Value, Other-Name: Other Value".
+///   Header values can't contain commas, since the list is split at every comma
+///
+/// # Returns
+/// * `Result<HeaderMap>` - Parsed headers (empty for empty input) or error on invalid syntax
+pub fn parse_extra_headers(raw: &str) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (name, value) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("EXTRA_HEADERS entry '{}' must be in the format 'Name: Value'", entry))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .with_context(|| format!("EXTRA_HEADERS contains invalid header name '{}'", name.trim()))?;
+        let value = HeaderValue::from_str(value.trim())
+            .with_context(|| format!("EXTRA_HEADERS contains invalid value for '{}'", name))?;
+        headers.insert(name, value);
+    }
+
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extra_headers_reads_comma_separated_list() {
+        let headers = parse_extra_headers(" X-Tenant: foo , X-Env:prod,").unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-tenant"], "foo");
+        assert_eq!(headers["x-env"], "prod");
+        assert!(parse_extra_headers("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_extra_headers_rejects_invalid_entries() {
+        assert!(parse_extra_headers("X-Tenant foo").is_err());
+        assert!(parse_extra_headers("Bad Name: foo").is_err());
+        // Commas always separate entries => the part after a comma within a value is no header
+        assert!(parse_extra_headers("X-List: a, b").is_err());
+    }
+
+    // Single test => the cases don't race on the shared environment variables
+    #[test]
+    fn load_secret_prefers_trimmed_file_and_falls_back_to_env() {
+        let path = env::temp_dir().join(format!("collector-secret-{}", std::process::id()));
+        std::fs::write(&path, "file-secret\n").unwrap();
+        // SAFETY: no other test reads or writes the secret variables
+        unsafe {
+            env::set_var("SECRET_API_KEY", "env-secret");
+            env::set_var("SECRET_API_KEY_FILE", &path);
+        }
+        assert_eq!(load_secret().unwrap(), "file-secret");
+
+        std::fs::remove_file(&path).unwrap();
+        let error = load_secret().unwrap_err();
+        assert!(error.to_string().contains("Failed to read SECRET_API_KEY_FILE"));
+
+        unsafe { env::remove_var("SECRET_API_KEY_FILE") };
+        assert_eq!(load_secret().unwrap(), "env-secret");
+
+        unsafe { env::remove_var("SECRET_API_KEY") };
+        assert!(load_secret().is_err());
+    }
+}
@@ -28,6 +28,7 @@ struct Args {
 /// - Creates HTTP client for API communication
 /// - Starts UDP syslog server
 /// - Runs until SIGINT/SIGTERM received
+/// - Logs the message counters of the server on shutdown
 /// - Provides clean shutdown handling
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -61,6 +62,11 @@ async fn main() -> Result<()> {
         }
     }
     
+    let stats = syslog_server.stats();
+    log::info!(
+        "Received {} messages, {} with invalid UTF-8 ({} dropped)",
+        stats.received, stats.invalid_utf8, stats.dropped_invalid_utf8
+    );
     log::info!("Container Log Collector stopped");
     Ok(())
 }
\ No newline at end of file
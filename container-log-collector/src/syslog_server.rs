@@ -3,7 +3,20 @@ use crate::config::Config;
 use anyhow::Result;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::net::UdpSocket;
+use tokio::sync::Semaphore;
+
+/// Counters of the syslog server, logged on shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyslogStats {
+    /// Number of received UDP messages
+    pub received: u64,
+    /// Number of received messages that contained invalid UTF-8 (forwarded lossily or dropped)
+    pub invalid_utf8: u64,
+    /// Number of messages dropped due to invalid UTF-8 with SYSLOG_STRICT_UTF8 enabled
+    pub dropped_invalid_utf8: u64,
+}
 
 /// Simple UDP syslog server that forwards messages directly to HTTP API
 /// Receives syslog messages via UDP and immediately forwards them to the log forwarding API
@@ -12,6 +25,14 @@ pub struct SyslogServer {
     config: Arc<Config>,
     /// HTTP client for forwarding logs to API
     api_client: Arc<ApiClient>,
+    /// Bounds the number of concurrently running forwards to the API
+    forward_permits: Arc<Semaphore>,
+    /// Number of received UDP messages
+    received_count: AtomicU64,
+    /// Number of received messages that contained invalid UTF-8 (reported with each warning)
+    invalid_utf8_count: AtomicU64,
+    /// Number of messages dropped due to invalid UTF-8 in strict mode
+    dropped_invalid_utf8_count: AtomicU64,
 }
 
 impl SyslogServer {
@@ -24,9 +45,63 @@ impl SyslogServer {
     /// # Returns
     /// * `Self` - New syslog server instance
     pub fn new(config: Arc<Config>, api_client: Arc<ApiClient>) -> Self {
+        let forward_permits = Arc::new(Semaphore::new(config.max_in_flight));
         Self {
             config,
             api_client,
+            forward_permits,
+            received_count: AtomicU64::new(0),
+            invalid_utf8_count: AtomicU64::new(0),
+            dropped_invalid_utf8_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a snapshot of the counters of the server
+    ///
+    /// # Returns
+    /// * `SyslogStats` - Received, invalid UTF-8 and dropped message counts since startup
+    pub fn stats(&self) -> SyslogStats {
+        SyslogStats {
+            received: self.received_count.load(Ordering::Relaxed),
+            invalid_utf8: self.invalid_utf8_count.load(Ordering::Relaxed),
+            dropped_invalid_utf8: self.dropped_invalid_utf8_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Decodes a raw UDP message into a string and counts invalid UTF-8
+    ///
+    /// # Arguments
+    /// * `raw_message` - Raw UDP message bytes received from sender
+    /// * `addr` - Source address of the UDP message
+    ///
+    /// # Returns
+    /// * `Option<String>` - Decoded message or None if it was dropped due to SYSLOG_STRICT_UTF8
+    ///
+    /// # Behavior
+    /// - Valid UTF-8 is returned unchanged
+    /// - Every message is counted as received
+    /// - Invalid UTF-8 increments the counter and is either dropped (strict mode) or lossily converted
+    fn decode_message(&self, raw_message: &[u8], addr: SocketAddr) -> Option<String> {
+        self.received_count.fetch_add(1, Ordering::Relaxed);
+        match std::str::from_utf8(raw_message) {
+            Ok(message) => Some(message.to_string()),
+            Err(e) => {
+                let count = self.invalid_utf8_count.fetch_add(1, Ordering::Relaxed) + 1;
+                if self.config.strict_utf8 {
+                    self.dropped_invalid_utf8_count.fetch_add(1, Ordering::Relaxed);
+                    log::warn!(
+                        "Dropping message from {} with invalid UTF-8 ({}), {} invalid messages so far",
+                        addr, e, count
+                    );
+                    None
+                } else {
+                    log::warn!(
+                        "Message from {} contains invalid UTF-8 ({}), forwarding lossily, {} invalid messages so far",
+                        addr, e, count
+                    );
+                    Some(String::from_utf8_lossy(raw_message).to_string())
+                }
+            }
         }
     }
 
@@ -38,7 +113,9 @@ impl SyslogServer {
     /// # Behavior
     /// - Binds UDP socket to configured address and port
     /// - Runs infinite loop receiving UDP messages
-    /// - Forwards each message immediately to HTTP API
+    /// - Validates UTF-8 and drops invalid messages if SYSLOG_STRICT_UTF8 is enabled
+    /// - Forwards each message in a spawned task, bounded by MAX_IN_FLIGHT concurrent forwards
+    /// - Waits for a free slot if saturated (logged as warning) instead of blocking on the API call
     /// - Logs errors but continues processing other messages
     /// - Uses 8KB buffer for incoming syslog messages
     pub async fn run(&self) -> Result<()> {
@@ -54,10 +131,17 @@ impl SyslogServer {
             log::trace!("Waiting for UDP message...");
             match socket.recv_from(&mut buf).await {
                 Ok((len, addr)) => {
-                    let message = &buf[..len];
-                    if let Err(e) = self.handle_syslog_message(message, addr).await {
-                        log::error!("Error handling syslog message from {}: {}", addr, e);
-                    }
+                    // Decoding copies the message out of the buffer => the buffer is reused while the forward runs
+                    let Some(message) = self.decode_message(&buf[..len], addr) else {
+                        continue;
+                    };
+                    let api_client = self.api_client.clone();
+                    self.spawn_forward(async move {
+                        if let Err(e) = Self::handle_syslog_message(&api_client, &message, addr).await {
+                            log::error!("Error handling syslog message from {}: {}", addr, e);
+                        }
+                    })
+                    .await?;
                 }
                 Err(e) => {
                     log::error!("Error receiving UDP message: {}", e);
@@ -66,27 +150,150 @@ impl SyslogServer {
         }
     }
 
+    /// Spawns a forward once a slot is free, bounded by MAX_IN_FLIGHT concurrent forwards
+    ///
+    /// # Arguments
+    /// * `forward` - Forward of a single message, runs in its own task
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success once the forward is spawned, error if the semaphore was closed
+    ///
+    /// # Behavior
+    /// - Takes a free slot immediately if available
+    /// - Waits for a free slot if saturated (logged as warning) => the receive loop is throttled
+    /// - Releases the slot once the forward finished
+    async fn spawn_forward<F>(&self, forward: F) -> Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let permit = match self.forward_permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                log::warn!(
+                    "Forwarding saturated ({} in flight), waiting for a free slot",
+                    self.config.max_in_flight
+                );
+                self.forward_permits.clone().acquire_owned().await?
+            }
+        };
+        log::trace!(
+            "{} forwards in flight",
+            self.config.max_in_flight - self.forward_permits.available_permits()
+        );
+
+        tokio::spawn(async move {
+            forward.await;
+            drop(permit);
+        });
+        Ok(())
+    }
+
     /// Handles a single incoming syslog message by forwarding it to the API
     /// 
     /// # Arguments
-    /// * `raw_message` - Raw UDP message bytes received from sender
+    /// * `api_client` - HTTP client for forwarding logs to API
+    /// * `message` - Decoded syslog message received from sender
     /// * `addr` - Source address of the UDP message
     /// 
     /// # Returns
     /// * `Result<()>` - Success or error if message processing/forwarding fails
     /// 
     /// # Behavior
-    /// - Converts raw bytes to UTF-8 string (lossy conversion for invalid UTF-8)
     /// - Logs the received message at debug level
     /// - Immediately forwards to API client without buffering
     /// - Returns error if API forwarding fails (logged by caller)
-    async fn handle_syslog_message(&self, raw_message: &[u8], addr: SocketAddr) -> Result<()> {
-        let message_str = String::from_utf8_lossy(raw_message).to_string();
-        log::debug!("Received syslog message from {}: {}", addr, message_str.trim());
+    async fn handle_syslog_message(api_client: &ApiClient, message: &str, addr: SocketAddr) -> Result<()> {
+        log::debug!("Received syslog message from {}: {}", addr, message.trim());
         
         // Forward the raw syslog message directly to the API
-        self.api_client.send_log(&message_str).await?;
+        api_client.send_log(message).await?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDER: &str = "127.0.0.1:40000";
+
+    async fn server(config: Config) -> SyslogServer {
+        let api_client = Arc::new(ApiClient::new(&config).await.unwrap());
+        SyslogServer::new(Arc::new(config), api_client)
+    }
+
+    #[tokio::test]
+    async fn decode_message_keeps_valid_utf8() {
+        let server = server(Config::for_tests("http://localhost:8080")).await;
+
+        let message = server.decode_message("<14>Jan 1 00:00:00 host app: größer".as_bytes(), SENDER.parse().unwrap());
+
+        assert_eq!(message.as_deref(), Some("<14>Jan 1 00:00:00 host app: größer"));
+        assert_eq!(
+            server.stats(),
+            SyslogStats { received: 1, invalid_utf8: 0, dropped_invalid_utf8: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_message_forwards_invalid_utf8_lossily_and_counts_it() {
+        let server = server(Config::for_tests("http://localhost:8080")).await;
+
+        let message = server.decode_message(b"app: temp \xff\xfe high", SENDER.parse().unwrap());
+        server.decode_message(b"\xc3", SENDER.parse().unwrap());
+
+        assert_eq!(message.as_deref(), Some("app: temp \u{FFFD}\u{FFFD} high"));
+        assert_eq!(
+            server.stats(),
+            SyslogStats { received: 2, invalid_utf8: 2, dropped_invalid_utf8: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_message_drops_invalid_utf8_in_strict_mode() {
+        let config = Config {
+            strict_utf8: true,
+            ..Config::for_tests("http://localhost:8080")
+        };
+        let server = server(config).await;
+
+        assert_eq!(server.decode_message(b"app: \xff", SENDER.parse().unwrap()), None);
+        assert!(server.decode_message(b"app: ok", SENDER.parse().unwrap()).is_some());
+        assert_eq!(
+            server.stats(),
+            SyslogStats { received: 2, invalid_utf8: 1, dropped_invalid_utf8: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn spawn_forward_never_exceeds_max_in_flight() {
+        let config = Config {
+            max_in_flight: 2,
+            ..Config::for_tests("http://localhost:8080")
+        };
+        let server = server(config).await;
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let max_in_flight = Arc::new(AtomicU64::new(0));
+        let finished = Arc::new(AtomicU64::new(0));
+
+        for _ in 0..10 {
+            let (in_flight, max_in_flight, finished) = (in_flight.clone(), max_in_flight.clone(), finished.clone());
+            server
+                .spawn_forward(async move {
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    finished.fetch_add(1, Ordering::SeqCst);
+                })
+                .await
+                .unwrap();
+        }
+        while finished.load(Ordering::SeqCst) < 10 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::AppState;
+use crate::server_error::ServerError;
+use actix_web::{FromRequest, HttpRequest, dev::Payload, http::StatusCode, web};
+use std::env;
+use std::future::{Ready, ready};
+
+/// Header carrying the api key, the same one the load balancer checks
+const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Expected api key of the data endpoints, loaded once during startup.
+///
+/// # Environment Variables
+/// * `SECRET_API_KEY_FILE` - Read the key from this file, e.g. a docker secret (takes precedence)
+/// * `SECRET_API_KEY` - Expected key (default: unset => checking is disabled and only the load balancer guards the api)
+///
+/// Same variables as the senders use => a shared `.env` / secret configures both sides.
+pub struct ApiKey {
+    expected: Option<String>,
+}
+
+impl ApiKey {
+    /// Loads the expected key, surrounding whitespace of a key file is trimmed.
+    ///
+    /// # Returns
+    /// * `Ok(ApiKey)` - Configured key or disabled check if neither variable is set
+    /// * `Err(String)` - `SECRET_API_KEY_FILE` can't be read or the key is empty
+    pub fn from_env() -> Result<Self, String> {
+        let expected = match env::var("SECRET_API_KEY_FILE") {
+            Ok(path) => Some(
+                std::fs::read_to_string(&path)
+                    .map(|key| key.trim().to_string())
+                    .map_err(|e| format!("SECRET_API_KEY_FILE '{}' can't be read: {}", path, e))?,
+            ),
+            Err(_) => env::var("SECRET_API_KEY").ok(),
+        };
+
+        if expected.as_deref().is_some_and(str::is_empty) {
+            return Err(String::from("API key must not be empty"));
+        }
+        Ok(Self { expected })
+    }
+
+    /// Whether requests are checked at all.
+    pub fn is_enabled(&self) -> bool {
+        self.expected.is_some()
+    }
+
+    /// Checks the key sent by a client.
+    ///
+    /// # Parameters
+    /// * `provided` - Value of the `X-Api-Key` header, if present
+    ///
+    /// # Returns
+    /// * `Ok(())` - Key matches or checking is disabled
+    /// * `Err(ServerError)` - 401 if the key is missing or wrong
+    pub fn check(&self, provided: Option<&str>) -> Result<(), ServerError> {
+        let Some(expected) = &self.expected else {
+            return Ok(());
+        };
+
+        match provided {
+            Some(provided) if constant_time_eq(provided.as_bytes(), expected.as_bytes()) => Ok(()),
+            Some(_) => Err(ServerError {
+                code: StatusCode::UNAUTHORIZED,
+                message: String::from("Invalid api key"),
+                additional_information: format!("The {} header does not match the configured key", API_KEY_HEADER),
+            }),
+            None => Err(ServerError {
+                code: StatusCode::UNAUTHORIZED,
+                message: String::from("Missing api key"),
+                additional_information: format!("Send the api key in the {} header", API_KEY_HEADER),
+            }),
+        }
+    }
+}
+
+/// Compares without returning early on the first mismatch => response times don't leak the key prefix.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Extractor guarding an endpoint with the api key of the `AppState`.
+///
+/// Endpoints taking this extractor reject requests without a matching `X-Api-Key` header with 401
+/// before the handler runs. Endpoints without it (e.g. `/whoareyou` and `/health` for probes) stay open.
+///
+/// # Examples
+/// ```rust
+/// #[get("/logs")]
+/// async fn get_logs(_auth: Authenticated, data: web::Data<AppState>, ...) -> ActixResult<HttpResponse>
+/// ```
+pub struct Authenticated;
+
+impl FromRequest for Authenticated {
+    type Error = ServerError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let Some(data) = req.app_data::<web::Data<AppState>>() else {
+            return ready(Err(ServerError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: String::from("Application state is not configured"),
+                additional_information: String::from("AppState missing in app data"),
+            }));
+        };
+        let provided = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok());
+
+        ready(data.api_key.check(provided).map(|_| Authenticated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_time_eq_compares_whole_input() {
+        assert!(constant_time_eq(b"1234567", b"1234567"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"1234567", b"1234568"));
+        assert!(!constant_time_eq(b"x234567", b"1234567"));
+        // Prefixes of the key don't match
+        assert!(!constant_time_eq(b"123", b"1234567"));
+    }
+
+    #[test]
+    fn check_requires_matching_key_if_enabled() {
+        let api_key = ApiKey {
+            expected: Some("1234567".to_string()),
+        };
+
+        assert!(api_key.check(Some("1234567")).is_ok());
+        assert_eq!(api_key.check(Some("wrong")).unwrap_err().code, StatusCode::UNAUTHORIZED);
+        assert_eq!(api_key.check(None).unwrap_err().code, StatusCode::UNAUTHORIZED);
+        assert!(ApiKey { expected: None }.check(None).is_ok());
+    }
+}
@@ -1,27 +1,399 @@
 use crate::log_entry::{ElasticLogDocument, LogEntry, ContainerLogEntry};
-use crate::query_structures::{LogQuery, SearchQuery, ContainerLogQuery, ContainerSearchQuery};
+use crate::query_structures::{LogQuery, SearchQuery, ContainerLogQuery, ContainerSearchQuery, LevelStatsQuery};
 use crate::server_error::ServerError;
 use actix_web::http::StatusCode;
+use chrono::{DateTime, SecondsFormat, Utc};
 use elasticsearch::{
-    Elasticsearch, IndexParts, SearchParts,
+    BulkOperation, BulkParts, Elasticsearch, IndexParts, OpenPointInTimeParts, SearchParts,
+    params::OpType,
     auth::Credentials,
-    http::transport::{SingleNodeConnectionPool, TransportBuilder},
-    indices::{IndicesCreateParts, IndicesExistsParts},
+    cert::{Certificate, CertificateValidation},
+    cluster::ClusterHealthParts,
+    http::transport::{ConnectionPool, MultiNodeConnectionPool, SingleNodeConnectionPool, TransportBuilder},
+    ilm::IlmPutLifecycleParts,
+    indices::{IndicesCreateParts, IndicesExistsParts, IndicesPutIndexTemplateParts, IndicesPutSettingsParts},
 };
 //use env_logger::builder;
 use serde::Serialize;
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::env;
+use std::future::Future;
 use std::result::Result::Ok;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use url::Url;
+use uuid::Uuid;
+
+/// Version of the document schema written by this api. Increase on breaking schema changes
+/// (e.g. renamed fields) so queries can tell documents of different versions apart.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Settings applied when persisting and querying documents, loaded once during startup.
+///
+/// # Environment Variables
+/// * `TIME_BASED_INDICES` - Write into daily indices `<index>-YYYY.MM.DD` instead of a single index (default: false)
+/// * `MAX_LOG_AGE_SECS` - Reject logs older than now minus this many seconds with 422 (default: unset => disabled)
+/// * `MAX_LOG_FUTURE_SECS` - Tolerated clock skew into the future while `MAX_LOG_AGE_SECS` is set (default: 300)
+/// * `SCHEMA_VERSION` - Version stamped on every indexed document (default: [`SCHEMA_VERSION`])
+/// * `MAX_MESSAGE_LEN` - Truncate messages to this many characters plus a marker (default: unset => disabled)
+/// * `ELASTIC_PIPELINE` - Ingest pipeline applied to every indexed document, must already exist in elastic search (default: none)
+/// * `STRICT_INGEST` - Reject ingested logs containing unknown fields instead of ignoring them (default: false)
+/// * `UNKNOWN_DEVICE` - Device of sensor logs sent without or with a blank device (default: unknown)
+/// * `VERBOSE_INDEX_RESPONSE` - Return the pretty printed entry instead of a terse `indexed` (default: false with DEPLOYMENT=PROD, true otherwise)
+/// * `INDEX_MAX_ATTEMPTS` - Attempts of a single document index request on timeouts, connection errors and 5xx (default: 3)
+/// * `INDEX_RETRY_BASE_MS` - Backoff before the first retry, doubled for every further retry plus jitter (default: 100)
+/// * `BULK_ERROR_MODE` - Status of bulk requests with failed entries, `ok` => 200 or `multi_status` => 207 (default: ok)
+#[derive(Debug, Clone)]
+pub struct IndexingConfig {
+    pub time_based_indices: bool,
+    pub verbose_response: bool,
+    pub schema_version: u32,
+    pub max_message_len: Option<usize>,
+    pub pipeline: Option<String>,
+    pub strict_ingest: bool,
+    pub unknown_device: String,
+    pub max_log_age: Option<chrono::Duration>,
+    pub max_log_future: chrono::Duration,
+    pub bulk_error_mode: BulkErrorMode,
+    pub index_max_attempts: u32,
+    pub index_retry_base_delay: Duration,
+}
+
+impl IndexingConfig {
+    pub fn from_env() -> Self {
+        Self {
+            time_based_indices: env::var("TIME_BASED_INDICES")
+                .unwrap_or_default()
+                .parse()
+                .unwrap_or(false),
+            verbose_response: env::var("VERBOSE_INDEX_RESPONSE")
+                .ok()
+                .and_then(|verbose| verbose.parse().ok())
+                .unwrap_or(env::var("DEPLOYMENT").unwrap_or_default() != "PROD"),
+            schema_version: env::var("SCHEMA_VERSION")
+                .ok()
+                .and_then(|version| version.parse().ok())
+                .unwrap_or(SCHEMA_VERSION),
+            max_message_len: env::var("MAX_MESSAGE_LEN")
+                .ok()
+                .and_then(|len| len.parse().ok()),
+            pipeline: env::var("ELASTIC_PIPELINE")
+                .ok()
+                .filter(|pipeline| !pipeline.trim().is_empty()),
+            strict_ingest: env::var("STRICT_INGEST")
+                .unwrap_or_default()
+                .parse()
+                .unwrap_or(false),
+            unknown_device: env::var("UNKNOWN_DEVICE")
+                .ok()
+                .filter(|device| !device.trim().is_empty())
+                .unwrap_or_else(|| "unknown".to_string()),
+            max_log_age: env::var("MAX_LOG_AGE_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .map(chrono::Duration::seconds),
+            max_log_future: chrono::Duration::seconds(
+                env::var("MAX_LOG_FUTURE_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
+            ),
+            bulk_error_mode: match env::var("BULK_ERROR_MODE").unwrap_or_default().trim() {
+                "multi_status" => BulkErrorMode::MultiStatus,
+                _ => BulkErrorMode::Ok,
+            },
+            index_max_attempts: env::var("INDEX_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse::<u32>()
+                .unwrap_or(3)
+                .max(1),
+            index_retry_base_delay: Duration::from_millis(
+                env::var("INDEX_RETRY_BASE_MS")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+            ),
+        }
+    }
+
+    /// Truncates the message of a log entry to `MAX_MESSAGE_LEN` characters in place.
+    ///
+    /// Truncated messages end with a `...[truncated]` marker, shorter messages are left untouched.
+    pub fn truncate_message<T: ElasticLogDocument>(&self, log_entry: &mut T) {
+        let Some(max_len) = self.max_message_len else {
+            return;
+        };
+        let message = log_entry.message_mut();
+
+        // Cut at a char boundary => multi byte characters are never split
+        if let Some((cut, _)) = message.char_indices().nth(max_len) {
+            message.truncate(cut);
+            message.push_str("...[truncated]");
+        }
+    }
+
+    /// Checks whether a log timestamp lies within the accepted window around now.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Timestamp is accepted (always the case without `MAX_LOG_AGE_SECS`)
+    /// * `Err(ServerError)` - 422 naming the offending timestamp if it is too old or too far in the future
+    pub fn check_timestamp(&self, timestamp: DateTime<Utc>) -> Result<(), ServerError> {
+        let Some(max_age) = self.max_log_age else {
+            return Ok(());
+        };
+        let now = Utc::now();
+
+        if timestamp < now - max_age {
+            return Err(ServerError {
+                code: StatusCode::UNPROCESSABLE_ENTITY,
+                message: format!(
+                    "Log timestamp {} is older than the allowed maximum age of {} seconds",
+                    timestamp.to_rfc3339(),
+                    max_age.num_seconds()
+                ),
+                additional_information: format!("Server time: {}", now.to_rfc3339()),
+            });
+        }
+
+        if timestamp > now + self.max_log_future {
+            return Err(ServerError {
+                code: StatusCode::UNPROCESSABLE_ENTITY,
+                message: format!(
+                    "Log timestamp {} is more than {} seconds in the future",
+                    timestamp.to_rfc3339(),
+                    self.max_log_future.num_seconds()
+                ),
+                additional_information: format!("Server time: {}", now.to_rfc3339()),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index (pattern) that has to be searched for documents of the given base index.
+    pub fn search_index(&self, base: &str) -> String {
+        if self.time_based_indices {
+            format!("{}-*", base)
+        } else {
+            base.to_string()
+        }
+    }
+}
+
+/// Resolves the daily index a document with the given timestamp belongs to.
+///
+/// # Examples
+/// ```rust
+/// // 2025-01-15T10:00:00Z => "iot_sensor-2025.01.15"
+/// let index_name = resolve_index_name("iot_sensor", log_entry.timestamp);
+/// ```
+pub fn resolve_index_name(base: &str, timestamp: DateTime<Utc>) -> String {
+    format!("{}-{}", base, timestamp.format("%Y.%m.%d"))
+}
+
+/// Fields of the sensor log index that may be used as sort keys
+pub const LOG_SORT_FIELDS: [&str; 5] = ["timestamp", "level", "temperature", "humidity", "msg.device"];
+
+/// Fields of the container log index that may be used as sort keys
+pub const CONTAINER_SORT_FIELDS: [&str; 2] = ["timestamp", "container_name"];
+
+/// Circuit breaker guarding requests towards the elastic search cluster.
+///
+/// After `failure_threshold` consecutive failures the circuit opens and every request fails fast
+/// with a 503 for the configured cooldown. Afterwards a single probe request is let through
+/// (half-open): a successful probe closes the circuit again, a failed one reopens it.
+/// All state is kept in atomics so the breaker can be shared between actix workers via `AppState`.
+///
+/// # Examples
+/// ```
+/// let breaker = CircuitBreaker::from_env();
+/// let result = breaker.call(send_document(&index_name, &client, &log_entry)).await?;
+/// ```
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: AtomicU32,
+    /// Milliseconds since `started` at which the circuit opened, 0 while closed
+    opened_at: AtomicU64,
+    probe_in_flight: AtomicBool,
+    started: Instant,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed circuit breaker. A `failure_threshold` of 0 disables the breaker.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            consecutive_failures: AtomicU32::new(0),
+            opened_at: AtomicU64::new(0),
+            probe_in_flight: AtomicBool::new(false),
+            started: Instant::now(),
+        }
+    }
+
+    /// Creates a circuit breaker configured via CIRCUIT_BREAKER_THRESHOLD (default: 5, 0 disables)
+    /// and CIRCUIT_BREAKER_COOLDOWN_SECS (default: 30).
+    pub fn from_env() -> Self {
+        let failure_threshold: u32 = env::var("CIRCUIT_BREAKER_THRESHOLD")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .unwrap_or(5);
+
+        let cooldown_secs: u64 = env::var("CIRCUIT_BREAKER_COOLDOWN_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30);
+
+        Self::new(failure_threshold, Duration::from_secs(cooldown_secs))
+    }
+
+    /// Runs the given elastic search request through the breaker.
+    ///
+    /// Only 5xx errors count as failures, client errors (4xx) leave the breaker untouched.
+    ///
+    /// # Returns
+    /// * `Ok(T)` - Result of the wrapped request
+    /// * `Err(ServerError)` - 503 if the circuit is open, otherwise the error of the wrapped request
+    pub async fn call<T>(
+        &self,
+        request: impl Future<Output = Result<T, ServerError>>,
+    ) -> Result<T, ServerError> {
+        self.check()?;
+
+        let result = request.await;
+        match &result {
+            Ok(_) => self.record_success(),
+            Err(e) if e.code.is_server_error() => self.record_failure(),
+            Err(_) => self.probe_in_flight.store(false, Ordering::SeqCst),
+        }
+
+        result
+    }
+
+    /// Returns an error if requests are currently not allowed to pass.
+    fn check(&self) -> Result<(), ServerError> {
+        let opened_at = self.opened_at.load(Ordering::SeqCst);
+        if self.failure_threshold == 0 || opened_at == 0 {
+            return Ok(());
+        }
+
+        let open_for = Duration::from_millis(self.now_millis().saturating_sub(opened_at));
+        if open_for >= self.cooldown
+            && self
+                .probe_in_flight
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        {
+            // Half-open => exactly one probe request may pass
+            return Ok(());
+        }
+
+        Err(ServerError {
+            code: StatusCode::SERVICE_UNAVAILABLE,
+            message: String::from("Elastic search is currently unavailable!"),
+            additional_information: format!(
+                "Circuit breaker open after {} consecutive failures, retrying after {}s cooldown",
+                self.failure_threshold,
+                self.cooldown.as_secs()
+            ),
+        })
+    }
+
+    /// Closes the circuit and resets the failure counter.
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+        self.opened_at.store(0, Ordering::SeqCst);
+        self.probe_in_flight.store(false, Ordering::SeqCst);
+    }
+
+    /// Counts a failure and (re)opens the circuit once the threshold is reached.
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.failure_threshold > 0 && failures >= self.failure_threshold {
+            let was_closed = self.opened_at.swap(self.now_millis(), Ordering::SeqCst) == 0;
+            if was_closed {
+                log::warn!("Circuit breaker opened after {} consecutive elastic search failures", failures);
+            }
+        }
+        self.probe_in_flight.store(false, Ordering::SeqCst);
+    }
+
+    /// Milliseconds since creation of the breaker, never 0 so it can't be confused with a closed circuit.
+    fn now_millis(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64 + 1
+    }
+}
 
 /// Creates a elastic search client
 ///
+/// # Environment Variables
+/// * `ELASTIC_URLS` - Comma-separated node urls, e.g. "https://es01:9200,https://es02:9200,https://es03:9200".
+///   With more than one url requests are distributed round-robin over the nodes
+/// * `ELASTIC_URL` - Single node url, used if `ELASTIC_URLS` is not set (backward compatible)
+///
 /// # Examples
 /// ```
 /// let client: Elasticsearch = create_client()?;
 /// ```
 pub fn create_client() -> Result<Elasticsearch, ServerError> {
+    let str_urls: String = env::var("ELASTIC_URLS")
+        .ok()
+        .filter(|urls| !urls.trim().is_empty())
+        .map_or_else(|| env::var("ELASTIC_URL"), Ok)
+        .map_err(|_| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("URL for elastic search authentication not set"),
+            additional_information: String::from("Set ELASTIC_URLS or ELASTIC_URL in .env / env variables!"),
+        })?;
+
+    let urls = str_urls
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(parse_url)
+        .collect::<Result<Vec<Url>, ServerError>>()?;
+
+    match urls.len() {
+        0 => Err(ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("URL for elastic search authentication not set"),
+            additional_information: String::from("ELASTIC_URLS / ELASTIC_URL must contain at least one url"),
+        }),
+        1 => create_client_for_pool(SingleNodeConnectionPool::new(urls[0].clone())),
+        // No reseeding => only the configured nodes are used, sniffing would need the nodes to publish reachable addresses
+        _ => create_client_for_pool(MultiNodeConnectionPool::round_robin(urls, None)),
+    }
+}
+
+/// Creates a elastic search client against an explicitly given base url.
+///
+/// Credentials and certificate validation (see [`cert_validation_from_env`]) are still read from the environment.
+/// This allows pointing the client at a different cluster (or a mock server) without touching ELASTIC_URL.
+///
+/// # Examples
+/// ```
+/// let client: Elasticsearch = create_client_for_url("http://localhost:9200")?;
+/// ```
+pub fn create_client_for_url(str_url: &str) -> Result<Elasticsearch, ServerError> {
+    create_client_for_pool(SingleNodeConnectionPool::new(parse_url(str_url)?))
+}
+
+/// Parses a node url with proper scheme detection.
+fn parse_url(str_url: &str) -> Result<Url, ServerError> {
+    Url::parse(str_url).map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Error while parsing URL via Url Crate!"),
+        additional_information: e.to_string(),
+    })
+}
+
+/// Creates a elastic search client on the given connection pool with the credentials of the environment.
+fn create_client_for_pool<P>(pool: P) -> Result<Elasticsearch, ServerError>
+where
+    P: ConnectionPool + std::fmt::Debug + Clone + Send + 'static,
+{
     let username: String = env::var("ELASTIC_USERNAME").map_err(|_| ServerError {
         code: StatusCode::INTERNAL_SERVER_ERROR,
         message: String::from("Username for elastic search authentication not set"),
@@ -32,26 +404,11 @@ pub fn create_client() -> Result<Elasticsearch, ServerError> {
         message: String::from("Password for elastic search authentication not set"),
         additional_information: String::from("Set ELASTIC_PASSWORD in .env / env variables!"),
     })?;
-    let str_url: String = env::var("ELASTIC_URL").map_err(|_| ServerError {
-        code: StatusCode::INTERNAL_SERVER_ERROR,
-        message: String::from("URL for elastic search authentication not set"),
-        additional_information: String::from("Set ELASTIC_URL in .env / env variables!"),
-    })?;
-
-    // Parse URL with proper scheme detection
-    let url: Url = Url::parse(&str_url).map_err(|e| ServerError {
-        code: StatusCode::INTERNAL_SERVER_ERROR,
-        message: String::from("Error while parsing URL via Url Crate!"),
-        additional_information: e.to_string(),
-    })?;
-
-    let pool: SingleNodeConnectionPool = SingleNodeConnectionPool::new(url);
 
-    //Since of a local project we disable cert and only use basic authentication
     let transport = TransportBuilder::new(pool)
         .auth(Credentials::Basic(username, password))
         .disable_proxy()
-        .cert_validation(elasticsearch::cert::CertificateValidation::None)
+        .cert_validation(cert_validation_from_env()?)
         .build()
         .map_err(|e| ServerError {
             code: StatusCode::GATEWAY_TIMEOUT,
@@ -62,8 +419,104 @@ pub fn create_client() -> Result<Elasticsearch, ServerError> {
     Ok(Elasticsearch::new(transport))
 }
 
+/// Reads the certificate validation of the elastic search connection.
+///
+/// # Environment Variables
+/// * `ELASTIC_CERT_VALIDATION` - One of (default: `none`, keeps local setups with self-signed certificates working)
+///   * `none` - Don't validate the certificate at all
+///   * `default` - Validate against the system root certificates
+///   * `ca:<path>` - Validate against the PEM encoded CA certificate at `<path>`, e.g. `ca:/usr/share/certs/ca/ca.crt`
+///
+/// # Returns
+/// * `Ok(CertificateValidation)` - Validation to configure on the `TransportBuilder`
+/// * `Err(ServerError)` - Unknown mode or the CA certificate can't be read / parsed
+fn cert_validation_from_env() -> Result<CertificateValidation, ServerError> {
+    let mode = env::var("ELASTIC_CERT_VALIDATION").unwrap_or_else(|_| "none".to_string());
+
+    match mode.trim() {
+        "none" => Ok(CertificateValidation::None),
+        "default" => Ok(CertificateValidation::Default),
+        mode => {
+            let path = mode.strip_prefix("ca:").ok_or_else(|| ServerError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: format!("Unknown ELASTIC_CERT_VALIDATION '{}'", mode),
+                additional_information: String::from("Use none, default or ca:<path to pem>"),
+            })?;
+            let pem = std::fs::read(path).map_err(|e| ServerError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: format!("CA certificate '{}' can't be read", path),
+                additional_information: e.to_string(),
+            })?;
+            let certificate = Certificate::from_pem(&pem).map_err(|e| ServerError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: format!("CA certificate '{}' is not a valid PEM certificate", path),
+                additional_information: e.to_string(),
+            })?;
+            Ok(CertificateValidation::Full(certificate))
+        }
+    }
+}
+
+/// Waits until the elastic search cluster is reachable by pinging it.
+///
+/// Used on startup since the API may come up before the cluster (e.g. docker compose ordering).
+/// Each failed attempt is logged and followed by a fixed delay.
+///
+/// # Parameters
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `retries` - Number of retries after the first failed attempt
+/// * `delay` - Delay between two attempts
+///
+/// # Returns
+/// * `Ok(())` - Cluster answered the ping successfully
+/// * `Err(ServerError)` - Cluster still unreachable after all retries
+///
+/// # Examples
+/// ```rust
+/// wait_for_elastic(&client, 10, Duration::from_secs(5)).await?;
+/// ```
+pub async fn wait_for_elastic(
+    client: &Elasticsearch,
+    retries: u32,
+    delay: Duration,
+) -> Result<(), ServerError> {
+    let mut attempt: u32 = 0;
+
+    loop {
+        attempt += 1;
+        let last_error = match client.ping().send().await {
+            Ok(response) if response.status_code().is_success() => {
+                log::info!("Elastic search reachable after {} attempt(s)", attempt);
+                return Ok(());
+            }
+            Ok(response) => format!("Ping returned status {}", response.status_code()),
+            Err(e) => e.to_string(),
+        };
+
+        if attempt > retries {
+            return Err(ServerError {
+                code: StatusCode::GATEWAY_TIMEOUT,
+                message: format!("Elastic search not reachable after {} attempt(s)!", attempt),
+                additional_information: last_error,
+            });
+        }
+
+        log::warn!(
+            "Elastic search not reachable (attempt {}/{}): {}. Retrying in {}s",
+            attempt,
+            retries + 1,
+            last_error,
+            delay.as_secs()
+        );
+        actix_web::rt::time::sleep(delay).await;
+    }
+}
+
 /// Creates the index used for the common log gen logs in elastic search based on the cluster on the client passed
 ///
+/// An existing index (also one created concurrently by another api instance) is no error,
+/// a creation rejected by elastic search (e.g. invalid mapping) fails with 500.
+///
 /// # Examples:
 /// ```
 ///     let client: Elasticsearch = create_client()?;
@@ -81,17 +534,6 @@ pub async fn create_logs_index(
     connector: &Elasticsearch,
     mapping: Value,
 ) -> Result<String, ServerError> {
-    // Get index settings from environment variables with defaults
-    let replicas: u32 = env::var("ELASTIC_INDEX_REPLICAS")
-        .unwrap_or_else(|_| "1".to_string())
-        .parse()
-        .unwrap_or(1);
-
-    let shards: u32 = env::var("ELASTIC_INDEX_SHARDS")
-        .unwrap_or_else(|_| "1".to_string())
-        .parse()
-        .unwrap_or(1);
-
     // Check if index exists
     let exists = connector
         .indices()
@@ -109,14 +551,11 @@ pub async fn create_logs_index(
     }
 
     //If not create one with a mapping matching the log
-    connector
+    let response = connector
         .indices()
         .create(IndicesCreateParts::Index(index_name))
         .body(json!({
-                "settings": {
-                    "number_of_replicas": replicas,
-                    "number_of_shards": shards
-                },
+                "settings": index_settings(),
                 "mappings": mapping
         }))
         .send()
@@ -127,125 +566,658 @@ pub async fn create_logs_index(
             additional_information: e.to_string(),
         })?;
 
+    if !response.status_code().is_success() {
+        let body = response.text().await.unwrap_or_default();
+        // Another api instance created it between the existence check and the creation
+        if body.contains("resource_already_exists_exception") {
+            return Ok(format!("Index '{}' already exists", index_name));
+        }
+        return Err(ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Index creation failed!"),
+            additional_information: body,
+        });
+    }
+
     Ok(format!("Index '{}' created successfully", index_name))
 }
 
-/// Persists a document in Elasticsearch for any log type that implements the required traits.
-///
-/// This function is generic over log types and handles the serialization and indexing
-/// process. It converts the log entry to a JSON document and sends it to the specified
-/// Elasticsearch index.
-///
-/// # Parameters
-/// * `index_name` - The name of the Elasticsearch index to store the document in
-/// * `client` - Reference to the configured Elasticsearch client
-/// * `log_entry` - The log entry to persist
+/// Creates or updates an index template matching the daily indices `<index_name>-*`.
 ///
-/// # Returns
-/// * `Ok(String)` - Success message with the inserted log entry in JSON format
-/// * `Err(ServerError)` - Error if serialization, network communication, or indexing fails
+/// Used instead of `create_logs_index` when time based indices are enabled, since the daily
+/// indices are created on the fly by elastic search and would otherwise get a dynamic mapping.
 ///
-/// # Examples
-/// ```rust
-/// let client = create_client()?;
-/// let log = LogEntry::new(/* ... */);
-/// let result = send_document("sensor_logs", &client, &log).await?;
-/// println!("{}", result); // "Log entry inserted: {...}"
+/// # Examples:
 /// ```
-pub async fn send_document<T>(
+/// create_index_template(&index_name, &client, create_log_mapping()).await?;
+/// ```
+pub async fn create_index_template(
     index_name: &str,
-    client: &Elasticsearch,
-    log_entry: &T,
-) -> Result<String, ServerError>
-where
-    T: ElasticLogDocument + Serialize,
-{
-    let json_value = log_entry.to_document_json().map_err(|e| ServerError {
-        code: StatusCode::INTERNAL_SERVER_ERROR,
-        message: String::from("Error while serializing log entry to JSON"),
-        additional_information: e.to_string(),
-    })?;
-
-    let response = client
-        .index(IndexParts::Index(index_name))
-        .body(json_value)
+    connector: &Elasticsearch,
+    mapping: Value,
+) -> Result<String, ServerError> {
+    let response = connector
+        .indices()
+        .put_index_template(IndicesPutIndexTemplateParts::Name(index_name))
+        .body(json!({
+            "index_patterns": [format!("{}-*", index_name)],
+            "template": {
+                "settings": index_settings(),
+                "mappings": mapping
+            }
+        }))
         .send()
         .await
         .map_err(|e| ServerError {
             code: StatusCode::GATEWAY_TIMEOUT,
-            message: String::from("Index creation failed!"),
+            message: String::from("Index template creation failed!"),
             additional_information: e.to_string(),
         })?;
 
     response.error_for_status_code().map_err(|e| ServerError {
         code: StatusCode::INTERNAL_SERVER_ERROR,
-        message: String::from("Index creation failed!"),
+        message: String::from("Index template creation failed!"),
         additional_information: e.to_string(),
     })?;
 
-    Ok(format!(
-        "Log entry inserted: {}",
-        serde_json::to_string_pretty(log_entry).map_err(|e| ServerError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: String::from("Error while parsing log entry into json!"),
-            additional_information: e.to_string(),
-        })?
-    ))
+    Ok(format!("Index template '{}-*' created successfully", index_name))
 }
 
-/// Retrieves information about all nodes in the Elasticsearch cluster.
+/// Returns the index settings read from environment variables with defaults.
+fn index_settings() -> Value {
+    let replicas: u32 = env::var("ELASTIC_INDEX_REPLICAS")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse()
+        .unwrap_or(1);
+
+    let shards: u32 = env::var("ELASTIC_INDEX_SHARDS")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse()
+        .unwrap_or(1);
+
+    let mut settings = json!({
+        "number_of_replicas": replicas,
+        "number_of_shards": shards
+    });
+    // New indices (and daily indices created from the template) pick up the retention policy
+    if let Some(ilm) = IlmConfig::from_env() {
+        settings["index.lifecycle.name"] = json!(ilm.policy_name);
+    }
+    settings
+}
+
+/// Retention of the log indices via an index lifecycle management (ILM) policy, loaded once during startup.
 ///
-/// This function queries the Elasticsearch cluster for detailed information about
-/// all active nodes, including their roles, versions, and operational status.
-/// The response contains comprehensive cluster topology information.
+/// # Environment Variables
+/// * `ILM_ENABLED` - Create the policy and attach it to the log indices (default: false)
+/// * `ILM_POLICY_NAME` - Name of the policy in elastic search (default: log_retention)
+/// * `ILM_WARM_AFTER_DAYS` - Age after which indices move to the warm phase (default: 7)
+/// * `ILM_DELETE_AFTER_DAYS` - Age after which indices are deleted (default: 30)
+#[derive(Debug, Clone)]
+pub struct IlmConfig {
+    pub policy_name: String,
+    pub warm_after_days: u32,
+    pub delete_after_days: u32,
+}
+
+impl IlmConfig {
+    /// Loads the policy settings, `None` unless `ILM_ENABLED` is set to true.
+    pub fn from_env() -> Option<Self> {
+        let enabled: bool = env::var("ILM_ENABLED")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+
+        Some(Self {
+            policy_name: env::var("ILM_POLICY_NAME").unwrap_or_else(|_| "log_retention".to_string()),
+            warm_after_days: env::var("ILM_WARM_AFTER_DAYS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .unwrap_or(7),
+            delete_after_days: env::var("ILM_DELETE_AFTER_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+        })
+    }
+
+    /// Returns the body of the ILM policy with hot, warm and delete phase.
+    ///
+    /// The warm phase is left out if it would start after the deletion, since elastic search
+    /// rejects phases with decreasing ages.
+    ///
+    /// # Examples
+    /// ```rust
+    /// // ILM_WARM_AFTER_DAYS=7, ILM_DELETE_AFTER_DAYS=30
+    /// // => {"policy": {"phases": {"hot": {"min_age": "0ms", ...}, "warm": {"min_age": "7d", ...}, "delete": {"min_age": "30d", ...}}}}
+    /// let body = IlmConfig::from_env().unwrap().policy_body();
+    /// ```
+    pub fn policy_body(&self) -> Value {
+        let mut phases = json!({
+            "hot": {
+                "min_age": "0ms",
+                "actions": { "set_priority": { "priority": 100 } }
+            },
+            "delete": {
+                "min_age": format!("{}d", self.delete_after_days),
+                "actions": { "delete": {} }
+            }
+        });
+
+        if self.warm_after_days < self.delete_after_days {
+            phases["warm"] = json!({
+                "min_age": format!("{}d", self.warm_after_days),
+                "actions": { "set_priority": { "priority": 50 } }
+            });
+        } else {
+            log::warn!(
+                "ILM_WARM_AFTER_DAYS ({}) is not below ILM_DELETE_AFTER_DAYS ({}), skipping the warm phase",
+                self.warm_after_days,
+                self.delete_after_days
+            );
+        }
+
+        json!({ "policy": { "phases": phases } })
+    }
+}
+
+/// Creates or updates the ILM policy in elastic search.
+///
+/// Has to run before the log indices are created, since their settings reference the policy by name.
 ///
 /// # Parameters
 /// * `client` - Reference to the configured Elasticsearch client
-///
-/// # Returns
-/// * `Ok(String)` - JSON string containing detailed node information for all cluster nodes
-/// * `Err(ServerError)` - Error if the request fails or response parsing fails
+/// * `name` - Name of the policy
+/// * `config` - Phase ages of the policy
 ///
 /// # Examples
 /// ```rust
-/// let client = create_client()?;
-/// let nodes_info = get_nodes(&client).await?;
-/// // Returns detailed JSON with node IDs, names, roles, versions, etc.
+/// if let Some(ilm) = IlmConfig::from_env() {
+///     ensure_ilm_policy(&client, &ilm.policy_name, &ilm).await?;
+/// }
 /// ```
-pub async fn get_nodes(client: &Elasticsearch) -> Result<String, ServerError> {
-    let result = client
-        .nodes()
-        .info(elasticsearch::nodes::NodesInfoParts::None)
+pub async fn ensure_ilm_policy(
+    client: &Elasticsearch,
+    name: &str,
+    config: &IlmConfig,
+) -> Result<String, ServerError> {
+    let response = client
+        .ilm()
+        .put_lifecycle(IlmPutLifecycleParts::Policy(name))
+        .body(config.policy_body())
         .send()
         .await
         .map_err(|e| ServerError {
             code: StatusCode::GATEWAY_TIMEOUT,
-            message: String::from("Fetching Node Information failed!"),
-            additional_information: e.to_string(),
-        })?
-        .text()
-        .await
-        .map_err(|e| ServerError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: String::from("Error while parsing node information!"),
+            message: String::from("ILM policy creation failed!"),
             additional_information: e.to_string(),
         })?;
 
-    Ok(result)
+    response.error_for_status_code().map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("ILM policy creation failed!"),
+        additional_information: e.to_string(),
+    })?;
+
+    Ok(format!("ILM policy '{}' created successfully", name))
 }
 
-/// Creates the Elasticsearch mapping schema for sensor log entries.
+/// Attaches an ILM policy to already existing indices.
 ///
-/// This function defines the field mappings and data types for sensor logs in Elasticsearch.
+/// Indices created after startup get the policy through their settings / the index template,
+/// this covers indices that existed before ILM was enabled.
 ///
-/// # Mapping Structure
-/// * `timestamp` - Date field with RFC3339/ISO-8601 format support
-/// * `level` - Keyword field for log levels (INFO, ERROR, WARN, etc.)
-/// * `temperature` - Float field for temperature sensor readings
+/// # Parameters
+/// * `index_pattern` - Index name or pattern like `<index_name>-*` for daily indices
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `policy_name` - Name of the policy created by [`ensure_ilm_policy`]
+pub async fn attach_ilm_policy(
+    index_pattern: &str,
+    client: &Elasticsearch,
+    policy_name: &str,
+) -> Result<String, ServerError> {
+    let response = client
+        .indices()
+        .put_settings(IndicesPutSettingsParts::Index(&[index_pattern]))
+        .allow_no_indices(true)
+        .body(json!({ "index.lifecycle.name": policy_name }))
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Attaching ILM policy failed!"),
+            additional_information: e.to_string(),
+        })?;
+
+    response.error_for_status_code().map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Attaching ILM policy failed!"),
+        additional_information: e.to_string(),
+    })?;
+
+    Ok(format!("ILM policy '{}' attached to '{}'", policy_name, index_pattern))
+}
+
+/// Persists a document in Elasticsearch for any log type that implements the required traits.
+///
+/// This function is generic over log types and handles the serialization and indexing
+/// process. It converts the log entry to a JSON document and sends it to the specified
+/// Elasticsearch index.
+///
+/// The document is created under its `event_id` (`op_type=create`) => a retry of a request that
+/// reached elastic search although no response arrived can't write a duplicate. A conflict on a
+/// retry means the document already exists and counts as success.
+///
+/// # Parameters
+/// * `index_name` - The name of the Elasticsearch index to store the document in
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `log_entry` - The log entry to persist
+/// * `config` - Indexing settings, e.g. whether the document goes into a daily index or through an ingest pipeline
+///
+/// # Returns
+/// * `Ok(String)` - `indexed` or, with `VERBOSE_INDEX_RESPONSE`, a success message with the inserted log entry in JSON format
+/// * `Err(ServerError)` - 422 if the timestamp is outside the accepted window, otherwise error if serialization, network communication, or indexing fails
+///   after `INDEX_MAX_ATTEMPTS` attempts (timeouts, connection errors and 5xx are retried with exponential backoff)
+///
+/// # Examples
+/// ```rust
+/// let client = create_client()?;
+/// let log = LogEntry::new(/* ... */);
+/// let result = send_document("sensor_logs", &client, &log, &IndexingConfig::from_env()).await?;
+/// println!("{}", result); // "indexed" or "Log entry inserted: {...}"
+/// ```
+pub async fn send_document<T>(
+    index_name: &str,
+    client: &Elasticsearch,
+    log_entry: &T,
+    config: &IndexingConfig,
+) -> Result<String, ServerError>
+where
+    T: ElasticLogDocument + Serialize,
+{
+    let (index_name, json_value) = prepare_document(index_name, log_entry, config)?;
+    // Fixed before the first attempt => every retry targets the same document
+    let document_id = json_value["event_id"].as_str().unwrap_or_default().to_string();
+
+    let mut attempt = 1;
+    let response = loop {
+        let mut request = client
+            .index(IndexParts::IndexId(&index_name, &document_id))
+            .op_type(OpType::Create)
+            .body(json_value.clone());
+        // ES side processing => an unknown pipeline makes elastic search reject the document
+        if let Some(pipeline) = &config.pipeline {
+            request = request.pipeline(pipeline);
+        }
+
+        let result = request.send().await;
+        let retryable = match &result {
+            Ok(response) => response.status_code().is_server_error(),
+            // No response at all => timeout or connection error
+            Err(_) => true,
+        };
+        // 4xx won't succeed on a retry, the final 5xx is reported below like before
+        if !retryable || attempt >= config.index_max_attempts {
+            break result.map_err(|e| ServerError {
+                code: StatusCode::GATEWAY_TIMEOUT,
+                message: String::from("Index creation failed!"),
+                additional_information: e.to_string(),
+            })?;
+        }
+
+        let delay = retry_delay(config.index_retry_base_delay, attempt);
+        log::warn!(
+            "Indexing into '{}' failed (attempt {}/{}), retrying in {}ms",
+            index_name,
+            attempt,
+            config.index_max_attempts,
+            delay.as_millis()
+        );
+        actix_web::rt::time::sleep(delay).await;
+        attempt += 1;
+    };
+
+    // An earlier attempt was indexed, only its response got lost
+    let already_indexed = attempt > 1 && response.status_code().as_u16() == StatusCode::CONFLICT.as_u16();
+    if !already_indexed {
+        response.error_for_status_code().map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Index creation failed!"),
+            additional_information: e.to_string(),
+        })?;
+    }
+
+    // Pretty printing every entry is costly on the hot ingest path => only done if requested
+    if !config.verbose_response {
+        return Ok(String::from("indexed"));
+    }
+
+    Ok(format!(
+        "Log entry inserted: {}",
+        serde_json::to_string_pretty(log_entry).map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Error while parsing log entry into json!"),
+            additional_information: e.to_string(),
+        })?
+    ))
+}
+
+/// Delay before the next attempt of [`send_document`]: exponential backoff with jitter.
+///
+/// The backoff doubles with every attempt, the delay is drawn from its upper half => concurrent
+/// requests failing at the same moment don't hit a recovering cluster all at once again.
+///
+/// # Examples
+/// ```rust
+/// // base 100ms => 50-100ms, 100-200ms, 200-400ms, ...
+/// let delay = retry_delay(Duration::from_millis(100), attempt);
+/// ```
+fn retry_delay(base: Duration, attempt: u32) -> Duration {
+    let backoff = base.saturating_mul(1 << (attempt - 1).min(10));
+    backoff / 2 + backoff.mul_f64(rand::random::<f64>() / 2.0)
+}
+
+/// Checks a log entry and builds the document persisted for it.
+///
+/// # Parameters
+/// * `index_name` - Base name of the index, resolved to the daily index with time based indices
+/// * `log_entry` - The log entry to persist
+/// * `config` - Indexing settings
+///
+/// # Returns
+/// * `Ok((String, Value))` - Target index and document including `schema_version` / `event_id` / `ingested_at`
+/// * `Err(ServerError)` - 422 if the timestamp is outside the accepted window, 500 if serialization fails
+fn prepare_document<T>(
+    index_name: &str,
+    log_entry: &T,
+    config: &IndexingConfig,
+) -> Result<(String, Value), ServerError>
+where
+    T: ElasticLogDocument,
+{
+    config.check_timestamp(log_entry.timestamp())?;
+
+    let index_name = if config.time_based_indices {
+        resolve_index_name(index_name, log_entry.timestamp())
+    } else {
+        index_name.to_string()
+    };
+
+    let mut json_value = log_entry.to_document_json().map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Error while serializing log entry to JSON"),
+        additional_information: e.to_string(),
+    })?;
+    // Migration anchor => documents of different schema versions can be told apart
+    json_value["schema_version"] = json!(config.schema_version);
+    // Unique per document and indexed as keyword => stable tiebreaker for sorting (see `sort_tiebreaker`)
+    json_value["event_id"] = json!(Uuid::new_v4().to_string());
+    // Set by the api only (never deserialized from clients) => the delay to the event timestamp shows the lag of the pipeline
+    json_value["ingested_at"] = json!(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true));
+
+    Ok((index_name, json_value))
+}
+
+/// Reads the `ingested_at` stamped by [`prepare_document`] from a stored document.
+///
+/// Log entries skip the field on deserialization, so clients can't set it => query results take it from here.
+/// `None` for documents indexed before it was stamped.
+fn stored_ingested_at(source: &serde_json::Map<String, Value>) -> Option<DateTime<Utc>> {
+    source
+        .get("ingested_at")
+        .and_then(Value::as_str)
+        .and_then(|ingested_at| DateTime::parse_from_rfc3339(ingested_at).ok())
+        .map(|ingested_at| ingested_at.with_timezone(&Utc))
+}
+
+/// Failure of a single log entry within a bulk request.
+#[derive(Debug, Serialize)]
+pub struct BulkItemError {
+    /// Position of the entry within the request body
+    pub position: usize,
+    pub error: String,
+}
+
+/// Outcome of a bulk request, entries are indexed independently of each other.
+#[derive(Debug, Default, Serialize)]
+pub struct BulkResult {
+    pub indexed: usize,
+    pub failed: usize,
+    pub errors: Vec<BulkItemError>,
+}
+
+impl BulkResult {
+    /// Records a failed entry.
+    pub fn fail(&mut self, position: usize, error: String) {
+        self.failed += 1;
+        self.errors.push(BulkItemError { position, error });
+    }
+
+    /// Adds the outcome of another part of the same request, errors stay ordered by position.
+    pub fn merge(&mut self, other: BulkResult) {
+        self.indexed += other.indexed;
+        self.failed += other.failed;
+        self.errors.extend(other.errors);
+        self.errors.sort_by_key(|error| error.position);
+    }
+
+    /// Response status of the request, 207 only if entries failed and the mode asks for it.
+    pub fn status(&self, mode: BulkErrorMode) -> StatusCode {
+        match mode {
+            BulkErrorMode::MultiStatus if self.failed > 0 => StatusCode::MULTI_STATUS,
+            _ => StatusCode::OK,
+        }
+    }
+}
+
+/// Response status of bulk requests in which some entries failed.
+///
+/// The body lists the failed positions either way => clients can resend only those entries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BulkErrorMode {
+    /// 200, clients have to check `failed` / `errors` of the body
+    Ok,
+    /// 207 Multi-Status, partial failures are visible from the status alone
+    MultiStatus,
+}
+
+/// Persists multiple log entries with a single request to the Elasticsearch `_bulk` API.
+///
+/// Every entry is prepared like in [`send_document`]. Entries rejected during preparation
+/// (e.g. timestamp outside the accepted window) or by elastic search are reported per position,
+/// the remaining entries are indexed anyway.
+///
+/// # Parameters
+/// * `index_name` - The name of the Elasticsearch index to store the documents in
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `log_entries` - Position within the request body and log entry
+/// * `config` - Indexing settings, e.g. whether the documents go into daily indices or through an ingest pipeline
+///
+/// # Returns
+/// * `Ok(BulkResult)` - Indexed / failed counts with the errors of the failed positions
+/// * `Err(ServerError)` - Error if the bulk request itself fails (network, rejected request)
+///
+/// # Examples
+/// ```rust
+/// let result = send_documents_bulk("sensor_logs", &client, &[(0, log)], &IndexingConfig::from_env()).await?;
+/// println!("{} indexed, {} failed", result.indexed, result.failed);
+/// ```
+pub async fn send_documents_bulk<T>(
+    index_name: &str,
+    client: &Elasticsearch,
+    log_entries: &[(usize, T)],
+    config: &IndexingConfig,
+) -> Result<BulkResult, ServerError>
+where
+    T: ElasticLogDocument,
+{
+    let mut result = BulkResult::default();
+    let mut positions = Vec::new();
+    let mut operations: Vec<BulkOperation<Value>> = Vec::new();
+
+    for (position, log_entry) in log_entries {
+        match prepare_document(index_name, log_entry, config) {
+            Ok((index_name, document)) => {
+                positions.push(*position);
+                operations.push(BulkOperation::index(document).index(index_name).into());
+            }
+            Err(e) => result.fail(*position, e.message),
+        }
+    }
+
+    if operations.is_empty() {
+        return Ok(result);
+    }
+
+    let mut request = client.bulk(BulkParts::None).body(operations);
+    if let Some(pipeline) = &config.pipeline {
+        request = request.pipeline(pipeline);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Bulk indexing failed!"),
+            additional_information: e.to_string(),
+        })?
+        .error_for_status_code()
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Bulk indexing failed!"),
+            additional_information: e.to_string(),
+        })?;
+
+    let body: Value = response.json().await.map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Error while parsing bulk response"),
+        additional_information: e.to_string(),
+    })?;
+
+    // Items are returned in the order of the operations
+    let items = body["items"].as_array().cloned().unwrap_or_default();
+    for (position, item) in positions.into_iter().zip(items) {
+        let item = &item["index"];
+        if item["status"].as_u64().is_some_and(|status| (200..300).contains(&status)) {
+            result.indexed += 1;
+        } else {
+            let error = item["error"]["reason"]
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| item["error"].to_string());
+            result.fail(position, error);
+        }
+    }
+    result.errors.sort_by_key(|error| error.position);
+
+    Ok(result)
+}
+
+/// Retrieves information about all nodes in the Elasticsearch cluster.
+///
+/// This function queries the Elasticsearch cluster for detailed information about
+/// all active nodes, including their roles, versions, and operational status.
+/// The response contains comprehensive cluster topology information.
+///
+/// # Parameters
+/// * `client` - Reference to the configured Elasticsearch client
+///
+/// # Returns
+/// * `Ok(String)` - JSON string containing detailed node information for all cluster nodes
+/// * `Err(ServerError)` - Error if the request fails or response parsing fails
+///
+/// # Examples
+/// ```rust
+/// let client = create_client()?;
+/// let nodes_info = get_nodes(&client).await?;
+/// // Returns detailed JSON with node IDs, names, roles, versions, etc.
+/// ```
+pub async fn get_nodes(client: &Elasticsearch) -> Result<String, ServerError> {
+    let result = client
+        .nodes()
+        .info(elasticsearch::nodes::NodesInfoParts::None)
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Fetching Node Information failed!"),
+            additional_information: e.to_string(),
+        })?
+        .text()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Error while parsing node information!"),
+            additional_information: e.to_string(),
+        })?;
+
+    Ok(result)
+}
+
+/// Retrieves the health status of the Elasticsearch cluster.
+///
+/// # Parameters
+/// * `client` - Reference to the configured Elasticsearch client
+///
+/// # Returns
+/// * `Ok(String)` - Cluster status `green`, `yellow` or `red`
+/// * `Err(ServerError)` - 503 if the cluster is unreachable, 500 if the response can't be parsed
+///
+/// # Examples
+/// ```rust
+/// let status = cluster_health(&client).await?;
+/// // "green"
+/// ```
+pub async fn cluster_health(client: &Elasticsearch) -> Result<String, ServerError> {
+    let response = client
+        .cluster()
+        .health(ClusterHealthParts::None)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status_code())
+        .map_err(|e| ServerError {
+            code: StatusCode::SERVICE_UNAVAILABLE,
+            message: String::from("Elastic search cluster is unreachable!"),
+            additional_information: e.to_string(),
+        })?;
+
+    let body = response.json::<Value>().await.map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Error while parsing cluster health!"),
+        additional_information: e.to_string(),
+    })?;
+
+    body["status"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Cluster health response has no status"),
+            additional_information: body.to_string(),
+        })
+}
+
+/// Creates the Elasticsearch mapping schema for sensor log entries.
+///
+/// This function defines the field mappings and data types for sensor logs in Elasticsearch.
+///
+/// # Mapping Structure
+/// * `timestamp` - Date field with RFC3339/ISO-8601 format support
+/// * `schema_version` - Integer field with the document schema version stamped on ingest
+/// * `ingested_at` - Date field with the time the api indexed the document
+/// * `event_id` - Keyword field with the unique id stamped on ingest, its `keyword` sub-field is the sort tiebreaker
+/// * `level` - Keyword field for log levels (INFO, ERROR, WARN, etc.)
+/// * `temperature` - Float field for temperature sensor readings
 /// * `humidity` - Float field for humidity sensor readings  
 /// * `msg.device` - Keyword field for device identification
 /// * `msg.msg` - Text field with standard analyzer for message content
 /// * `msg.exceeded_values` - Boolean field indicating threshold violations
+/// * `msg.exceeded_fields` - Keyword field naming the measurement of each `exceeded_values` flag
+/// * `source` - Optional keyword field marking the origin, e.g. `synthetic`
 ///
 /// # Returns
 /// * `Value` - JSON object containing the complete mapping definition
@@ -263,14 +1235,23 @@ pub fn create_log_mapping() -> Value {
                 // RFC3339/ISO-8601 format => Parseable by chrono
                 "format": "strict_date_optional_time||epoch_millis"
             },
+            "schema_version": { "type": "integer" },
+            "event_id": event_id_mapping(),
+            "ingested_at": {
+                "type": "date",
+                "format": "strict_date_optional_time||epoch_millis"
+            },
             "level": { "type": "keyword" },
             "temperature": { "type": "float" },
             "humidity": { "type": "float" },
+            // Optional origin marker, e.g. "synthetic" for generator data
+            "source": { "type": "keyword" },
             "msg": {
                 "properties": {
                     "device": { "type": "keyword" },
                     "msg": { "type": "text", "analyzer": "standard" },
-                    "exceeded_values": { "type": "boolean" }
+                    "exceeded_values": { "type": "boolean" },
+                    "exceeded_fields": { "type": "keyword" }
                 }
             }
         }
@@ -283,8 +1264,13 @@ pub fn create_log_mapping() -> Value {
 ///
 /// # Mapping Structure
 /// * `timestamp` - Date field with RFC3339/ISO-8601 format support for temporal queries
+/// * `schema_version` - Integer field with the document schema version stamped on ingest
+/// * `ingested_at` - Date field with the time the api indexed the document
+/// * `event_id` - Keyword field with the unique id stamped on ingest, its `keyword` sub-field is the sort tiebreaker
 /// * `container_name` - Keyword field for exact container name matching and filtering
 /// * `log_message` - Text field with standard analyzer for full-text search capabilities
+/// * `parsed` - Optional object with the content of JSON log messages (flattened, all leaves are keywords)
+/// * `raw` - Optional original syslog line, kept in the source only (not indexed)
 ///
 /// # Returns
 /// * `Value` - JSON object containing the complete mapping definition for container logs
@@ -302,8 +1288,59 @@ pub fn create_container_log_mapping() -> Value {
                 // RFC3339/ISO-8601 format => Parseable by chrono
                 "format": "strict_date_optional_time||epoch_millis"
             },
+            "schema_version": { "type": "integer" },
+            "event_id": event_id_mapping(),
+            "ingested_at": {
+                "type": "date",
+                "format": "strict_date_optional_time||epoch_millis"
+            },
             "container_name": { "type": "keyword" },
             "log_message": { "type": "text", "analyzer": "standard"  },
+            // Optional JSON content of arbitrary containers => a single flattened field instead of
+            // dynamic mapping, so varying keys can't explode the mapping or conflict in their types
+            "parsed": { "type": "flattened" },
+            // Only kept for forensic comparison => not searchable, saves index storage
+            "raw": { "type": "text", "index": false },
+        }
+    })
+}
+
+/// Creates the Elasticsearch mapping schema for the operational self logs of the api.
+///
+/// # Mapping Structure
+/// * `timestamp` - Date field with the end of the summarized interval
+/// * `schema_version` - Integer field with the document schema version stamped on ingest
+/// * `ingested_at` - Date field with the time the api indexed the document
+/// * `event_id` - Keyword field with the unique id stamped on ingest, its `keyword` sub-field is the sort tiebreaker
+/// * `instance_id` - Keyword field identifying the api instance
+/// * `interval_secs` - Integer field with the length of the summarized interval
+/// * `requests` / `errors` - Long fields with the request counts of the interval
+/// * `error_rate` - Float field with the share of failed requests
+/// * `message` - Text field with a readable summary
+///
+/// # Examples
+/// ```rust
+/// create_logs_index("forwarder_ops", &client, create_ops_mapping()).await?;
+/// ```
+pub fn create_ops_mapping() -> Value {
+    json!({
+        "properties": {
+            "timestamp": {
+                "type": "date",
+                "format": "strict_date_optional_time||epoch_millis"
+            },
+            "schema_version": { "type": "integer" },
+            "event_id": event_id_mapping(),
+            "ingested_at": {
+                "type": "date",
+                "format": "strict_date_optional_time||epoch_millis"
+            },
+            "instance_id": { "type": "keyword" },
+            "interval_secs": { "type": "integer" },
+            "requests": { "type": "long" },
+            "errors": { "type": "long" },
+            "error_rate": { "type": "float" },
+            "message": { "type": "text", "analyzer": "standard" },
         }
     })
 }
@@ -312,7 +1349,7 @@ pub fn create_container_log_mapping() -> Value {
 ///
 /// This function performs structured queries on container logs with support for filtering
 /// by container name and time range. Results are sorted by timestamp in descending order
-/// (newest first) and support pagination.
+/// (newest first) unless `sort_field`/`sort_order` are given and support pagination.
 ///
 /// # Parameters
 /// * `index_name` - The name of the Elasticsearch index containing container logs
@@ -321,13 +1358,16 @@ pub fn create_container_log_mapping() -> Value {
 ///
 /// # Query Filters
 /// * `container_name` - Filter logs by specific container name (exact match)
+/// * `case_insensitive` - Match `container_name` regardless of casing (default: false)
 /// * `from`/`to` - Time range filter using DateTime<Utc> boundaries
+/// * `sort_field` - One of [`CONTAINER_SORT_FIELDS`] (default: `timestamp`)
+/// * `sort_order` - `asc` or `desc` (default: `desc`)
 /// * `limit` - Maximum number of results to return (default: 100)
 /// * `offset` - Number of results to skip for pagination (default: 0)
 ///
 /// # Returns
 /// * `Ok(Vec<ContainerLogEntry>)` - List of matching container log entries
-/// * `Err(ServerError)` - Error if query execution or response parsing fails
+/// * `Err(ServerError)` - Bad request for an invalid sort, error if query execution or response parsing fails
 ///
 /// # Examples
 /// ```rust
@@ -335,6 +1375,8 @@ pub fn create_container_log_mapping() -> Value {
 ///     container_name: Some("web-server".to_string()),
 ///     from: Some(yesterday),
 ///     to: Some(now),
+///     sort_field: Some("timestamp".to_string()),
+///     sort_order: Some("asc".to_string()),
 ///     limit: Some(50),
 ///     offset: Some(0),
 /// };
@@ -345,12 +1387,16 @@ pub async fn query_container_logs(
     client: &Elasticsearch,
     query: &ContainerLogQuery,
 ) -> Result<Vec<ContainerLogEntry>, ServerError> {
+    let sort = parse_sort(
+        None,
+        query.sort_field.as_deref(),
+        query.sort_order.as_deref(),
+        &CONTAINER_SORT_FIELDS,
+    )?;
     let mut must_clauses = Vec::new();
     
     if let Some(container_name) = &query.container_name {
-        must_clauses.push(json!({
-            "term": { "container_name": container_name }
-        }));
+        must_clauses.push(keyword_term("container_name", container_name, query.case_insensitive));
     }
     
     if query.from.is_some() || query.to.is_some() {
@@ -367,14 +1413,14 @@ pub async fn query_container_logs(
     let search_body = if must_clauses.is_empty() {
         json!({
             "query": { "match_all": {} },
-            "sort": [{ "timestamp": { "order": "desc" } }],
+            "sort": sort,
             "size": query.limit.unwrap_or(100),
             "from": query.offset.unwrap_or(0)
         })
     } else {
         json!({
             "query": { "bool": { "must": must_clauses } },
-            "sort": [{ "timestamp": { "order": "desc" } }],
+            "sort": sort,
             "size": query.limit.unwrap_or(100),
             "from": query.offset.unwrap_or(0)
         })
@@ -411,12 +1457,13 @@ pub async fn query_container_logs(
     let mut logs = Vec::new();
     for hit in hits {
         if let Some(source) = hit["_source"].as_object() {
-            let log_entry: ContainerLogEntry = serde_json::from_value(json!(source))
+            let mut log_entry: ContainerLogEntry = serde_json::from_value(json!(source))
                 .map_err(|e| ServerError {
                     code: StatusCode::INTERNAL_SERVER_ERROR,
                     message: String::from("Failed to deserialize container log entry"),
                     additional_information: e.to_string(),
                 })?;
+            log_entry.ingested_at = stored_ingested_at(source);
             logs.push(log_entry);
         }
     }
@@ -427,8 +1474,8 @@ pub async fn query_container_logs(
 /// Performs full-text search on container logs using multi-field matching.
 ///
 /// This function executes fuzzy full-text search across container log fields with
-/// automatic relevance scoring. It searches both log message content and container
-/// names, providing flexible search capabilities with automatic typo tolerance.
+/// automatic relevance scoring. It searches log message content, container names and
+/// the values of parsed JSON messages, providing flexible search capabilities with automatic typo tolerance.
 ///
 /// # Parameters
 /// * `index_name` - The name of the Elasticsearch index containing container logs
@@ -457,12 +1504,13 @@ pub async fn search_container_logs(
         "query": {
             "multi_match": {
                 "query": search.query,
-                "fields": ["log_message", "container_name"],
+                // `parsed` matches whole values of JSON log messages (flattened => keywords)
+                "fields": ["log_message", "container_name", "parsed"],
                 "type": "best_fields",
                 "fuzziness": "AUTO"
             }
         },
-        "sort": [{ "timestamp": { "order": "desc" } }],
+        "sort": default_sort(),
         "size": search.limit.unwrap_or(100),
         "from": search.offset.unwrap_or(0)
     });
@@ -498,12 +1546,13 @@ pub async fn search_container_logs(
     let mut logs = Vec::new();
     for hit in hits {
         if let Some(source) = hit["_source"].as_object() {
-            let log_entry: ContainerLogEntry = serde_json::from_value(json!(source))
+            let mut log_entry: ContainerLogEntry = serde_json::from_value(json!(source))
                 .map_err(|e| ServerError {
                     code: StatusCode::INTERNAL_SERVER_ERROR,
                     message: String::from("Failed to deserialize container log entry"),
                     additional_information: e.to_string(),
                 })?;
+            log_entry.ingested_at = stored_ingested_at(source);
             logs.push(log_entry);
         }
     }
@@ -511,6 +1560,130 @@ pub async fn search_container_logs(
     Ok(logs)
 }
 
+/// Parses the sort parameters of a query into an Elasticsearch sort array.
+///
+/// `sort` is a comma-separated specification, each entry has the form `field:direction` where
+/// direction is `asc` or `desc` (default: `desc`). It takes precedence over the separate
+/// `sort_field` / `sort_order` parameters, of which a missing field defaults to `timestamp` and a
+/// missing order to `desc` => `sort_order=asc` alone returns the oldest logs first.
+/// Without any parameter the default `timestamp desc` sort is returned.
+/// A tiebreaker is always appended to keep the order stable.
+///
+/// # Parameters
+/// * `sort` - Optional sort specification, e.g. `level:desc,timestamp:desc`
+/// * `sort_field` - Optional single sort field
+/// * `sort_order` - Optional direction `asc` or `desc` of `sort_field`
+/// * `allowed_fields` - Allowlist of sortable field names
+///
+/// # Returns
+/// * `Ok(Vec<Value>)` - Elasticsearch sort array in the given key order
+/// * `Err(ServerError)` - Bad request if a field is not allowed or a direction is invalid
+///
+/// # Examples
+/// ```rust
+/// let sort = parse_sort(Some("level:desc,timestamp:asc"), None, None, &LOG_SORT_FIELDS)?;
+/// // [{ "level": { "order": "desc" } }, { "timestamp": { "order": "asc" } }, { "event_id.keyword": { "order": "asc", ... } }]
+/// let sort = parse_sort(None, Some("container_name"), Some("asc"), &CONTAINER_SORT_FIELDS)?;
+/// // [{ "container_name": { "order": "asc" } }, { "event_id.keyword": { "order": "asc", ... } }]
+/// ```
+pub fn parse_sort(
+    sort: Option<&str>,
+    sort_field: Option<&str>,
+    sort_order: Option<&str>,
+    allowed_fields: &[&str],
+) -> Result<Vec<Value>, ServerError> {
+    let mut sort_array = Vec::new();
+    match sort.map(str::trim) {
+        Some(sort) if !sort.is_empty() => {
+            for entry in sort.split(',').map(str::trim) {
+                let (field, direction) = entry.split_once(':').unwrap_or((entry, "desc"));
+                sort_array.push(sort_key(field, direction, allowed_fields)?);
+            }
+        }
+        _ if sort_field.is_none() && sort_order.is_none() => return Ok(default_sort()),
+        _ => sort_array.push(sort_key(
+            sort_field.unwrap_or("timestamp"),
+            sort_order.unwrap_or("desc"),
+            allowed_fields,
+        )?),
+    }
+
+    sort_array.push(sort_tiebreaker());
+    Ok(sort_array)
+}
+
+/// Validates a single sort key against the allowlist and builds its sort clause.
+fn sort_key(field: &str, direction: &str, allowed_fields: &[&str]) -> Result<Value, ServerError> {
+    let (field, direction) = (field.trim(), direction.trim().to_lowercase());
+
+    if !allowed_fields.contains(&field) {
+        return Err(ServerError {
+            code: StatusCode::BAD_REQUEST,
+            message: format!("Field '{}' is not sortable", field),
+            additional_information: format!("Allowed sort fields: {}", allowed_fields.join(", ")),
+        });
+    }
+
+    if direction != "asc" && direction != "desc" {
+        return Err(ServerError {
+            code: StatusCode::BAD_REQUEST,
+            message: format!("Invalid sort direction '{}' for field '{}'", direction, field),
+            additional_information: String::from("Sort direction must be 'asc' or 'desc'"),
+        });
+    }
+
+    let mut sort_key = json!({});
+    sort_key[field] = json!({ "order": direction });
+    Ok(sort_key)
+}
+
+/// Secondary sort key appended to every sort array.
+///
+/// Documents sharing the same sort values (e.g. identical timestamps) would otherwise be returned
+/// in nondeterministic order, which breaks offset / search_after based pagination.
+/// `event_id` is unique and stamped on ingest => unlike `_doc` the order is the same on every shard,
+/// replica and daily index. Sorting on `_id` isn't possible since it requires fielddata.
+///
+/// Sorts on the `keyword` sub-field: indices created before `event_id` was mapped got it through
+/// dynamic mapping as `text` (not sortable) with a `keyword` sub-field, [`event_id_mapping`] adds the
+/// same sub-field => one sort key for old and new indices. Documents indexed before `event_id`
+/// existed are sorted last, indices without it don't fail.
+fn sort_tiebreaker() -> Value {
+    json!({ "event_id.keyword": { "order": "asc", "missing": "_last", "unmapped_type": "keyword" } })
+}
+
+/// Mapping of `event_id` shared by all log indices.
+///
+/// Keyword with a `keyword` sub-field, matching the sub-field dynamic mapping creates on indices
+/// that existed before `event_id` => [`sort_tiebreaker`] works on both without reindexing.
+fn event_id_mapping() -> Value {
+    json!({ "type": "keyword", "fields": { "keyword": { "type": "keyword" } } })
+}
+
+/// Default sort array: newest first, tie broken by [`sort_tiebreaker`].
+pub fn default_sort() -> Vec<Value> {
+    vec![json!({ "timestamp": { "order": "desc" } }), sort_tiebreaker()]
+}
+
+/// Builds an exact match on a keyword field, optionally ignoring the casing.
+///
+/// Uses the `case_insensitive` flag of the term query instead of a lowercase normalizer in the
+/// mapping => works on existing indices without reindexing.
+///
+/// # Examples
+/// ```rust
+/// let clause = keyword_term("msg.device", "Arduino0", Some(true));
+/// // { "term": { "msg.device": { "value": "Arduino0", "case_insensitive": true } } }
+/// ```
+fn keyword_term(field: &str, value: &str, case_insensitive: Option<bool>) -> Value {
+    let mut clause = json!({ "term": {} });
+    clause["term"][field] = json!({
+        "value": value,
+        "case_insensitive": case_insensitive.unwrap_or(false)
+    });
+    clause
+}
+
 /// Queries sensor logs from Elasticsearch with comprehensive filtering capabilities.
 ///
 /// This function performs structured queries on sensor logs with support for filtering
@@ -525,12 +1698,18 @@ pub async fn search_container_logs(
 /// # Query Filters
 /// * `level` - Filter by log level (INFO, ERROR, WARN, etc.) - case insensitive, stored as uppercase
 /// * `device` - Filter logs by specific device identifier (exact match)
+/// * `case_insensitive` - Match `device` regardless of casing, e.g. `Arduino0` finds `arduino0` (default: false)
+/// * `source` - Filter by origin, e.g. `synthetic` for generator data; `real` matches logs without source
 /// * `from`/`to` - Time range filter using DateTime<Utc> boundaries
+/// * `temp_min`/`temp_max` - Inclusive temperature range, e.g. `temp_min=30`
+/// * `humidity_min`/`humidity_max` - Inclusive humidity range
+/// * `sort` - Comma-separated sort keys like `level:desc,timestamp:desc` (default: `timestamp:desc`)
+/// * `sort_field`/`sort_order` - Single sort key like `temperature` / `asc`, ignored if `sort` is given
 /// * `limit` - Maximum number of results to return (default: 100)
 /// * `offset` - Number of results to skip for pagination (default: 0)
 ///
 /// # Returns
-/// * `Ok(Vec<LogEntry>)` - List of matching sensor log entries sorted by timestamp (newest first)
+/// * `Ok((Vec<LogEntry>, u64))` - Matching sensor log entries of the requested page in sort order and the total number of matches
 /// * `Err(ServerError)` - Error if query execution or response parsing fails
 ///
 /// # Examples
@@ -538,18 +1717,70 @@ pub async fn search_container_logs(
 /// let query = LogQuery {
 ///     level: Some("error".to_string()),
 ///     device: Some("sensor-01".to_string()),
+///     source: Some("real".to_string()),
 ///     from: Some(yesterday),
 ///     to: Some(now),
+///     sort: Some("level:desc,timestamp:desc".to_string()),
 ///     limit: Some(100),
 ///     offset: Some(0),
 /// };
-/// let logs = query_logs("sensor_logs", &client, &query).await?;
+/// let (logs, total) = query_logs("sensor_logs", &client, &query).await?;
 /// ```
 pub async fn query_logs(
     index_name: &str,
     client: &Elasticsearch,
     query: &LogQuery,
-) -> Result<Vec<LogEntry>, ServerError> {
+) -> Result<(Vec<LogEntry>, u64), ServerError> {
+    let sort = parse_sort(
+        query.sort.as_deref(),
+        query.sort_field.as_deref(),
+        query.sort_order.as_deref(),
+        &LOG_SORT_FIELDS,
+    )?;
+    let search_body = json!({
+        "query": log_filter(query),
+        "sort": sort,
+        "size": query.limit.unwrap_or(100),
+        "from": query.offset.unwrap_or(0),
+        // Exact count instead of the default lower bound of 10000
+        "track_total_hits": true
+    });
+
+    let response = client
+        .search(SearchParts::Index(&[index_name]))
+        .body(search_body)
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Search request failed"),
+            additional_information: e.to_string(),
+        })?;
+        
+    let response_body: Value = response
+        .json()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Failed to parse search response"),
+            additional_information: e.to_string(),
+        })?;
+        
+    let logs = parse_log_hits(&response_body)?;
+
+    // Total of all matching documents, independent of limit / offset
+    let total = response_body["hits"]["total"]["value"]
+        .as_u64()
+        .unwrap_or(logs.len() as u64);
+
+    Ok((logs, total))
+}
+
+/// Builds the query clause of the `LogQuery` filters, shared by [`query_logs`] and [`query_logs_after`].
+///
+/// # Returns
+/// * `Value` - `match_all` without filters, otherwise a `bool` query with all filters as `must` clauses
+fn log_filter(query: &LogQuery) -> Value {
     let mut must_clauses = Vec::new();
     
     if let Some(level) = &query.level {
@@ -559,9 +1790,20 @@ pub async fn query_logs(
     }
     
     if let Some(device) = &query.device {
-        must_clauses.push(json!({
-            "term": { "msg.device": device }
-        }));
+        must_clauses.push(keyword_term("msg.device", device, query.case_insensitive));
+    }
+
+    if let Some(source) = &query.source {
+        // Real sensor logs carry no source field => "real" matches documents without it
+        if source.eq_ignore_ascii_case("real") {
+            must_clauses.push(json!({
+                "bool": { "must_not": { "exists": { "field": "source" } } }
+            }));
+        } else {
+            must_clauses.push(json!({
+                "term": { "source": source.to_lowercase() }
+            }));
+        }
     }
     
     if query.from.is_some() || query.to.is_some() {
@@ -574,25 +1816,181 @@ pub async fn query_logs(
         }
         must_clauses.push(range_query);
     }
-    
-    let search_body = if must_clauses.is_empty() {
-        json!({
-            "query": { "match_all": {} },
-            "sort": [{ "timestamp": { "order": "desc" } }],
-            "size": query.limit.unwrap_or(100),
-            "from": query.offset.unwrap_or(0)
-        })
+
+    for (field, min, max) in [
+        ("temperature", query.temp_min, query.temp_max),
+        ("humidity", query.humidity_min, query.humidity_max),
+    ] {
+        if min.is_some() || max.is_some() {
+            let mut range_query = json!({ "range": { field: {} } });
+            if let Some(min) = min {
+                range_query["range"][field]["gte"] = json!(min);
+            }
+            if let Some(max) = max {
+                range_query["range"][field]["lte"] = json!(max);
+            }
+            must_clauses.push(range_query);
+        }
+    }
+
+    if must_clauses.is_empty() {
+        json!({ "match_all": {} })
     } else {
-        json!({
-            "query": { "bool": { "must": must_clauses } },
-            "sort": [{ "timestamp": { "order": "desc" } }],
-            "size": query.limit.unwrap_or(100),
-            "from": query.offset.unwrap_or(0)
-        })
-    };
-    
+        json!({ "bool": { "must": must_clauses } })
+    }
+}
+
+/// Deserializes the sensor logs of a search response.
+///
+/// # Returns
+/// * `Ok(Vec<LogEntry>)` - Logs of all hits in response order
+/// * `Err(ServerError)` - 500 if the response has no hits array or a source isn't a valid log entry
+fn parse_log_hits(response_body: &Value) -> Result<Vec<LogEntry>, ServerError> {
+    let hits = response_body["hits"]["hits"]
+        .as_array()
+        .ok_or_else(|| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Invalid search response format"),
+            additional_information: String::from("Expected hits array in response"),
+        })?;
+        
+    let mut logs = Vec::new();
+    for hit in hits {
+        if let Some(source) = hit["_source"].as_object() {
+            let mut log_entry: LogEntry = serde_json::from_value(json!(source))
+                .map_err(|e| ServerError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR,
+                    message: String::from("Failed to deserialize log entry"),
+                    additional_information: e.to_string(),
+                })?;
+            log_entry.ingested_at = stored_ingested_at(source);
+            logs.push(log_entry);
+        }
+    }
+
+    Ok(logs)
+}
+
+/// Keep alive of the points in time used by [`query_logs_after`], renewed with every page
+pub const POINT_IN_TIME_KEEP_ALIVE: &str = "1m";
+
+/// Opens a point in time on the index => pages of [`query_logs_after`] read a consistent snapshot.
+///
+/// # Returns
+/// * `Ok(String)` - Id of the point in time, to be closed with [`close_point_in_time`]
+/// * `Err(ServerError)` - 504 if elastic search is unreachable, 500 if it rejects the request
+pub async fn open_point_in_time(index_name: &str, client: &Elasticsearch) -> Result<String, ServerError> {
+    let response = client
+        .open_point_in_time(OpenPointInTimeParts::Index(&[index_name]))
+        .keep_alive(POINT_IN_TIME_KEEP_ALIVE)
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Opening point in time failed"),
+            additional_information: e.to_string(),
+        })?
+        .error_for_status_code()
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Opening point in time failed"),
+            additional_information: e.to_string(),
+        })?;
+
+    let body: Value = response.json().await.map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Failed to parse point in time response"),
+        additional_information: e.to_string(),
+    })?;
+    body["id"].as_str().map(String::from).ok_or_else(|| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Invalid point in time response"),
+        additional_information: String::from("Expected id in response"),
+    })
+}
+
+/// Closes a point in time opened by [`open_point_in_time`] => elastic search can free its resources early.
+pub async fn close_point_in_time(client: &Elasticsearch, pit_id: &str) -> Result<(), ServerError> {
+    client
+        .close_point_in_time()
+        .body(json!({ "id": pit_id }))
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Closing point in time failed"),
+            additional_information: e.to_string(),
+        })?
+        .error_for_status_code()
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Closing point in time failed"),
+            additional_information: e.to_string(),
+        })?;
+    Ok(())
+}
+
+/// Page of sensor logs read by [`query_logs_after`].
+pub struct LogPage {
+    pub logs: Vec<LogEntry>,
+    /// Sort values of the last hit => `search_after` of the next page, `None` for an empty page
+    pub search_after: Option<Value>,
+    /// Point in time for the next page, elastic search may hand out an updated id
+    pub pit_id: String,
+}
+
+/// Queries the page of sensor logs following `search_after` within a point in time.
+///
+/// Unlike `from`/`size` pagination of [`query_logs`] this isn't bounded by `index.max_result_window`
+/// (default 10000) => used to export arbitrarily many logs. Filters and sort are taken from the query,
+/// `limit` / `offset` are ignored. Elastic search appends the implicit `_shard_doc` tiebreaker of the
+/// point in time => pages neither skip nor repeat logs sharing the same sort values.
+///
+/// # Parameters
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `query` - Filters and sort of the logs
+/// * `pit_id` - Point in time opened by [`open_point_in_time`] on the index
+/// * `search_after` - Sort values of the last log of the previous page, `None` for the first page
+/// * `size` - Maximum number of logs of the page
+///
+/// # Returns
+/// * `Ok(LogPage)` - Logs of the page in sort order with the cursor of the next page
+/// * `Err(ServerError)` - 400 for an invalid sort, otherwise like [`query_logs`]
+///
+/// # Examples
+/// ```rust
+/// let pit_id = open_point_in_time("sensor_logs", &client).await?;
+/// let page = query_logs_after(&client, &query, &pit_id, None, 1000).await?;
+/// let next = query_logs_after(&client, &query, &page.pit_id, page.search_after.as_ref(), 1000).await?;
+/// close_point_in_time(&client, &next.pit_id).await?;
+/// ```
+pub async fn query_logs_after(
+    client: &Elasticsearch,
+    query: &LogQuery,
+    pit_id: &str,
+    search_after: Option<&Value>,
+    size: usize,
+) -> Result<LogPage, ServerError> {
+    let sort = parse_sort(
+        query.sort.as_deref(),
+        query.sort_field.as_deref(),
+        query.sort_order.as_deref(),
+        &LOG_SORT_FIELDS,
+    )?;
+    // No index => the point in time determines the searched indices
+    let mut search_body = json!({
+        "query": log_filter(query),
+        "sort": sort,
+        "size": size,
+        "pit": { "id": pit_id, "keep_alive": POINT_IN_TIME_KEEP_ALIVE },
+        "track_total_hits": false
+    });
+    if let Some(search_after) = search_after {
+        search_body["search_after"] = search_after.clone();
+    }
+
     let response = client
-        .search(SearchParts::Index(&[index_name]))
+        .search(SearchParts::None)
         .body(search_body)
         .send()
         .await
@@ -600,39 +1998,32 @@ pub async fn query_logs(
             code: StatusCode::GATEWAY_TIMEOUT,
             message: String::from("Search request failed"),
             additional_information: e.to_string(),
-        })?;
-        
-    let response_body: Value = response
-        .json()
-        .await
+        })?
+        .error_for_status_code()
         .map_err(|e| ServerError {
             code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: String::from("Failed to parse search response"),
+            message: String::from("Search request failed"),
             additional_information: e.to_string(),
         })?;
-        
-    let hits = response_body["hits"]["hits"]
+
+    let response_body: Value = response.json().await.map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Failed to parse search response"),
+        additional_information: e.to_string(),
+    })?;
+
+    let logs = parse_log_hits(&response_body)?;
+    let search_after = response_body["hits"]["hits"]
         .as_array()
-        .ok_or_else(|| ServerError {
-            code: StatusCode::INTERNAL_SERVER_ERROR,
-            message: String::from("Invalid search response format"),
-            additional_information: String::from("Expected hits array in response"),
-        })?;
-        
-    let mut logs = Vec::new();
-    for hit in hits {
-        if let Some(source) = hit["_source"].as_object() {
-            let log_entry: LogEntry = serde_json::from_value(json!(source))
-                .map_err(|e| ServerError {
-                    code: StatusCode::INTERNAL_SERVER_ERROR,
-                    message: String::from("Failed to deserialize log entry"),
-                    additional_information: e.to_string(),
-                })?;
-            logs.push(log_entry);
-        }
-    }
-    
-    Ok(logs)
+        .and_then(|hits| hits.last())
+        .map(|hit| hit["sort"].clone());
+    let pit_id = response_body["pit_id"].as_str().unwrap_or(pit_id).to_string();
+
+    Ok(LogPage {
+        logs,
+        search_after,
+        pit_id,
+    })
 }
 
 /// Performs full-text search on sensor logs using multi-field matching with fuzzy capabilities.
@@ -680,7 +2071,7 @@ pub async fn search_logs(
                 "fuzziness": "AUTO"
             }
         },
-        "sort": [{ "timestamp": { "order": "desc" } }],
+        "sort": default_sort(),
         "size": search.limit.unwrap_or(100),
         "from": search.offset.unwrap_or(0)
     });
@@ -716,15 +2107,635 @@ pub async fn search_logs(
     let mut logs = Vec::new();
     for hit in hits {
         if let Some(source) = hit["_source"].as_object() {
-            let log_entry: LogEntry = serde_json::from_value(json!(source))
+            let mut log_entry: LogEntry = serde_json::from_value(json!(source))
                 .map_err(|e| ServerError {
                     code: StatusCode::INTERNAL_SERVER_ERROR,
                     message: String::from("Failed to deserialize log entry"),
                     additional_information: e.to_string(),
                 })?;
+            log_entry.ingested_at = stored_ingested_at(source);
             logs.push(log_entry);
         }
     }
     
     Ok(logs)
 }
+
+/// Builds the query restricting documents to a time range, matching all documents without bounds.
+fn timestamp_range_query(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Value {
+    if from.is_none() && to.is_none() {
+        return json!({ "match_all": {} });
+    }
+
+    let mut range_query = json!({ "range": { "timestamp": {} } });
+    if let Some(from) = from {
+        range_query["range"]["timestamp"]["gte"] = json!(from.to_rfc3339());
+    }
+    if let Some(to) = to {
+        range_query["range"]["timestamp"]["lte"] = json!(to.to_rfc3339());
+    }
+    range_query
+}
+
+/// Counts sensor logs per level with a `terms` aggregation, without fetching any documents.
+///
+/// # Parameters
+/// * `index_name` - The name of the Elasticsearch index containing sensor logs
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `query` - Optional `from`/`to` time range the counts are restricted to
+///
+/// # Returns
+/// * `Ok(BTreeMap<String, u64>)` - Number of logs per level, levels without logs are omitted
+/// * `Err(ServerError)` - Error if query execution or response parsing fails
+///
+/// # Examples
+/// ```rust
+/// let counts = aggregate_levels("sensor_logs", &client, &LevelStatsQuery { from: Some(yesterday), to: None }).await?;
+/// // {"CRITICAL": 12, "INFO": 4711, "WARN": 98}
+/// ```
+pub async fn aggregate_levels(
+    index_name: &str,
+    client: &Elasticsearch,
+    query: &LevelStatsQuery,
+) -> Result<BTreeMap<String, u64>, ServerError> {
+    let search_body = json!({
+        "query": timestamp_range_query(query.from, query.to),
+        // Only the buckets are needed => no documents are returned
+        "size": 0,
+        "aggs": {
+            "levels": { "terms": { "field": "level", "size": 10 } }
+        }
+    });
+
+    let response = client
+        .search(SearchParts::Index(&[index_name]))
+        .body(search_body)
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Aggregation request failed"),
+            additional_information: e.to_string(),
+        })?;
+
+    let response_body: Value = response
+        .json()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Failed to parse aggregation response"),
+            additional_information: e.to_string(),
+        })?;
+
+    let buckets = response_body["aggregations"]["levels"]["buckets"]
+        .as_array()
+        .ok_or_else(|| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Invalid aggregation response format"),
+            additional_information: String::from("Expected level buckets in response"),
+        })?;
+
+    Ok(buckets
+        .iter()
+        .filter_map(|bucket| Some((bucket["key"].as_str()?.to_string(), bucket["doc_count"].as_u64()?)))
+        .collect())
+}
+
+/// Device of the sensor logs with its number of logs.
+#[derive(Debug, Serialize)]
+pub struct DeviceCount {
+    pub device: String,
+    pub count: u64,
+}
+
+/// Lists the distinct devices of the sensor logs with a `terms` aggregation on `msg.device`.
+///
+/// # Parameters
+/// * `index_name` - The name of the Elasticsearch index containing sensor logs
+/// * `client` - Reference to the configured Elasticsearch client
+/// * `max_devices` - Maximum number of devices returned, the ones with most logs first
+///
+/// # Returns
+/// * `Ok(Vec<DeviceCount>)` - Devices ordered by descending number of logs
+/// * `Err(ServerError)` - Error if query execution or response parsing fails
+///
+/// # Examples
+/// ```rust
+/// let devices = list_devices("sensor_logs", &client, 1000).await?;
+/// // [{"device": "arduino0", "count": 1234}, ...]
+/// ```
+pub async fn list_devices(
+    index_name: &str,
+    client: &Elasticsearch,
+    max_devices: usize,
+) -> Result<Vec<DeviceCount>, ServerError> {
+    let search_body = json!({
+        "size": 0,
+        "aggs": {
+            "devices": { "terms": { "field": "msg.device", "size": max_devices } }
+        }
+    });
+
+    let response = client
+        .search(SearchParts::Index(&[index_name]))
+        .body(search_body)
+        .send()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::GATEWAY_TIMEOUT,
+            message: String::from("Aggregation request failed"),
+            additional_information: e.to_string(),
+        })?;
+
+    let response_body: Value = response
+        .json()
+        .await
+        .map_err(|e| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Failed to parse aggregation response"),
+            additional_information: e.to_string(),
+        })?;
+
+    let buckets = response_body["aggregations"]["devices"]["buckets"]
+        .as_array()
+        .ok_or_else(|| ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Invalid aggregation response format"),
+            additional_information: String::from("Expected device buckets in response"),
+        })?;
+
+    Ok(buckets
+        .iter()
+        .filter_map(|bucket| {
+            Some(DeviceCount {
+                device: bucket["key"].as_str()?.to_string(),
+                count: bucket["doc_count"].as_u64()?,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sort_defaults_to_newest_first() {
+        assert_eq!(parse_sort(None, None, None, &LOG_SORT_FIELDS).unwrap(), default_sort());
+        assert_eq!(parse_sort(Some("  "), None, None, &LOG_SORT_FIELDS).unwrap(), default_sort());
+    }
+
+    #[test]
+    fn parse_sort_keeps_key_order_and_appends_tiebreaker() {
+        let sort = parse_sort(Some("level:desc, timestamp:ASC,humidity"), None, None, &LOG_SORT_FIELDS).unwrap();
+
+        assert_eq!(
+            sort,
+            vec![
+                json!({ "level": { "order": "desc" } }),
+                json!({ "timestamp": { "order": "asc" } }),
+                json!({ "humidity": { "order": "desc" } }),
+                sort_tiebreaker(),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_sort_rejects_unknown_fields_and_directions() {
+        let error = parse_sort(Some("msg.msg:asc"), None, None, &LOG_SORT_FIELDS).unwrap_err();
+        assert_eq!(error.code, StatusCode::BAD_REQUEST);
+
+        let error = parse_sort(Some("level:up"), None, None, &LOG_SORT_FIELDS).unwrap_err();
+        assert_eq!(error.code, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn parse_sort_reads_single_field_and_order() {
+        assert_eq!(
+            parse_sort(None, Some("container_name"), Some("asc"), &CONTAINER_SORT_FIELDS).unwrap(),
+            vec![json!({ "container_name": { "order": "asc" } }), sort_tiebreaker()]
+        );
+        // Missing field defaults to timestamp, missing order to desc
+        assert_eq!(
+            parse_sort(None, None, Some("asc"), &LOG_SORT_FIELDS).unwrap(),
+            vec![json!({ "timestamp": { "order": "asc" } }), sort_tiebreaker()]
+        );
+        assert_eq!(
+            parse_sort(None, Some("level"), None, &LOG_SORT_FIELDS).unwrap(),
+            vec![json!({ "level": { "order": "desc" } }), sort_tiebreaker()]
+        );
+    }
+
+    #[test]
+    fn parse_sort_prefers_sort_over_single_field() {
+        assert_eq!(
+            parse_sort(Some("humidity:asc"), Some("level"), Some("desc"), &LOG_SORT_FIELDS).unwrap(),
+            vec![json!({ "humidity": { "order": "asc" } }), sort_tiebreaker()]
+        );
+        let error = parse_sort(None, Some("log_message"), None, &CONTAINER_SORT_FIELDS).unwrap_err();
+        assert_eq!(error.code, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn retry_delay_doubles_backoff_with_jitter() {
+        let base = Duration::from_millis(100);
+
+        for (attempt, backoff) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = retry_delay(base, attempt);
+            let backoff = Duration::from_millis(backoff);
+            assert!(delay >= backoff / 2 && delay <= backoff, "attempt {}: {:?}", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn retry_delay_caps_exponent() {
+        let base = Duration::from_millis(1);
+
+        assert!(retry_delay(base, 50) <= Duration::from_millis(1 << 10));
+        assert_eq!(retry_delay(Duration::ZERO, 3), Duration::ZERO);
+    }
+
+    #[test]
+    fn prepare_document_stamps_ingested_at_instead_of_client_value() {
+        let log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": Utc::now(),
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": "all values in range",
+            "ingested_at": "2000-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert!(log_entry.ingested_at.is_none());
+
+        let before = Utc::now();
+        let (_, document) = prepare_document("sensor_logs", &log_entry, &IndexingConfig::from_env()).unwrap();
+
+        let source = document.as_object().unwrap();
+        assert!(stored_ingested_at(source).is_some_and(|ingested_at| ingested_at >= before - chrono::Duration::milliseconds(1)));
+        assert!(source["event_id"].as_str().is_some_and(|event_id| !event_id.is_empty()));
+    }
+
+    /// Failure of an elastic search request that counts towards opening the circuit.
+    fn unavailable() -> ServerError {
+        ServerError {
+            code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: String::from("Index creation failed!"),
+            additional_information: String::new(),
+        }
+    }
+
+    #[test]
+    fn circuit_breaker_opens_after_threshold_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+
+        breaker.record_failure();
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure();
+        assert_eq!(breaker.check().unwrap_err().code, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn circuit_breaker_lets_single_probe_pass_after_cooldown() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+        // Half-open => further requests wait for the outcome of the probe
+        assert!(breaker.check().is_err());
+    }
+
+    #[test]
+    fn circuit_breaker_closes_on_successful_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+
+        breaker.record_success();
+
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn circuit_breaker_reopens_on_failed_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+
+        breaker.record_failure();
+
+        // The cooldown starts over
+        assert!(breaker.check().is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().is_ok());
+    }
+
+    #[actix_web::test]
+    async fn circuit_breaker_call_counts_only_server_errors() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        let client_error = ServerError {
+            code: StatusCode::BAD_REQUEST,
+            ..unavailable()
+        };
+
+        assert!(breaker.call(async { Err::<(), _>(client_error) }).await.is_err());
+        assert!(breaker.call(async { Ok::<_, ServerError>(1) }).await.is_ok());
+
+        assert!(breaker.call(async { Err::<(), _>(unavailable()) }).await.is_err());
+        let error = breaker.call(async { Ok::<_, ServerError>(1) }).await.unwrap_err();
+        assert_eq!(error.code, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn circuit_breaker_with_threshold_zero_never_opens() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+
+        for _ in 0..10 {
+            breaker.record_failure();
+        }
+
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn sort_tiebreaker_uses_keyword_sub_field_of_every_mapping() {
+        let tiebreaker = sort_tiebreaker();
+        let (field, _) = tiebreaker.as_object().unwrap().iter().next().unwrap();
+        assert_eq!(field, "event_id.keyword");
+
+        // New indices get the sub-field dynamic mapping creates on legacy indices
+        for mapping in [create_log_mapping(), create_container_log_mapping(), create_ops_mapping()] {
+            assert_eq!(mapping["properties"]["event_id"]["fields"]["keyword"]["type"], "keyword");
+        }
+    }
+
+    fn log_query(query: Value) -> LogQuery {
+        serde_json::from_value(query).unwrap()
+    }
+
+    #[test]
+    fn log_filter_isolates_synthetic_logs() {
+        assert_eq!(
+            log_filter(&log_query(json!({ "source": "Synthetic" }))),
+            json!({ "bool": { "must": [{ "term": { "source": "synthetic" } }] } })
+        );
+        // Real sensor logs carry no source
+        assert_eq!(
+            log_filter(&log_query(json!({ "source": "real" }))),
+            json!({ "bool": { "must": [{ "bool": { "must_not": { "exists": { "field": "source" } } } }] } })
+        );
+        assert_eq!(log_filter(&log_query(json!({}))), json!({ "match_all": {} }));
+    }
+
+    #[test]
+    fn log_filter_combines_level_and_temperature_range() {
+        let filter = log_filter(&log_query(json!({ "level": "warn", "temp_min": 30.0, "humidity_max": 0.5 })));
+
+        // All clauses within `must` => a log has to match every one of them
+        assert_eq!(
+            filter,
+            json!({ "bool": { "must": [
+                { "term": { "level": "WARN" } },
+                { "range": { "temperature": { "gte": 30.0 } } },
+                { "range": { "humidity": { "lte": 0.5 } } }
+            ] } })
+        );
+    }
+
+    #[test]
+    fn resolve_index_name_uses_day_of_log_timestamp() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-01-15T23:59:59.999+00:00").unwrap().with_timezone(&Utc);
+        assert_eq!(resolve_index_name("iot_sensor", timestamp), "iot_sensor-2025.01.15");
+
+        // Offsets are converted to UTC before the day is taken
+        let timestamp = DateTime::parse_from_rfc3339("2025-01-16T00:30:00+02:00").unwrap().with_timezone(&Utc);
+        assert_eq!(resolve_index_name("iot_sensor", timestamp), "iot_sensor-2025.01.15");
+    }
+
+    #[test]
+    fn prepare_document_routes_into_daily_index_only_with_time_based_indices() {
+        let log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-03-02T10:00:00Z",
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": "all values in range"
+        }))
+        .unwrap();
+        let config = IndexingConfig {
+            max_log_age: None,
+            ..IndexingConfig::from_env()
+        };
+
+        let (index_name, _) = prepare_document(
+            "sensor_logs",
+            &log_entry,
+            &IndexingConfig { time_based_indices: true, ..config.clone() },
+        )
+        .unwrap();
+        assert_eq!(index_name, "sensor_logs-2025.03.02");
+
+        let (index_name, _) = prepare_document(
+            "sensor_logs",
+            &log_entry,
+            &IndexingConfig { time_based_indices: false, ..config },
+        )
+        .unwrap();
+        assert_eq!(index_name, "sensor_logs");
+    }
+
+    #[test]
+    fn unparseable_timestamps_are_rejected_before_an_index_is_resolved() {
+        // No daily index can be derived => the entry is rejected on deserialization instead of falling back to today
+        for timestamp in ["yesterday", "2025-13-01T00:00:00Z", ""] {
+            let log_entry = serde_json::from_value::<LogEntry>(json!({
+                "timestamp": timestamp,
+                "level": "INFO",
+                "temperature": 21.5,
+                "humidity": 40.0,
+                "msg": "all values in range"
+            }));
+            assert!(log_entry.is_err(), "{} was accepted", timestamp);
+        }
+    }
+
+    #[test]
+    fn mappings_index_schema_version() {
+        for mapping in [create_log_mapping(), create_container_log_mapping(), create_ops_mapping()] {
+            assert_eq!(mapping["properties"]["schema_version"]["type"], "integer");
+        }
+    }
+
+    #[test]
+    fn ilm_policy_body_contains_configured_phase_ages() {
+        let config = IlmConfig {
+            policy_name: "log_retention".to_string(),
+            warm_after_days: 7,
+            delete_after_days: 30,
+        };
+
+        assert_eq!(
+            config.policy_body(),
+            json!({ "policy": { "phases": {
+                "hot": { "min_age": "0ms", "actions": { "set_priority": { "priority": 100 } } },
+                "warm": { "min_age": "7d", "actions": { "set_priority": { "priority": 50 } } },
+                "delete": { "min_age": "30d", "actions": { "delete": {} } }
+            } } })
+        );
+    }
+
+    #[test]
+    fn ilm_policy_body_skips_warm_phase_after_deletion() {
+        let config = IlmConfig {
+            policy_name: "log_retention".to_string(),
+            warm_after_days: 14,
+            delete_after_days: 14,
+        };
+
+        let body = config.policy_body();
+
+        assert!(body["policy"]["phases"].get("warm").is_none());
+        assert_eq!(body["policy"]["phases"]["delete"]["min_age"], "14d");
+    }
+
+    #[test]
+    fn keyword_term_sets_case_insensitive_flag() {
+        assert_eq!(
+            keyword_term("msg.device", "Arduino0", Some(true)),
+            json!({ "term": { "msg.device": { "value": "Arduino0", "case_insensitive": true } } })
+        );
+        assert_eq!(
+            keyword_term("container_name", "api", None),
+            json!({ "term": { "container_name": { "value": "api", "case_insensitive": false } } })
+        );
+    }
+
+    #[test]
+    fn log_filter_matches_device_case_insensitive_on_request() {
+        let filter = log_filter(&log_query(json!({ "device": "ARDUINO0", "case_insensitive": true })));
+
+        assert_eq!(filter["bool"]["must"][0]["term"]["msg.device"]["case_insensitive"], true);
+        // The value is passed as given, elastic search ignores the casing
+        assert_eq!(filter["bool"]["must"][0]["term"]["msg.device"]["value"], "ARDUINO0");
+    }
+
+    fn max_age_config() -> IndexingConfig {
+        IndexingConfig {
+            max_log_age: Some(chrono::Duration::hours(1)),
+            max_log_future: chrono::Duration::seconds(300),
+            ..IndexingConfig::from_env()
+        }
+    }
+
+    #[test]
+    fn check_timestamp_accepts_timestamps_within_window() {
+        let config = max_age_config();
+        assert!(config.check_timestamp(Utc::now() - chrono::Duration::minutes(5)).is_ok());
+        // Small clock skew into the future is tolerated
+        assert!(config.check_timestamp(Utc::now() + chrono::Duration::seconds(60)).is_ok());
+    }
+
+    #[test]
+    fn check_timestamp_rejects_too_old_and_future_timestamps() {
+        let config = max_age_config();
+
+        let too_old = Utc::now() - chrono::Duration::hours(2);
+        let error = config.check_timestamp(too_old).unwrap_err();
+        assert_eq!(error.code, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(error.message.contains(&too_old.to_rfc3339()));
+
+        let future = Utc::now() + chrono::Duration::minutes(10);
+        let error = config.check_timestamp(future).unwrap_err();
+        assert_eq!(error.code, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(error.message.contains("in the future"));
+    }
+
+    #[test]
+    fn check_timestamp_is_disabled_without_max_age() {
+        let config = IndexingConfig {
+            max_log_age: None,
+            ..IndexingConfig::from_env()
+        };
+
+        assert!(config.check_timestamp(Utc::now() - chrono::Duration::days(3650)).is_ok());
+        assert!(config.check_timestamp(Utc::now() + chrono::Duration::days(3650)).is_ok());
+    }
+
+    #[test]
+    fn garbage_timestamps_never_reach_the_age_check() {
+        let log_entry = serde_json::from_value::<LogEntry>(json!({
+            "timestamp": "not a timestamp",
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": "all values in range"
+        }));
+
+        assert!(log_entry.is_err());
+    }
+
+    /// Message of a container log after truncation with the given limit
+    fn truncated(message: &str, max_message_len: usize) -> String {
+        let mut log_entry: ContainerLogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "container_name": "api",
+            "log_message": message
+        }))
+        .unwrap();
+        let config = IndexingConfig {
+            max_message_len: Some(max_message_len),
+            ..IndexingConfig::from_env()
+        };
+
+        config.truncate_message(&mut log_entry);
+        log_entry.log_message
+    }
+
+    #[test]
+    fn truncate_message_keeps_messages_up_to_the_limit() {
+        assert_eq!(truncated("12345", 5), "12345");
+        assert_eq!(truncated("1234", 5), "1234");
+        assert_eq!(truncated("", 5), "");
+    }
+
+    #[test]
+    fn truncate_message_cuts_after_the_limit() {
+        assert_eq!(truncated("123456", 5), "12345...[truncated]");
+    }
+
+    #[test]
+    fn truncate_message_counts_characters_not_bytes() {
+        // "é" takes two bytes => a byte based cut at 5 would split it
+        assert_eq!(truncated("1234é6", 5), "1234é...[truncated]");
+        assert_eq!(truncated("ééééé", 5), "ééééé");
+        assert_eq!(truncated("🌡️ 30°C", 2), "🌡️...[truncated]");
+    }
+
+    #[test]
+    fn truncate_message_applies_to_sensor_messages() {
+        let mut log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": { "device": "Arduino0", "msg": "INFO: all values in range", "exceeded_values": [false, false] }
+        }))
+        .unwrap();
+        let config = IndexingConfig {
+            max_message_len: Some(4),
+            ..IndexingConfig::from_env()
+        };
+
+        config.truncate_message(&mut log_entry);
+
+        assert_eq!(log_entry.msg.msg_mut().as_str(), "INFO...[truncated]");
+    }
+}
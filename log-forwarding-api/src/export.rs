@@ -0,0 +1,109 @@
+use crate::log_entry::LogEntry;
+use crate::server_error::ServerError;
+use actix_web::http::StatusCode;
+use serde_json::Value;
+
+/// Columns of the csv export, `msg` is flattened into `msg_*` columns
+pub const EXPORT_COLUMNS: [&str; 10] = [
+    "timestamp",
+    "level",
+    "temperature",
+    "humidity",
+    "msg_device",
+    "msg_msg",
+    "msg_exceeded_values",
+    "msg_exceeded_fields",
+    "source",
+    "ingested_at",
+];
+
+/// Returns the header line of the csv export.
+pub fn csv_header() -> String {
+    format!("{}\n", EXPORT_COLUMNS.join(","))
+}
+
+/// Serializes a log entry into a csv line matching [`EXPORT_COLUMNS`].
+///
+/// Missing optional fields stay empty, list fields are joined with `;`.
+///
+/// # Returns
+/// * `Ok(String)` - Csv line including the trailing newline
+/// * `Err(ServerError)` - If the log entry can't be serialized
+pub fn csv_row(log_entry: &LogEntry) -> Result<String, ServerError> {
+    let flat = log_entry.to_flat_json().map_err(|e| ServerError {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("Error while flattening log entry"),
+        additional_information: e.to_string(),
+    })?;
+
+    let fields: Vec<String> = EXPORT_COLUMNS
+        .iter()
+        .map(|column| csv_field(&flat[*column]))
+        .collect();
+    Ok(format!("{}\n", fields.join(",")))
+}
+
+/// Formats a single json value as csv field, quoting it if it contains separators, quotes or line breaks.
+fn csv_field(value: &Value) -> String {
+    let raw = match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(";"),
+        other => other.to_string(),
+    };
+
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn csv_field_formats_scalars_and_lists() {
+        assert_eq!(csv_field(&Value::Null), "");
+        assert_eq!(csv_field(&json!("INFO")), "INFO");
+        assert_eq!(csv_field(&json!(21.5)), "21.5");
+        assert_eq!(csv_field(&json!([true, false])), "true;false");
+        assert_eq!(csv_field(&json!(["temperature", "humidity"])), "temperature;humidity");
+    }
+
+    #[test]
+    fn csv_field_quotes_separators_quotes_and_line_breaks() {
+        assert_eq!(csv_field(&json!("a,b")), "\"a,b\"");
+        assert_eq!(csv_field(&json!("say \"hi\"")), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field(&json!("line\nbreak")), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn csv_row_matches_header_columns() {
+        let log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "level": "WARN",
+            "temperature": 26.0,
+            "humidity": 40.0,
+            "msg": { "device": "Arduino0", "msg": "WARN: high, temperature", "exceeded_values": [true, false] }
+        }))
+        .unwrap();
+
+        let row = csv_row(&log_entry).unwrap();
+
+        assert_eq!(
+            row,
+            "2025-06-01T12:00:00Z,WARN,26.0,40.0,Arduino0,\"WARN: high, temperature\",true;false,,,\n"
+        );
+        assert_eq!(csv_header().matches(',').count(), EXPORT_COLUMNS.len() - 1);
+    }
+}
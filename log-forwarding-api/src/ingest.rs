@@ -0,0 +1,122 @@
+use crate::log_entry::StrictFields;
+use crate::server_error::ServerError;
+use actix_web::http::StatusCode;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+/// Parses an ingested json payload into a typed log entry.
+///
+/// Missing or mistyped fields are reported with the serde error, which names the offending field.
+/// In strict mode fields that are not part of the log entry are rejected as well, so typos in
+/// field names don't get silently dropped. In lenient mode unknown fields are ignored.
+/// Unknown fields are detected via the `deny_unknown_fields` twin of the entry (see [`StrictFields`]).
+///
+/// # Parameters
+/// * `payload` - Request body as json value
+/// * `strict` - Whether unknown fields are rejected (`STRICT_INGEST`)
+///
+/// # Returns
+/// * `Ok(T)` - Parsed log entry
+/// * `Err(ServerError)` - Bad request naming the missing, invalid or unknown fields
+///
+/// # Examples
+/// ```rust
+/// // {"timestamp": ..., "levle": "INFO", ...} => 400 "Invalid log entry: missing field `level`"
+/// let log_entry: LogEntry = parse_entry(payload.into_inner(), data.indexing.strict_ingest)?;
+/// ```
+pub fn parse_entry<T>(payload: Value, strict: bool) -> Result<T, ServerError>
+where
+    T: DeserializeOwned + StrictFields,
+{
+    let entry = T::deserialize(&payload).map_err(|e| ServerError {
+        code: StatusCode::BAD_REQUEST,
+        message: format!("Invalid log entry: {}", e),
+        additional_information: e.to_string(),
+    })?;
+
+    // Checked against the strict twin of the entry => fields are compared by name, not by value
+    if strict {
+        T::check_fields(&payload).map_err(|e| ServerError {
+            code: StatusCode::BAD_REQUEST,
+            message: format!("Invalid log entry: {}", e),
+            additional_information: String::from("Strict ingest is enabled (STRICT_INGEST)"),
+        })?;
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_entry::{ContainerLogEntry, LogEntry};
+    use serde_json::json;
+
+    fn sensor_payload() -> Value {
+        json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": {
+                "device": "Arduino0",
+                "msg": "INFO: all values in range",
+                "exceeded_values": [false, false],
+                "exceeded_fields": []
+            }
+        })
+    }
+
+    #[test]
+    fn parse_entry_accepts_known_fields_in_strict_mode() {
+        assert!(parse_entry::<LogEntry>(sensor_payload(), true).is_ok());
+
+        let mut payload = sensor_payload();
+        payload["msg"] = json!("plain text message");
+        payload["source"] = Value::Null;
+        assert!(parse_entry::<LogEntry>(payload, true).is_ok());
+    }
+
+    #[test]
+    fn parse_entry_rejects_unknown_fields_in_strict_mode() {
+        let mut payload = sensor_payload();
+        payload["levle"] = json!("INFO");
+        let error = parse_entry::<LogEntry>(payload.clone(), true).unwrap_err();
+        assert_eq!(error.code, StatusCode::BAD_REQUEST);
+        assert!(error.message.contains("levle"));
+        // Lenient mode drops unknown fields
+        assert!(parse_entry::<LogEntry>(payload, false).is_ok());
+
+        let mut payload = sensor_payload();
+        payload["msg"]["devce"] = json!("Arduino1");
+        let error = parse_entry::<LogEntry>(payload, true).unwrap_err();
+        assert!(error.message.contains("devce"));
+    }
+
+    #[test]
+    fn parse_entry_names_missing_and_mistyped_fields() {
+        let mut payload = sensor_payload();
+        payload.as_object_mut().unwrap().remove("level");
+        let error = parse_entry::<LogEntry>(payload, false).unwrap_err();
+        assert_eq!(error.code, StatusCode::BAD_REQUEST);
+        assert!(error.message.contains("level"));
+
+        let mut payload = sensor_payload();
+        payload["temperature"] = json!("warm");
+        assert!(parse_entry::<LogEntry>(payload, false).is_err());
+    }
+
+    #[test]
+    fn parse_entry_keeps_parsed_content_of_container_logs() {
+        let payload = json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "container_name": "web",
+            "log_message": "{\"user\": 42}",
+            "parsed": { "user": 42, "any": { "nested": "field" } }
+        });
+
+        let entry = parse_entry::<ContainerLogEntry>(payload, true).unwrap();
+
+        assert_eq!(entry.parsed, Some(json!({ "user": 42, "any": { "nested": "field" } })));
+    }
+}
@@ -0,0 +1,9 @@
+//! Elastic search access and document types of the log forwarding api.
+//!
+//! Kept apart from the actix binary so the integration tests in `tests/` can run them
+//! against a mocked cluster (see `elastic::create_client_for_url`).
+pub mod elastic;
+pub mod log_entry;
+pub mod log_entry_components;
+pub mod query_structures;
+pub mod server_error;
@@ -1,9 +1,19 @@
-use crate::log_entry_components::{InnerMsg, LogLevel};
+use crate::log_entry_components::{InnerMsg, LogLevel, StrictInnerMsg};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
 use serde_json::{Value};
 pub trait ElasticLogDocument {
     fn to_document_json(&self) -> Result<Value, serde_json::Error>;
+    /// Event time of the document, used e.g. to resolve time based indices
+    fn timestamp(&self) -> DateTime<Utc>;
+    /// Free text message of the document, used e.g. to redact sensitive data before indexing
+    fn message_mut(&mut self) -> &mut String;
+}
+
+/// Log entry types with a strict twin, used to reject unknown fields with STRICT_INGEST.
+pub trait StrictFields {
+    /// Checks that the payload contains no fields beside the ones of the log entry (also nested).
+    fn check_fields(payload: &Value) -> Result<(), serde_json::Error>;
 }
 
 /// This struct matches the log json generated by the LogGen component
@@ -14,11 +24,66 @@ pub struct LogEntry {
     pub temperature: f64,
     pub humidity: f64,
     pub msg: InnerMsg,
+    /// Origin marker, e.g. "synthetic" for generator data. Absent for real sensor logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Time the api indexed the log, stamped on ingest. Only present in query results.
+    /// Never taken from clients => filled from the stored document by the query functions.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub ingested_at: Option<DateTime<Utc>>,
 }
 impl ElasticLogDocument for LogEntry {
     fn to_document_json(&self) -> Result<Value, serde_json::Error> {
         serde_json::to_value(self)
     }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn message_mut(&mut self) -> &mut String {
+        self.msg.msg_mut()
+    }
+}
+
+/// Field names of [`LogEntry`] => unknown fields fail on deserialization.
+/// Only the names are checked here, the values are validated by `LogEntry` itself.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct StrictLogEntry {
+    timestamp: IgnoredAny,
+    level: IgnoredAny,
+    temperature: IgnoredAny,
+    humidity: IgnoredAny,
+    msg: StrictInnerMsg,
+    source: Option<IgnoredAny>,
+}
+
+impl StrictFields for LogEntry {
+    fn check_fields(payload: &Value) -> Result<(), serde_json::Error> {
+        StrictLogEntry::deserialize(payload).map(|_| ())
+    }
+}
+
+impl LogEntry {
+    /// Serializes the log entry with the nested `msg` object flattened into top-level fields.
+    ///
+    /// Every field of `msg` is prefixed with `msg_`, e.g. `msg.device` becomes `msg_device`,
+    /// which eases loading query results directly into CSV / DataFrames.
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - JSON object without nested `msg`
+    /// * `Err(serde_json::Error)` - If the log entry can't be serialized
+    pub fn to_flat_json(&self) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(Value::Object(msg)) = value.as_object_mut().and_then(|object| object.remove("msg")) {
+            for (key, field) in msg {
+                value[format!("msg_{}", key)] = field;
+            }
+        }
+        Ok(value)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -26,10 +91,87 @@ pub struct ContainerLogEntry {
     pub timestamp: DateTime<Utc>,
     pub container_name: String,
     pub log_message: String,
+    /// Structured content of JSON log messages, set by the collector if PARSE_JSON_LOGS is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<Value>,
+    /// Original syslog line, set by the collector if STORE_RAW_SYSLOG is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    /// Time the api indexed the log, stamped on ingest. Only present in query results.
+    /// Never taken from clients => filled from the stored document by the query functions.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub ingested_at: Option<DateTime<Utc>>,
+}
+
+/// Field names of [`ContainerLogEntry`], see [`StrictLogEntry`].
+/// `parsed` holds arbitrary JSON => only the top-level fields are checked.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct StrictContainerLogEntry {
+    timestamp: IgnoredAny,
+    container_name: IgnoredAny,
+    log_message: IgnoredAny,
+    parsed: Option<IgnoredAny>,
+    raw: Option<IgnoredAny>,
+}
+
+impl StrictFields for ContainerLogEntry {
+    fn check_fields(payload: &Value) -> Result<(), serde_json::Error> {
+        StrictContainerLogEntry::deserialize(payload).map(|_| ())
+    }
 }
 
 impl ElasticLogDocument for ContainerLogEntry {
     fn to_document_json(&self) -> Result<Value, serde_json::Error> {
         serde_json::to_value(self)
     }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn message_mut(&mut self) -> &mut String {
+        &mut self.log_message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn to_flat_json_moves_msg_fields_to_top_level() {
+        let log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "level": "WARN",
+            "temperature": 31.5,
+            "humidity": 40.0,
+            "msg": {
+                "device": "Arduino0",
+                "msg": "WARNING: temperature exceeded",
+                "exceeded_values": [true, false]
+            },
+            "source": "synthetic"
+        }))
+        .unwrap();
+
+        let flat = log_entry.to_flat_json().unwrap();
+
+        assert_eq!(
+            flat,
+            json!({
+                "timestamp": "2025-06-01T12:00:00Z",
+                "level": "WARN",
+                "temperature": 31.5,
+                "humidity": 40.0,
+                "source": "synthetic",
+                "msg_device": "Arduino0",
+                "msg_msg": "WARNING: temperature exceeded",
+                "msg_exceeded_values": [true, false]
+            })
+        );
+        assert!(flat.as_object().unwrap().values().all(|value| !value.is_object()));
+    }
 }
@@ -1,15 +1,169 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de, de::IgnoredAny};
+use serde_json::Value;
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum LogLevel {
     Critical,
     Warn,
     Info,
+    Debug,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Nested message of a sensor log.
+///
+/// Deserializes from the regular object as well as from a plain string, which is wrapped
+/// without device and exceeded values => minimal clients can send `"msg": "text"`.
+/// A missing device defaults to [`DEFAULT_DEVICE`] and is replaced by the configured
+/// fallback in [`InnerMsg::fill_missing_device`] before indexing.
+#[derive(Debug, Serialize)]
 pub struct InnerMsg {
     device: String,
+    /// Whether `device` is the default instead of a device sent by the client
+    #[serde(skip)]
+    device_defaulted: bool,
+    msg: String,
+    exceeded_values: Vec<bool>,
+    /// Measurement names of the flags in `exceeded_values` (same index), empty for older senders
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exceeded_fields: Vec<String>,
+}
+
+/// Device of sensor logs sent without device, e.g. with the plain string form of `msg`
+pub const DEFAULT_DEVICE: &str = "unknown";
+
+/// Object form of [`InnerMsg`], deserialized by derive to keep serde's field level errors
+#[derive(Deserialize)]
+struct InnerMsgObject {
+    device: Option<String>,
     msg: String,
     exceeded_values: Vec<bool>,
+    #[serde(default)]
+    exceeded_fields: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for InnerMsg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(msg) => Ok(InnerMsg {
+                device: DEFAULT_DEVICE.to_string(),
+                device_defaulted: true,
+                msg,
+                exceeded_values: Vec::new(),
+                exceeded_fields: Vec::new(),
+            }),
+            value => {
+                let object = InnerMsgObject::deserialize(value).map_err(de::Error::custom)?;
+                let device_defaulted = object.device.is_none();
+                Ok(InnerMsg {
+                    device: object.device.unwrap_or_else(|| DEFAULT_DEVICE.to_string()),
+                    device_defaulted,
+                    msg: object.msg,
+                    exceeded_values: object.exceeded_values,
+                    exceeded_fields: object.exceeded_fields,
+                })
+            }
+        }
+    }
+}
+
+/// Field names of [`InnerMsg`] for strict ingest => unknown fields of the object form are rejected.
+/// The plain string form has no fields and is always accepted.
+pub struct StrictInnerMsg;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct StrictInnerMsgObject {
+    device: Option<IgnoredAny>,
+    msg: IgnoredAny,
+    exceeded_values: IgnoredAny,
+    exceeded_fields: Option<IgnoredAny>,
+}
+
+impl<'de> Deserialize<'de> for StrictInnerMsg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(_) => Ok(StrictInnerMsg),
+            value => StrictInnerMsgObject::deserialize(value)
+                .map(|_| StrictInnerMsg)
+                .map_err(|e| de::Error::custom(format!("msg: {}", e))),
+        }
+    }
+}
+
+impl InnerMsg {
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    pub fn msg_mut(&mut self) -> &mut String {
+        &mut self.msg
+    }
+
+    /// Replaces a missing or blank device, so device queries and the TUI don't get empty keywords.
+    ///
+    /// # Parameters
+    /// * `fallback` - Device name used instead of [`DEFAULT_DEVICE`] (`UNKNOWN_DEVICE`)
+    pub fn fill_missing_device(&mut self, fallback: &str) {
+        if self.device_defaulted || self.device.trim().is_empty() {
+            self.device = fallback.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn string_msg_defaults_to_unknown_device() {
+        let msg: InnerMsg = serde_json::from_value(json!("all values in range")).unwrap();
+
+        assert_eq!(msg.device(), DEFAULT_DEVICE);
+        assert_eq!(serde_json::to_value(&msg).unwrap()["device"], "unknown");
+    }
+
+    #[test]
+    fn object_msg_without_device_defaults_to_unknown_device() {
+        let msg: InnerMsg = serde_json::from_value(json!({ "msg": "ok", "exceeded_values": [] })).unwrap();
+
+        assert_eq!(msg.device(), DEFAULT_DEVICE);
+    }
+
+    #[test]
+    fn fill_missing_device_replaces_default_and_blank_devices_only() {
+        let mut msg: InnerMsg = serde_json::from_value(json!("ok")).unwrap();
+        msg.fill_missing_device("sensor-unknown");
+        assert_eq!(msg.device(), "sensor-unknown");
+
+        let mut msg: InnerMsg = serde_json::from_value(json!({ "device": " ", "msg": "ok", "exceeded_values": [] })).unwrap();
+        msg.fill_missing_device("sensor-unknown");
+        assert_eq!(msg.device(), "sensor-unknown");
+
+        // Devices sent by the client are kept
+        let mut msg: InnerMsg =
+            serde_json::from_value(json!({ "device": "Arduino0", "msg": "ok", "exceeded_values": [] })).unwrap();
+        msg.fill_missing_device("sensor-unknown");
+        assert_eq!(msg.device(), "Arduino0");
+    }
+
+    #[test]
+    fn log_with_empty_device_is_indexed_with_fallback_device() {
+        use crate::log_entry::{ElasticLogDocument, LogEntry};
+
+        let mut log_entry: LogEntry = serde_json::from_value(json!({
+            "timestamp": "2025-06-01T12:00:00Z",
+            "level": "INFO",
+            "temperature": 21.5,
+            "humidity": 40.0,
+            "msg": { "device": "", "msg": "INFO: all values in range", "exceeded_values": [false, false] }
+        }))
+        .unwrap();
+
+        log_entry.msg.fill_missing_device(DEFAULT_DEVICE);
+
+        let document = log_entry.to_document_json().unwrap();
+        assert_eq!(document["msg"]["device"], DEFAULT_DEVICE);
+    }
 }
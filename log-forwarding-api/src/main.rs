@@ -1,58 +1,186 @@
-mod elastic;
-mod log_entry;
-mod log_entry_components;
-mod query_structures;
-mod server_error;
+mod auth;
+mod export;
+mod ingest;
+mod rate_limit;
+mod redaction;
+mod self_logging;
+
+use log_forwarding_api::{elastic, log_entry, query_structures, server_error};
 
 use crate::server_error::ServerError;
+use auth::{ApiKey, Authenticated};
 use actix_web::{
-    App, HttpResponse, HttpServer, Result as ActixResult, error::ErrorInternalServerError, get,
-    http::StatusCode, middleware::Logger, post, web,
+    App, HttpResponse, HttpServer, Result as ActixResult, dev::Service, get, http::StatusCode,
+    middleware::Logger, post, web, web::Bytes,
 };
 use dotenvy::dotenv;
 use elastic::{
-    create_client, create_container_log_mapping, create_log_mapping, create_logs_index, get_nodes,
-    query_logs, search_logs, send_document, query_container_logs, search_container_logs,
+    BulkResult, CircuitBreaker, IlmConfig, IndexingConfig, LOG_SORT_FIELDS, aggregate_levels,
+    attach_ilm_policy, cluster_health, create_client, create_container_log_mapping,
+    create_index_template, create_log_mapping, create_logs_index, create_ops_mapping,
+    close_point_in_time, ensure_ilm_policy, get_nodes, list_devices, open_point_in_time, query_logs, query_logs_after,
+    parse_sort, search_logs, send_document, send_documents_bulk, query_container_logs, search_container_logs,
+    wait_for_elastic,
 };
 use elasticsearch::Elasticsearch;
+use export::{csv_header, csv_row};
+use futures_util::{StreamExt, future::ready, stream};
+use ingest::parse_entry;
 use log_entry::{ContainerLogEntry, LogEntry};
-use query_structures::{LogQuery, SearchQuery, ContainerLogQuery, ContainerSearchQuery};
+use query_structures::{LogQuery, SearchQuery, ContainerLogQuery, ContainerSearchQuery, LevelStatsQuery};
+use rate_limit::DeviceRateLimiter;
+use redaction::Redactor;
+use self_logging::{OpsCounters, OpsDocument};
+use serde_json::Value;
 use std::env;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Upper bound of devices listed by `/devices` => keeps the aggregation cheap with many devices
+const MAX_DEVICES: usize = 1000;
+/// Logs queried per page by `/logs/export` => at most one page is held in memory
+const EXPORT_PAGE_SIZE: usize = 1000;
+/// Logs exported by `/logs/export` without `limit`
+const EXPORT_DEFAULT_LIMIT: usize = 10_000;
+/// Upper bound of `limit` of `/logs/export`
+const EXPORT_MAX_LIMIT: usize = 1_000_000;
+
 struct AppState {
     client: Elasticsearch,
     host_id: Uuid,
     index_name: String,
     container_logs_index_name: String,
+    breaker: CircuitBreaker,
+    indexing: IndexingConfig,
+    redactor: Redactor,
+    device_limiter: DeviceRateLimiter,
+    ops: OpsCounters,
+    api_key: ApiKey,
+}
+
+/// Container name of this instance or, if not available, the uuid generated on startup.
+fn instance_id(data: &AppState) -> String {
+    env::var("HOSTNAME").unwrap_or_else(|_| data.host_id.to_string())
+}
+
+/// Indexes a summary of the request counters into the ops index every interval (SELF_LOGGING).
+///
+/// Ops documents go through `send_document` like regular logs, but always into a single index
+/// without ingest pipeline and timestamp window, since those are meant for ingested logs.
+///
+/// # Parameters
+/// * `data` - Shared application state holding the counters and the elastic search client
+/// * `ops_index_name` - Index the summaries are written to
+/// * `interval` - Length of one summarized interval
+async fn run_self_logging(data: web::Data<AppState>, ops_index_name: String, interval: Duration) {
+    let indexing = IndexingConfig {
+        time_based_indices: false,
+        pipeline: None,
+        max_log_age: None,
+        ..data.indexing.clone()
+    };
+    let mut ticker = actix_web::rt::time::interval(interval);
+    // First tick completes immediately => skip it so the first summary covers a full interval
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        let document = OpsDocument::new(instance_id(&data), interval.as_secs(), data.ops.take());
+        if let Err(e) = data
+            .breaker
+            .call(send_document(&ops_index_name, &data.client, &document, &indexing))
+            .await
+        {
+            log::warn!("Failed to index ops summary: {}", e);
+        }
+    }
 }
 
 /// Endpoint used to send logsender logs towards the es cluster.
 #[post("/send_log")]
 async fn send_log(
+    _auth: Authenticated,
     data: web::Data<AppState>,
-    log_message: web::Json<LogEntry>,
+    log_message: web::Json<Value>,
 ) -> ActixResult<HttpResponse> {
-    let log_entry = log_message.into_inner();
-    // Map_err needed since send_document doesnt return a actix error.
-    let return_val = send_document(&data.index_name, &data.client, &log_entry)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    // Parsed by hand => missing / unknown fields are reported with a clear 400
+    let mut log_entry: LogEntry = parse_entry(log_message.into_inner(), data.indexing.strict_ingest)?;
+    log_entry.msg.fill_missing_device(&data.indexing.unknown_device);
+    // Noisy devices are rejected with 429 before touching elastic search
+    data.device_limiter.check(log_entry.msg.device())?;
+    data.redactor.apply(&mut log_entry);
+    data.indexing.truncate_message(&mut log_entry);
+    // ServerError implements ResponseError => status codes (e.g. 503 of an open circuit) reach the client.
+    let return_val = data
+        .breaker
+        .call(send_document(&data.index_name, &data.client, &log_entry, &data.indexing))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "result": return_val })))
 }
 
+/// Endpoint used to send a batch of logsender logs towards the es cluster with a single bulk request.
+///
+/// Entries are handled independently => invalid, rate limited or rejected entries are reported
+/// with their position in the batch while the remaining entries are indexed.
+/// With failed entries the status is 200 or 207 depending on `BULK_ERROR_MODE`.
+#[post("/send_logs_bulk")]
+async fn send_logs_bulk(
+    _auth: Authenticated,
+    data: web::Data<AppState>,
+    log_messages: web::Json<Vec<Value>>,
+) -> ActixResult<HttpResponse> {
+    let mut result = BulkResult::default();
+    let mut log_entries = Vec::new();
+
+    for (position, payload) in log_messages.into_inner().into_iter().enumerate() {
+        let mut log_entry: LogEntry = match parse_entry(payload, data.indexing.strict_ingest) {
+            Ok(log_entry) => log_entry,
+            Err(e) => {
+                result.fail(position, e.message);
+                continue;
+            }
+        };
+        log_entry.msg.fill_missing_device(&data.indexing.unknown_device);
+        if let Err(e) = data.device_limiter.check(log_entry.msg.device()) {
+            result.fail(position, e.message);
+            continue;
+        }
+        data.redactor.apply(&mut log_entry);
+        data.indexing.truncate_message(&mut log_entry);
+        log_entries.push((position, log_entry));
+    }
+
+    let indexed = data
+        .breaker
+        .call(send_documents_bulk(&data.index_name, &data.client, &log_entries, &data.indexing))
+        .await?;
+    result.merge(indexed);
+
+    Ok(HttpResponse::build(result.status(data.indexing.bulk_error_mode)).json(result))
+}
+
 /// Endpoint used to send logsender logs towards the es cluster.
 #[post("/send_container_log")]
 async fn send_container_log(
+    _auth: Authenticated,
     data: web::Data<AppState>,
-    log_message: web::Json<ContainerLogEntry>,
+    log_message: web::Json<Value>,
 ) -> ActixResult<HttpResponse> {
-    let log_entry = log_message.into_inner();
-    // Map_err needed since send_document doesnt return a actix error.
-    let return_val = send_document(&data.container_logs_index_name, &data.client, &log_entry)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    let mut log_entry: ContainerLogEntry =
+        parse_entry(log_message.into_inner(), data.indexing.strict_ingest)?;
+    data.redactor.apply(&mut log_entry);
+    data.indexing.truncate_message(&mut log_entry);
+    // ServerError implements ResponseError => status codes (e.g. 503 of an open circuit) reach the client.
+    let return_val = data
+        .breaker
+        .call(send_document(
+            &data.container_logs_index_name,
+            &data.client,
+            &log_entry,
+            &data.indexing,
+        ))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "result": return_val })))
 }
@@ -62,64 +190,254 @@ async fn send_container_log(
 async fn who_are_you(data: web::Data<AppState>) -> ActixResult<HttpResponse> {
     Ok(HttpResponse::Ok().json(serde_json::json!(
         {
-            "instance_id": env::var("HOSTNAME").unwrap_or_else(|_| data.host_id.to_string())
+            "instance_id": instance_id(&data)
         }
     )))
 }
 
+/// Readiness probe reporting the elastic search cluster status, open like `/whoareyou`.
+///
+/// Returns `{ "status": "green|yellow|red" }` while the cluster is reachable, with the status code of [`health_status_code`],
+/// and 503 otherwise. Bypasses the circuit breaker => the probe reflects the cluster itself instead of the breaker state.
+#[get("/health")]
+async fn health(data: web::Data<AppState>) -> ActixResult<HttpResponse> {
+    let status = cluster_health(&data.client).await?;
+
+    Ok(HttpResponse::build(health_status_code(&status)).json(serde_json::json!({ "status": status })))
+}
+
+/// Maps the cluster status to the status code of `/health`.
+///
+/// A `red` cluster has unassigned primary shards => writes to them fail, so probes get a 503.
+/// `yellow` (e.g. missing replicas on a single node) is still able to serve and stays 200.
+fn health_status_code(cluster_status: &str) -> StatusCode {
+    match cluster_status {
+        "red" => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    }
+}
+
 #[get("/elasticnodeinfo")]
-async fn elastic_node_info(data: web::Data<AppState>) -> ActixResult<HttpResponse> {
-    let return_val = get_nodes(&data.client)
-        .await
-        .map_err(ErrorInternalServerError)?;
+async fn elastic_node_info(
+    _auth: Authenticated,
+    data: web::Data<AppState>,
+) -> ActixResult<HttpResponse> {
+    let return_val = data.breaker.call(get_nodes(&data.client)).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "result": return_val })))
 }
 
 #[get("/logs")]
 async fn get_logs(
+    _auth: Authenticated,
     data: web::Data<AppState>,
     query: web::Query<LogQuery>,
 ) -> ActixResult<HttpResponse> {
-    let logs = query_logs(&data.index_name, &data.client, &query)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    // ServerError carries its own status code => invalid sort parameters surface as 400
+    let (logs, total) = data
+        .breaker
+        .call(query_logs(&data.indexing.search_index(&data.index_name), &data.client, &query))
+        .await?;
+    // Same defaults as the query => clients can page without knowing them
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(100);
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "logs": logs })))
+    if query.flatten.unwrap_or(false) {
+        let flat_logs = logs
+            .iter()
+            .map(LogEntry::to_flat_json)
+            .collect::<Result<Vec<Value>, serde_json::Error>>()
+            .map_err(|e| ServerError {
+                code: StatusCode::INTERNAL_SERVER_ERROR,
+                message: String::from("Error while flattening log entries"),
+                additional_information: e.to_string(),
+            })?;
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "logs": flat_logs,
+            "total": total,
+            "offset": offset,
+            "limit": limit
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "logs": logs,
+        "total": total,
+        "offset": offset,
+        "limit": limit
+    })))
+}
+
+/// Endpoint exporting sensor logs as csv, taking the same parameters as `/logs`.
+///
+/// The body is streamed page by page => the first rows are sent before later pages are queried.
+/// Without `limit` at most EXPORT_DEFAULT_LIMIT logs are exported, larger limits are capped at
+/// EXPORT_MAX_LIMIT. Pages are read with `search_after` within a point in time, so exports aren't
+/// bounded by the `index.max_result_window` of elastic search and see a consistent snapshot.
+/// The point in time is closed after the last page, if the client disconnects it expires on its own.
+#[get("/logs/export")]
+async fn export_logs(
+    _auth: Authenticated,
+    data: web::Data<AppState>,
+    query: web::Query<LogQuery>,
+) -> ActixResult<HttpResponse> {
+    let query = query.into_inner();
+    // Validated upfront => an invalid sort is a 400 instead of an aborted stream
+    parse_sort(
+        query.sort.as_deref(),
+        query.sort_field.as_deref(),
+        query.sort_order.as_deref(),
+        &LOG_SORT_FIELDS,
+    )?;
+    let index_name = data.indexing.search_index(&data.index_name);
+    let pit_id = data
+        .breaker
+        .call(open_point_in_time(&index_name, &data.client))
+        .await?;
+    let cursor = ExportCursor {
+        pit_id,
+        search_after: None,
+        // The offset is skipped while paging => no `from` limited by max_result_window
+        skip: query.offset.unwrap_or(0),
+        remaining: query.limit.unwrap_or(EXPORT_DEFAULT_LIMIT).min(EXPORT_MAX_LIMIT),
+    };
+
+    let header = stream::once(ready(Ok::<Bytes, ServerError>(Bytes::from(csv_header()))));
+    let rows = stream::try_unfold(cursor, move |mut cursor| {
+        let (data, query) = (data.clone(), query.clone());
+
+        async move {
+            if cursor.remaining == 0 {
+                close_export(&data, &cursor.pit_id).await;
+                return Ok(None);
+            }
+            let page_size = EXPORT_PAGE_SIZE.min(cursor.skip.saturating_add(cursor.remaining));
+            let page = data
+                .breaker
+                .call(query_logs_after(
+                    &data.client,
+                    &query,
+                    &cursor.pit_id,
+                    cursor.search_after.as_ref(),
+                    page_size,
+                ))
+                .await?;
+            if page.logs.is_empty() {
+                close_export(&data, &page.pit_id).await;
+                return Ok(None);
+            }
+
+            let skipped = cursor.skip.min(page.logs.len());
+            let mut chunk = String::new();
+            for log_entry in page.logs.iter().skip(skipped).take(cursor.remaining) {
+                chunk.push_str(&csv_row(log_entry)?);
+                cursor.remaining -= 1;
+            }
+            cursor.skip -= skipped;
+            cursor.pit_id = page.pit_id;
+            cursor.search_after = page.search_after;
+            Ok(Some((Bytes::from(chunk), cursor)))
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv")
+        .insert_header(("Content-Disposition", "attachment; filename=\"logs.csv\""))
+        .streaming(header.chain(rows)))
+}
+
+/// Paging state of `/logs/export`.
+struct ExportCursor {
+    pit_id: String,
+    /// Sort values of the last read log, `None` before the first page
+    search_after: Option<Value>,
+    /// Logs still to skip for the requested offset
+    skip: usize,
+    /// Logs still to export
+    remaining: usize,
+}
+
+/// Closes the point in time of a finished export. Failures are only logged, since it expires anyway.
+async fn close_export(data: &AppState, pit_id: &str) {
+    if let Err(e) = close_point_in_time(&data.client, pit_id).await {
+        log::warn!("Closing point in time of export failed: {}", e.additional_information);
+    }
+}
+
+/// Endpoint returning the number of sensor logs per level, optionally within a `from`/`to` range.
+#[get("/logs/stats")]
+async fn log_stats(
+    _auth: Authenticated,
+    data: web::Data<AppState>,
+    query: web::Query<LevelStatsQuery>,
+) -> ActixResult<HttpResponse> {
+    let counts = data
+        .breaker
+        .call(aggregate_levels(&data.indexing.search_index(&data.index_name), &data.client, &query))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(counts))
+}
+
+/// Endpoint listing the known devices with their number of logs, e.g. for a device filter picker.
+///
+/// At most `MAX_DEVICES` devices are returned, the ones with most logs first.
+#[get("/devices")]
+async fn devices(_auth: Authenticated, data: web::Data<AppState>) -> ActixResult<HttpResponse> {
+    let devices = data
+        .breaker
+        .call(list_devices(&data.indexing.search_index(&data.index_name), &data.client, MAX_DEVICES))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "devices": devices })))
 }
 
 #[get("/logs/search")]
 async fn search_logs_endpoint(
+    _auth: Authenticated,
     data: web::Data<AppState>,
     query: web::Query<SearchQuery>,
 ) -> ActixResult<HttpResponse> {
-    let logs = search_logs(&data.index_name, &data.client, &query)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    let logs = data
+        .breaker
+        .call(search_logs(&data.indexing.search_index(&data.index_name), &data.client, &query))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "logs": logs })))
 }
 
 #[get("/container-logs")]
 async fn get_container_logs(
+    _auth: Authenticated,
     data: web::Data<AppState>,
     query: web::Query<ContainerLogQuery>,
 ) -> ActixResult<HttpResponse> {
-    let logs = query_container_logs(&data.container_logs_index_name, &data.client, &query)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    let logs = data
+        .breaker
+        .call(query_container_logs(
+            &data.indexing.search_index(&data.container_logs_index_name),
+            &data.client,
+            &query,
+        ))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "logs": logs })))
 }
 
 #[get("/container-logs/search")]
 async fn search_container_logs_endpoint(
+    _auth: Authenticated,
     data: web::Data<AppState>,
     query: web::Query<ContainerSearchQuery>,
 ) -> ActixResult<HttpResponse> {
-    let logs = search_container_logs(&data.container_logs_index_name, &data.client, &query)
-        .await
-        .map_err(ErrorInternalServerError)?;
+    let logs = data
+        .breaker
+        .call(search_container_logs(
+            &data.indexing.search_index(&data.container_logs_index_name),
+            &data.client,
+            &query,
+        ))
+        .await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "logs": logs })))
 }
@@ -130,6 +448,9 @@ async fn main() -> std::io::Result<()> {
     if env::var("DEPLOYMENT").unwrap_or_default() != "PROD" {
         dotenv().ok();
     }
+    // Initialized before startup so connection attempts are logged
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
     let client: Elasticsearch = create_client().unwrap();
     let index_name: String = env::var("INDEX_NAME")
         .map_err(|_| ServerError {
@@ -149,43 +470,256 @@ async fn main() -> std::io::Result<()> {
         })
         .unwrap();
 
-    // Creates a index if missing, otherwise returns
-    create_logs_index(&index_name, &client, create_log_mapping())
+    // Elastic search might not be ready yet (e.g. docker compose startup) => wait before creating indices
+    let startup_retries: u32 = env::var("STARTUP_RETRIES")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .unwrap_or(10);
+    let startup_delay_secs: u64 = env::var("STARTUP_DELAY_SECS")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse()
+        .unwrap_or(5);
+    wait_for_elastic(&client, startup_retries, Duration::from_secs(startup_delay_secs))
         .await
         .unwrap();
 
-    create_logs_index(
-        &container_logs_index_name,
-        &client,
-        create_container_log_mapping(),
-    )
-    .await
-    .unwrap();
+    // Opt-in retention => the policy has to exist before indices referencing it are created
+    let ilm = IlmConfig::from_env();
+    if let Some(ilm) = &ilm {
+        log::info!(
+            "{}",
+            ensure_ilm_policy(&client, &ilm.policy_name, ilm).await.unwrap()
+        );
+    }
+
+    let indexing = IndexingConfig::from_env();
+    if indexing.time_based_indices {
+        // Daily indices are created on first write => register the mapping as template instead
+        create_index_template(&index_name, &client, create_log_mapping())
+            .await
+            .unwrap();
+
+        create_index_template(
+            &container_logs_index_name,
+            &client,
+            create_container_log_mapping(),
+        )
+        .await
+        .unwrap();
+    } else {
+        // Creates a index if missing, otherwise returns
+        create_logs_index(&index_name, &client, create_log_mapping())
+            .await
+            .unwrap();
+
+        create_logs_index(
+            &container_logs_index_name,
+            &client,
+            create_container_log_mapping(),
+        )
+        .await
+        .unwrap();
+    }
+
+    // Indices from before ILM was enabled don't get the policy through their settings
+    if let Some(ilm) = &ilm {
+        for index in [&index_name, &container_logs_index_name] {
+            let pattern = if indexing.time_based_indices {
+                format!("{}-*", index)
+            } else {
+                index.clone()
+            };
+            log::info!(
+                "{}",
+                attach_ilm_policy(&pattern, &client, &ilm.policy_name).await.unwrap()
+            );
+        }
+    }
+
+    // Defense in depth => requests reaching an instance directly are checked like behind the load balancer
+    let api_key = ApiKey::from_env().expect("Invalid api key configuration");
+    if !api_key.is_enabled() {
+        log::warn!("SECRET_API_KEY is not set, data endpoints accept requests without api key");
+    }
 
     let state = web::Data::new(AppState {
         client: client.clone(),
         host_id: Uuid::new_v4(),
         index_name,
         container_logs_index_name,
+        breaker: CircuitBreaker::from_env(),
+        indexing,
+        // Opt-in => without REDACTION_PATTERNS log entries are indexed unchanged
+        redactor: Redactor::from_env().expect("REDACTION_PATTERNS contains an invalid regex"),
+        device_limiter: DeviceRateLimiter::from_env(),
+        ops: OpsCounters::default(),
+        api_key,
     });
 
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+    // Dogfooding => the api indexes summaries of its own traffic, viewable like any other index
+    let self_logging: bool = env::var("SELF_LOGGING")
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(false);
+    if self_logging {
+        let ops_index_name =
+            env::var("OPS_INDEX_NAME").unwrap_or_else(|_| "forwarder_ops".to_string());
+        let interval_secs: u64 = env::var("SELF_LOGGING_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .unwrap_or(60)
+            .max(1);
+
+        create_logs_index(&ops_index_name, &client, create_ops_mapping())
+            .await
+            .unwrap();
+        log::info!("Self logging into {} every {}s", ops_index_name, interval_secs);
+        actix_web::rt::spawn(run_self_logging(
+            state.clone(),
+            ops_index_name,
+            Duration::from_secs(interval_secs),
+        ));
+    }
+
+    // Lower values protect against connection floods but cap throughput under many concurrent clients.
+    // The server binds plain HTTP (TLS terminates at the load balancer) => no handshake rate limit here.
+    let max_connections = parse_max_connections(env::var("MAX_CONNECTIONS").ok()).expect("Invalid connection limit");
+    log::info!("Accepting at most {} connections per worker", max_connections);
+
     HttpServer::new(move || {
+        let ops_state = state.clone();
         App::new()
             .app_data(state.clone())
             .service(send_log)
+            .service(send_logs_bulk)
             .service(who_are_you)
+            .service(health)
             .service(elastic_node_info)
             .service(send_container_log)
             .service(get_logs)
             .service(search_logs_endpoint)
+            .service(log_stats)
+            .service(export_logs)
+            .service(devices)
             .service(get_container_logs)
             .service(search_container_logs_endpoint)
+            // Counts every response for the self logging summaries
+            .wrap_fn(move |req, srv| {
+                let ops_state = ops_state.clone();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await?;
+                    ops_state.ops.record(response.status());
+                    Ok(response)
+                }
+            })
             .wrap(Logger::default())
     })
+    .max_connections(max_connections)
     .bind(("0.0.0.0", 8080))?
     .run()
     .await?;
 
     Ok(())
 }
+
+/// Parses `MAX_CONNECTIONS`, the limit of concurrent connections per worker thread.
+///
+/// # Parameters
+/// * `raw` - Value of the variable, `None` if unset
+///
+/// # Returns
+/// * `Ok(usize)` - Configured limit, the actix default of 25000 if unset
+/// * `Err(String)` - If the value is no positive integer
+fn parse_max_connections(raw: Option<String>) -> Result<usize, String> {
+    let Some(raw) = raw else {
+        return Ok(25000);
+    };
+    raw.trim()
+        .parse()
+        .ok()
+        .filter(|max| *max > 0)
+        .ok_or_else(|| format!("MAX_CONNECTIONS must be a positive integer, got '{}'", raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_max_connections_defaults_to_actix_default() {
+        assert_eq!(parse_max_connections(None), Ok(25000));
+        assert_eq!(parse_max_connections(Some(" 512 ".to_string())), Ok(512));
+    }
+
+    #[test]
+    fn parse_max_connections_rejects_invalid_values() {
+        assert!(parse_max_connections(Some("0".to_string())).is_err());
+        assert!(parse_max_connections(Some("-1".to_string())).is_err());
+        assert!(parse_max_connections(Some("many".to_string())).is_err());
+    }
+
+    #[test]
+    fn health_status_code_fails_only_red_clusters() {
+        assert_eq!(health_status_code("green"), StatusCode::OK);
+        assert_eq!(health_status_code("yellow"), StatusCode::OK);
+        assert_eq!(health_status_code("red"), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[actix_web::test]
+    async fn self_logging_indexes_summary_without_counting_itself() {
+        use wiremock::matchers::path_regex;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path_regex("^/forwarder_ops/_doc/"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .insert_header("X-Elastic-Product", "Elasticsearch")
+                    .set_body_json(serde_json::json!({ "_index": "forwarder_ops", "result": "created" })),
+            )
+            .mount(&server)
+            .await;
+        // Credentials are read from the environment, the mock doesn't check them
+        // SAFETY: only read by client construction, which all tests do with these values
+        unsafe {
+            env::set_var("ELASTIC_USERNAME", "elastic");
+            env::set_var("ELASTIC_PASSWORD", "changeme");
+        }
+        let state = web::Data::new(AppState {
+            client: elastic::create_client_for_url(&server.uri()).unwrap(),
+            host_id: Uuid::new_v4(),
+            index_name: "sensor_logs".to_string(),
+            container_logs_index_name: "container_logs".to_string(),
+            breaker: CircuitBreaker::new(0, Duration::from_secs(30)),
+            indexing: IndexingConfig::from_env(),
+            redactor: Redactor::from_env().unwrap(),
+            device_limiter: DeviceRateLimiter::new(0),
+            ops: OpsCounters::default(),
+            api_key: ApiKey::from_env().unwrap(),
+        });
+        state.ops.record(StatusCode::OK);
+        state.ops.record(StatusCode::BAD_REQUEST);
+
+        let task = actix_web::rt::spawn(run_self_logging(
+            state.clone(),
+            "forwarder_ops".to_string(),
+            Duration::from_millis(20),
+        ));
+        let mut requests = Vec::new();
+        for _ in 0..200 {
+            requests = server.received_requests().await.unwrap();
+            if !requests.is_empty() {
+                break;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+
+        let document: Value = serde_json::from_slice(&requests.first().expect("No ops summary indexed").body).unwrap();
+        assert_eq!(document["requests"], 2);
+        assert_eq!(document["errors"], 1);
+        // The summary is written to elastic search directly instead of through the api => it isn't counted as request
+        assert_eq!(state.ops.take(), (0, 0));
+    }
+}
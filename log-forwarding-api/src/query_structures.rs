@@ -1,12 +1,37 @@
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LogQuery {
     pub limit: Option<usize>,
     pub offset: Option<usize>,
     pub level: Option<String>,
     pub device: Option<String>,
+    /// Match `device` regardless of casing (default: false)
+    pub case_insensitive: Option<bool>,
+    /// Origin of the logs, e.g. `synthetic` or `real` (logs without source)
+    pub source: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Inclusive bounds of the temperature, e.g. `temp_min=30` for all logs above 30°C
+    pub temp_min: Option<f64>,
+    pub temp_max: Option<f64>,
+    /// Inclusive bounds of the humidity
+    pub humidity_min: Option<f64>,
+    pub humidity_max: Option<f64>,
+    /// Comma-separated sort keys, e.g. `level:desc,timestamp:desc` (takes precedence over `sort_field`/`sort_order`)
+    pub sort: Option<String>,
+    /// Single sort key, e.g. `level` (default: `timestamp`)
+    pub sort_field: Option<String>,
+    /// `asc` or `desc` (default: `desc`)
+    pub sort_order: Option<String>,
+    /// Return the `msg` fields as top-level `msg_*` fields instead of a nested object
+    pub flatten: Option<bool>,
+}
+
+/// Time range of `/logs/stats`, both bounds are optional.
+#[derive(Debug, Deserialize)]
+pub struct LevelStatsQuery {
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
 }
@@ -23,8 +48,14 @@ pub struct ContainerLogQuery {
     pub limit: Option<usize>,
     pub offset: Option<usize>,
     pub container_name: Option<String>,
+    /// Match `container_name` regardless of casing (default: false)
+    pub case_insensitive: Option<bool>,
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
+    /// Single sort key, e.g. `container_name` (default: `timestamp`)
+    pub sort_field: Option<String>,
+    /// `asc` or `desc` (default: `desc`)
+    pub sort_order: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,182 @@
+use crate::server_error::ServerError;
+use actix_web::http::StatusCode;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Length of the sliding window the per device limit applies to
+const WINDOW: Duration = Duration::from_secs(1);
+/// Devices are client supplied => longer names are cut, so a single key can't hold much memory
+const MAX_DEVICE_KEY_LEN: usize = 128;
+
+/// Per device sliding window rate limiter for ingested sensor logs.
+///
+/// Keeps the arrival times of the last accepted logs per device. A log is rejected with 429
+/// if its device already sent `limit` logs within the last second, other devices are unaffected.
+/// The map is shared between actix workers via `AppState`. Devices without arrivals in the
+/// window are swept once per window => the map only holds the devices active in the last second.
+///
+/// # Examples
+/// ```
+/// let limiter = DeviceRateLimiter::from_env();
+/// limiter.check(log_entry.msg.device())?;
+/// ```
+pub struct DeviceRateLimiter {
+    limit: usize,
+    arrivals: Mutex<Arrivals>,
+}
+
+/// Arrival times per device and the time of the last sweep over all devices
+struct Arrivals {
+    devices: HashMap<String, VecDeque<Instant>>,
+    last_sweep: Instant,
+}
+
+impl DeviceRateLimiter {
+    /// Creates a limiter allowing `limit` logs per device and second. A limit of 0 disables it.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            arrivals: Mutex::new(Arrivals {
+                devices: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Creates a limiter from the environment.
+    ///
+    /// # Environment Variables
+    /// * `PER_DEVICE_RATE_LIMIT` - Maximum logs per device and second (default: 0 => disabled)
+    pub fn from_env() -> Self {
+        Self::new(
+            env::var("PER_DEVICE_RATE_LIMIT")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+        )
+    }
+
+    /// Registers a log of the given device if it is within the limit.
+    ///
+    /// # Parameters
+    /// * `device` - Device the log originates from
+    ///
+    /// # Returns
+    /// * `Ok(())` - Log is accepted and counted
+    /// * `Err(ServerError)` - 429 if the device exceeded the limit within the current window
+    pub fn check(&self, device: &str) -> Result<(), ServerError> {
+        self.check_at(device, Instant::now())
+    }
+
+    /// [`DeviceRateLimiter::check`] at the given time => the window can be tested without sleeping.
+    fn check_at(&self, device: &str, now: Instant) -> Result<(), ServerError> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+
+        let mut arrivals = self.arrivals.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Devices that stopped sending would otherwise keep their entry forever
+        if now.saturating_duration_since(arrivals.last_sweep) >= WINDOW {
+            arrivals.devices.retain(|_, device_arrivals| {
+                device_arrivals
+                    .back()
+                    .is_some_and(|arrival| now.saturating_duration_since(*arrival) < WINDOW)
+            });
+            arrivals.last_sweep = now;
+        }
+
+        let key = match device.char_indices().nth(MAX_DEVICE_KEY_LEN) {
+            Some((end, _)) => &device[..end],
+            None => device,
+        };
+        let device_arrivals = arrivals.devices.entry(key.to_string()).or_default();
+
+        // Drop arrivals that left the sliding window
+        while device_arrivals
+            .front()
+            .is_some_and(|arrival| now.saturating_duration_since(*arrival) >= WINDOW)
+        {
+            device_arrivals.pop_front();
+        }
+
+        if device_arrivals.len() >= self.limit {
+            return Err(ServerError {
+                code: StatusCode::TOO_MANY_REQUESTS,
+                message: format!("Device '{}' exceeded the rate limit", device),
+                additional_information: format!("At most {} logs per second are accepted per device", self.limit),
+            });
+        }
+
+        device_arrivals.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_rejects_logs_above_limit_within_window() {
+        let limiter = DeviceRateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("Arduino0", start).is_ok());
+        assert!(limiter.check_at("Arduino0", start + Duration::from_millis(100)).is_ok());
+        let error = limiter
+            .check_at("Arduino0", start + Duration::from_millis(200))
+            .unwrap_err();
+        assert_eq!(error.code, StatusCode::TOO_MANY_REQUESTS);
+        // Other devices have their own window
+        assert!(limiter.check_at("Arduino1", start + Duration::from_millis(200)).is_ok());
+    }
+
+    #[test]
+    fn check_accepts_logs_again_once_arrivals_left_window() {
+        let limiter = DeviceRateLimiter::new(1);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("Arduino0", start).is_ok());
+        assert!(limiter.check_at("Arduino0", start + Duration::from_millis(999)).is_err());
+        assert!(limiter.check_at("Arduino0", start + WINDOW).is_ok());
+    }
+
+    #[test]
+    fn check_is_disabled_with_limit_zero() {
+        let limiter = DeviceRateLimiter::new(0);
+        let now = Instant::now();
+
+        assert!((0..100).all(|_| limiter.check_at("Arduino0", now).is_ok()));
+        assert!(limiter.arrivals.lock().unwrap().devices.is_empty());
+    }
+
+    #[test]
+    fn check_sweeps_inactive_devices() {
+        let limiter = DeviceRateLimiter::new(5);
+        let start = Instant::now();
+        for device in 0..100 {
+            limiter.check_at(&format!("device-{}", device), start).unwrap();
+        }
+
+        limiter.check_at("Arduino0", start + 2 * WINDOW).unwrap();
+
+        let arrivals = limiter.arrivals.lock().unwrap();
+        assert_eq!(arrivals.devices.len(), 1);
+        assert!(arrivals.devices.contains_key("Arduino0"));
+    }
+
+    #[test]
+    fn check_caps_device_key_length() {
+        let limiter = DeviceRateLimiter::new(1);
+        let now = Instant::now();
+        let long_device = "ä".repeat(10 * MAX_DEVICE_KEY_LEN);
+
+        limiter.check_at(&long_device, now).unwrap();
+
+        let arrivals = limiter.arrivals.lock().unwrap();
+        let key = arrivals.devices.keys().next().unwrap();
+        assert_eq!(key.chars().count(), MAX_DEVICE_KEY_LEN);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::log_entry::ElasticLogDocument;
+use regex::Regex;
+use std::env;
+
+/// Built-in pattern for email addresses, selectable via `email` in REDACTION_PATTERNS
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+/// Built-in pattern for credit card like digit sequences, selectable via `credit_card` in REDACTION_PATTERNS
+const CREDIT_CARD_PATTERN: &str = r"\b(?:\d[ -]?){12,18}\d\b";
+
+/// Masks sensitive data within log messages before they get indexed.
+///
+/// Patterns are compiled once at startup. Without configured patterns redaction is disabled
+/// and log entries are indexed unchanged.
+pub struct Redactor {
+    patterns: Vec<Regex>,
+    placeholder: String,
+}
+
+impl Redactor {
+    /// Creates a redactor from the environment.
+    ///
+    /// # Environment Variables
+    /// * `REDACTION_PATTERNS` - `;` separated list of regexes or built-in names (`email`, `credit_card`) (default: none => disabled)
+    /// * `REDACTION_PLACEHOLDER` - Replacement for matches (default: `[REDACTED]`)
+    ///
+    /// # Returns
+    /// * `Ok(Redactor)` - Redactor with all patterns compiled
+    /// * `Err(regex::Error)` - If a custom pattern is not a valid regex
+    pub fn from_env() -> Result<Self, regex::Error> {
+        let raw_patterns = env::var("REDACTION_PATTERNS").unwrap_or_default();
+        let placeholder =
+            env::var("REDACTION_PLACEHOLDER").unwrap_or_else(|_| "[REDACTED]".to_string());
+
+        let patterns = raw_patterns
+            .split(';')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| match pattern {
+                "email" => Regex::new(EMAIL_PATTERN),
+                "credit_card" => Regex::new(CREDIT_CARD_PATTERN),
+                custom => Regex::new(custom),
+            })
+            .collect::<Result<Vec<Regex>, regex::Error>>()?;
+
+        if !patterns.is_empty() {
+            log::info!("Redacting {} pattern(s) on ingest", patterns.len());
+        }
+
+        Ok(Self {
+            patterns,
+            placeholder,
+        })
+    }
+
+    /// Replaces all pattern matches within the text by the placeholder.
+    ///
+    /// # Parameters
+    /// * `text` - Message text to redact
+    ///
+    /// # Returns
+    /// * `String` - Text with every match replaced, unchanged if nothing matches
+    pub fn redact(&self, text: &str) -> String {
+        self.patterns
+            .iter()
+            .fold(text.to_string(), |redacted, pattern| {
+                pattern
+                    .replace_all(&redacted, self.placeholder.as_str())
+                    .into_owned()
+            })
+    }
+
+    /// Redacts the message field of a log entry in place.
+    ///
+    /// # Parameters
+    /// * `log_entry` - Log entry whose message (`msg.msg` / `log_message`) gets redacted
+    pub fn apply<T: ElasticLogDocument>(&self, log_entry: &mut T) {
+        if self.patterns.is_empty() {
+            return;
+        }
+        let message = log_entry.message_mut();
+        *message = self.redact(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(patterns: &[&str]) -> Redactor {
+        Redactor {
+            patterns: patterns.iter().map(|pattern| Regex::new(pattern).unwrap()).collect(),
+            placeholder: "[REDACTED]".to_string(),
+        }
+    }
+
+    #[test]
+    fn redact_masks_builtin_patterns() {
+        let redactor = redactor(&[EMAIL_PATTERN, CREDIT_CARD_PATTERN]);
+
+        assert_eq!(
+            redactor.redact("login of jane.doe@example.com paid with 4111 1111 1111 1111"),
+            "login of [REDACTED] paid with [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redact_masks_every_match_of_custom_patterns() {
+        let redactor = redactor(&[r"token=\w+"]);
+
+        assert_eq!(redactor.redact("token=abc and token=def"), "[REDACTED] and [REDACTED]");
+    }
+
+    #[test]
+    fn redact_keeps_text_without_matches() {
+        assert_eq!(redactor(&[EMAIL_PATTERN]).redact("temperature 21.5"), "temperature 21.5");
+        assert_eq!(redactor(&[]).redact("jane.doe@example.com"), "jane.doe@example.com");
+    }
+}
@@ -0,0 +1,126 @@
+use crate::log_entry::ElasticLogDocument;
+use actix_web::http::StatusCode;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Request counters of this api instance, aggregated between two self logging intervals.
+///
+/// Shared between actix workers via `AppState` and updated by a middleware for every response.
+#[derive(Default)]
+pub struct OpsCounters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl OpsCounters {
+    /// Counts a handled request, responses with 4xx / 5xx status count as error.
+    pub fn record(&self, status: StatusCode) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if status.is_client_error() || status.is_server_error() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the counters of the current interval and starts the next one.
+    ///
+    /// # Returns
+    /// * `(u64, u64)` - Requests and errors since the last call
+    pub fn take(&self) -> (u64, u64) {
+        (
+            self.requests.swap(0, Ordering::Relaxed),
+            self.errors.swap(0, Ordering::Relaxed),
+        )
+    }
+}
+
+/// Operational summary of one api instance over one interval, indexed into the ops index.
+#[derive(Debug, Serialize)]
+pub struct OpsDocument {
+    pub timestamp: DateTime<Utc>,
+    pub instance_id: String,
+    pub interval_secs: u64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Share of failed requests (0.0 - 1.0), 0 without requests
+    pub error_rate: f64,
+    pub message: String,
+}
+
+impl OpsDocument {
+    /// Creates the summary document of an interval.
+    ///
+    /// # Parameters
+    /// * `instance_id` - Container name or startup uuid of this api instance
+    /// * `interval_secs` - Length of the summarized interval
+    /// * `(requests, errors)` - Counters taken from [`OpsCounters::take`]
+    pub fn new(instance_id: String, interval_secs: u64, (requests, errors): (u64, u64)) -> Self {
+        let error_rate = if requests == 0 {
+            0.0
+        } else {
+            errors as f64 / requests as f64
+        };
+
+        Self {
+            timestamp: Utc::now(),
+            message: format!(
+                "{} requests, {} errors ({:.1}%) in the last {}s",
+                requests,
+                errors,
+                error_rate * 100.0,
+                interval_secs
+            ),
+            instance_id,
+            interval_secs,
+            requests,
+            errors,
+            error_rate,
+        }
+    }
+}
+
+impl ElasticLogDocument for OpsDocument {
+    fn to_document_json(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn message_mut(&mut self) -> &mut String {
+        &mut self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops_counters_count_errors_and_reset_on_take() {
+        let counters = OpsCounters::default();
+
+        counters.record(StatusCode::OK);
+        counters.record(StatusCode::CREATED);
+        counters.record(StatusCode::UNAUTHORIZED);
+        counters.record(StatusCode::GATEWAY_TIMEOUT);
+
+        assert_eq!(counters.take(), (4, 2));
+        assert_eq!(counters.take(), (0, 0));
+    }
+
+    #[test]
+    fn ops_document_summarizes_interval() {
+        let document = OpsDocument::new("api-1".to_string(), 60, (8, 2));
+
+        assert_eq!(document.error_rate, 0.25);
+        assert_eq!(document.message, "8 requests, 2 errors (25.0%) in the last 60s");
+        let json = document.to_document_json().unwrap();
+        assert_eq!(json["instance_id"], "api-1");
+        assert_eq!(json["requests"], 8);
+        // Without requests there is no error rate to divide by
+        assert_eq!(OpsDocument::new("api-1".to_string(), 60, (0, 0)).error_rate, 0.0);
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{
     HttpResponse, error,
-    http::{StatusCode, header::ContentType},
+    http::{StatusCode, header, header::ContentType},
 };
 use derive_more::derive::{Display, Error};
 
@@ -14,9 +14,14 @@ pub struct ServerError {
 
 impl error::ResponseError for ServerError {
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code())
-            .insert_header(ContentType::html())
-            .body(format!("Message: {}", self.message))
+        let mut response = HttpResponse::build(self.status_code());
+        response.insert_header(ContentType::json());
+        // Rate limits use a 1s window => clients may retry after a second
+        if self.code == StatusCode::TOO_MANY_REQUESTS {
+            response.insert_header((header::RETRY_AFTER, "1"));
+        }
+        // Only the message => additional information (e.g. elastic search internals) isn't exposed to clients
+        response.body(serde_json::json!({ "error": self.message }).to_string())
     }
 
     fn status_code(&self) -> StatusCode {
@@ -0,0 +1,553 @@
+//! Runs the elastic search functions against a mocked cluster.
+//!
+//! Every test starts its own wiremock server and points a client at it via `create_client_for_url`,
+//! so success and error responses of elastic search can be covered without a running cluster.
+use actix_web::http::StatusCode;
+use elasticsearch::Elasticsearch;
+use log_forwarding_api::elastic::{
+    BulkErrorMode, IndexingConfig, create_client_for_url, create_log_mapping, create_logs_index, open_point_in_time, query_logs,
+    query_logs_after, send_document, send_documents_bulk, wait_for_elastic,
+};
+use log_forwarding_api::log_entry::LogEntry;
+use log_forwarding_api::query_structures::LogQuery;
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const INDEX: &str = "sensor_logs";
+
+fn client_for(server: &MockServer) -> Elasticsearch {
+    // Credentials are read from the environment, the mock doesn't check them
+    // SAFETY: every test sets the same values, so concurrent tests read consistent data
+    unsafe {
+        std::env::set_var("ELASTIC_USERNAME", "elastic");
+        std::env::set_var("ELASTIC_PASSWORD", "changeme");
+    }
+    create_client_for_url(&server.uri()).expect("Client for the mock server")
+}
+
+/// Response of the mocked cluster, marked as elastic search like a real node does.
+fn es_response(status: u16, body: Value) -> ResponseTemplate {
+    ResponseTemplate::new(status)
+        .insert_header("X-Elastic-Product", "Elasticsearch")
+        .set_body_json(body)
+}
+
+fn es_error(status: u16, kind: &str) -> ResponseTemplate {
+    es_response(status, json!({ "error": { "type": kind, "reason": kind }, "status": status }))
+}
+
+/// Fast retries and a terse response => tests neither wait on backoff nor depend on DEPLOYMENT.
+fn indexing_config(max_attempts: u32) -> IndexingConfig {
+    IndexingConfig {
+        verbose_response: false,
+        max_log_age: None,
+        index_max_attempts: max_attempts,
+        index_retry_base_delay: Duration::from_millis(1),
+        ..IndexingConfig::from_env()
+    }
+}
+
+fn log_entry() -> LogEntry {
+    serde_json::from_value(json!({
+        "timestamp": "2025-06-01T12:00:00Z",
+        "level": "INFO",
+        "temperature": 21.5,
+        "humidity": 40.0,
+        "msg": {
+            "device": "Arduino0",
+            "msg": "INFO: all values in range",
+            "exceeded_values": [false, false]
+        }
+    }))
+    .unwrap()
+}
+
+fn log_query() -> LogQuery {
+    serde_json::from_value(json!({})).unwrap()
+}
+
+#[actix_web::test]
+async fn create_logs_index_creates_missing_index() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path(format!("/{}", INDEX)))
+        .respond_with(es_response(404, json!({})))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path(format!("/{}", INDEX)))
+        .respond_with(es_response(200, json!({ "acknowledged": true, "index": INDEX })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = create_logs_index(INDEX, &client_for(&server), create_log_mapping()).await;
+
+    assert!(result.unwrap().contains("created"));
+}
+
+#[actix_web::test]
+async fn create_logs_index_skips_existing_index() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path(format!("/{}", INDEX)))
+        .respond_with(es_response(200, json!({})))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .respond_with(es_response(200, json!({ "acknowledged": true })))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let result = create_logs_index(INDEX, &client_for(&server), create_log_mapping()).await;
+
+    assert!(result.unwrap().contains("already exists"));
+}
+
+#[actix_web::test]
+async fn create_logs_index_reports_rejected_mapping() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(es_response(404, json!({})))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .respond_with(es_error(400, "mapper_parsing_exception"))
+        .mount(&server)
+        .await;
+
+    let error = create_logs_index(INDEX, &client_for(&server), create_log_mapping())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(error.additional_information.contains("mapper_parsing_exception"));
+}
+
+#[actix_web::test]
+async fn send_document_indexes_entry() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3)).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+}
+
+#[actix_web::test]
+async fn send_document_does_not_retry_rejected_document() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(400, "document_parsing_exception"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn send_document_retries_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(503, "unavailable_shards_exception"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3)).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+}
+
+#[actix_web::test]
+async fn send_document_counts_conflict_on_retry_as_indexed() {
+    let server = MockServer::start().await;
+    // The first attempt reached elastic search, but its response got lost
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(503, "unavailable_shards_exception"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(409, "version_conflict_engine_exception"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3)).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+}
+
+#[actix_web::test]
+async fn send_document_reuses_document_id_on_retry() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(503, "unavailable_shards_exception"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3))
+        .await
+        .unwrap_err();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|request| request.url.path() == requests[0].url.path()));
+    assert!(requests[0].url.query().is_some_and(|query| query.contains("op_type=create")));
+}
+
+#[actix_web::test]
+async fn send_document_gives_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(503, "unavailable_shards_exception"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let error = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(2))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn query_logs_returns_entries_and_total() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/{}/_search", INDEX)))
+        .respond_with(es_response(
+            200,
+            json!({
+                "hits": {
+                    "total": { "value": 42, "relation": "eq" },
+                    "hits": [{ "_index": INDEX, "_id": "1", "_source": serde_json::to_value(log_entry()).unwrap() }]
+                }
+            }),
+        ))
+        .mount(&server)
+        .await;
+
+    let (logs, total) = query_logs(INDEX, &client_for(&server), &log_query()).await.unwrap();
+
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].msg.device(), "Arduino0");
+    assert_eq!(total, 42);
+}
+
+#[actix_web::test]
+async fn query_logs_reports_search_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/{}/_search", INDEX)))
+        .respond_with(es_error(500, "search_phase_execution_exception"))
+        .mount(&server)
+        .await;
+
+    let error = query_logs(INDEX, &client_for(&server), &log_query()).await.unwrap_err();
+
+    assert_eq!(error.code, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn query_logs_rejects_invalid_sort_without_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(es_response(200, json!({ "hits": { "hits": [] } })))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let query = LogQuery {
+        sort: Some("msg.msg:asc".to_string()),
+        ..log_query()
+    };
+
+    let error = query_logs(INDEX, &client_for(&server), &query).await.unwrap_err();
+
+    assert_eq!(error.code, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn export_pages_continue_after_last_sort_values() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/{}/_pit", INDEX)))
+        .respond_with(es_response(200, json!({ "id": "pit-1" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/_search"))
+        .and(body_partial_json(json!({ "pit": { "id": "pit-1" }, "search_after": [1748779200000u64, "a", 7] })))
+        .respond_with(es_response(
+            200,
+            json!({
+                "pit_id": "pit-2",
+                "hits": {
+                    "hits": [{ "_index": INDEX, "_source": serde_json::to_value(log_entry()).unwrap(), "sort": [1748779200000u64, "b", 8] }]
+                }
+            }),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = client_for(&server);
+
+    let pit_id = open_point_in_time(INDEX, &client).await.unwrap();
+    let page = query_logs_after(&client, &log_query(), &pit_id, Some(&json!([1748779200000u64, "a", 7])), 1000)
+        .await
+        .unwrap();
+
+    assert_eq!(page.logs.len(), 1);
+    assert_eq!(page.search_after, Some(json!([1748779200000u64, "b", 8])));
+    assert_eq!(page.pit_id, "pit-2");
+}
+
+#[actix_web::test]
+async fn wait_for_elastic_retries_until_cluster_is_reachable() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/"))
+        .respond_with(es_response(503, json!({})))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/"))
+        .respond_with(es_response(200, json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = wait_for_elastic(&client_for(&server), 5, Duration::from_millis(1)).await;
+
+    assert!(result.is_ok());
+}
+
+#[actix_web::test]
+async fn wait_for_elastic_gives_up_after_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/"))
+        .respond_with(es_response(503, json!({})))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let error = wait_for_elastic(&client_for(&server), 2, Duration::from_millis(1))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, StatusCode::GATEWAY_TIMEOUT);
+    assert!(error.additional_information.contains("503"));
+}
+
+#[actix_web::test]
+async fn send_document_attaches_configured_pipeline() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .and(query_param("pipeline", "geoip"))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = IndexingConfig {
+        pipeline: Some("geoip".to_string()),
+        ..indexing_config(1)
+    };
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &config).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+}
+
+#[actix_web::test]
+async fn send_document_omits_pipeline_by_default() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = IndexingConfig {
+        pipeline: None,
+        ..indexing_config(1)
+    };
+
+    send_document(INDEX, &client_for(&server), &log_entry(), &config).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].url.query_pairs().all(|(name, _)| name != "pipeline"));
+}
+
+#[actix_web::test]
+async fn send_document_indexes_schema_version() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .and(body_partial_json(json!({ "schema_version": 7 })))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = IndexingConfig {
+        schema_version: 7,
+        ..indexing_config(1)
+    };
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &config).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+}
+
+#[actix_web::test]
+async fn send_documents_bulk_reports_failed_positions() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/_bulk"))
+        .respond_with(es_response(
+            200,
+            json!({
+                "errors": true,
+                "items": [
+                    { "index": { "_index": INDEX, "status": 201 } },
+                    { "index": { "_index": INDEX, "status": 400, "error": { "type": "document_parsing_exception", "reason": "failed to parse field [temperature]" } } },
+                    { "index": { "_index": INDEX, "status": 201 } }
+                ]
+            }),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let log_entries = [(0, log_entry()), (1, log_entry()), (2, log_entry())];
+
+    let result = send_documents_bulk(INDEX, &client_for(&server), &log_entries, &indexing_config(1))
+        .await
+        .unwrap();
+
+    assert_eq!((result.indexed, result.failed), (2, 1));
+    assert_eq!(result.errors[0].position, 1);
+    assert_eq!(result.errors[0].error, "failed to parse field [temperature]");
+    assert_eq!(result.status(BulkErrorMode::Ok), StatusCode::OK);
+    assert_eq!(result.status(BulkErrorMode::MultiStatus), StatusCode::MULTI_STATUS);
+}
+
+#[actix_web::test]
+async fn send_document_retry_is_created_once_and_succeeds() {
+    let server = MockServer::start().await;
+    // The first attempt creates the document, but its response is lost (503)
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(503, "unavailable_shards_exception"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The retry under the same id finds the created document
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(409, "version_conflict_engine_exception"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3)).await;
+
+    assert_eq!(result.unwrap(), "indexed");
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].url.path(), requests[1].url.path());
+}
+
+#[actix_web::test]
+async fn send_document_reports_conflict_on_first_attempt() {
+    let server = MockServer::start().await;
+    // Without a preceding attempt the id belongs to another document
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_error(409, "version_conflict_engine_exception"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = send_document(INDEX, &client_for(&server), &log_entry(), &indexing_config(3))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn send_document_responds_terse_or_verbose() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let client = client_for(&server);
+
+    let terse = send_document(INDEX, &client, &log_entry(), &indexing_config(1)).await.unwrap();
+    let verbose_config = IndexingConfig {
+        verbose_response: true,
+        ..indexing_config(1)
+    };
+    let verbose = send_document(INDEX, &client, &log_entry(), &verbose_config).await.unwrap();
+
+    assert_eq!(terse, "indexed");
+    // Verbose => the pretty printed entry follows the prefix
+    let entry = verbose.strip_prefix("Log entry inserted: ").expect("Verbose prefix");
+    assert!(entry.contains('\n'));
+    let entry: Value = serde_json::from_str(entry).unwrap();
+    assert_eq!(entry["msg"]["device"], "Arduino0");
+}
+
+#[actix_web::test]
+async fn indexed_documents_are_queried_with_ingested_at_after_event_time() {
+    let server = MockServer::start().await;
+    Mock::given(path_regex(format!("^/{}/_doc/", INDEX)))
+        .respond_with(es_response(201, json!({ "_index": INDEX, "_id": "1", "result": "created" })))
+        .mount(&server)
+        .await;
+    let client = client_for(&server);
+    send_document(INDEX, &client, &log_entry(), &indexing_config(1)).await.unwrap();
+
+    // Serve the document exactly as the api indexed it
+    let requests = server.received_requests().await.unwrap();
+    let source: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    Mock::given(method("POST"))
+        .and(path(format!("/{}/_search", INDEX)))
+        .respond_with(es_response(
+            200,
+            json!({
+                "hits": {
+                    "total": { "value": 1, "relation": "eq" },
+                    "hits": [{ "_index": INDEX, "_id": "1", "_source": source }]
+                }
+            }),
+        ))
+        .mount(&server)
+        .await;
+
+    let (logs, _) = query_logs(INDEX, &client, &log_query()).await.unwrap();
+
+    let ingested_at = logs[0].ingested_at.expect("ingested_at of the stored document");
+    assert!(ingested_at >= logs[0].timestamp);
+}
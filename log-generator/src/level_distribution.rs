@@ -0,0 +1,201 @@
+use rand::Rng;
+use rand::seq::index::sample;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::logging_types::log_types::Log;
+
+/// Target share per level, e.g. `CRITICAL=0.01,WARN=0.1`. Levels that are not listed share
+/// the remaining proportion in their natural ratio.
+///
+/// # Examples
+/// ```
+/// let distribution: LevelDistribution = "CRITICAL=0.01,WARN=0.1".parse()?;
+/// ´´´
+#[derive(Clone, Debug)]
+pub struct LevelDistribution {
+    targets: HashMap<String, f64>,
+}
+
+impl std::str::FromStr for LevelDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut targets = HashMap::new();
+
+        for pair in s.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (level, share) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected LEVEL=share, got '{}'", pair))?;
+            let level = level.trim().to_uppercase();
+            if !["DEBUG", "INFO", "WARN", "CRITICAL"].contains(&level.as_str()) {
+                return Err(format!("Unknown level '{}'", level));
+            }
+            let share: f64 = share
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid share for {}: '{}'", level, share))?;
+            if !(0.0..=1.0).contains(&share) {
+                return Err(format!("Share for {} must be within 0.0 and 1.0: {}", level, share));
+            }
+            targets.insert(level, share);
+        }
+
+        let total: f64 = targets.values().sum();
+        if total > 1.0 + f64::EPSILON {
+            return Err(format!("Shares must not sum up to more than 1.0: {}", total));
+        }
+        Ok(Self { targets })
+    }
+}
+
+impl LevelDistribution {
+    /// Drops logs until the levels match the target shares, keeping as many logs as possible.
+    ///
+    /// Levels are only ever thinned out, never duplicated => the result size is bounded by the
+    /// level that is scarcest relative to its target share. Logs to keep are drawn uniformly
+    /// within each level and the original order is preserved.
+    ///
+    /// # Arguments
+    /// * `logs` - Generated logs
+    /// * `rng` - Random generator, seeded for reproducible output
+    ///
+    /// # Returns
+    /// * `Vec<Log>` - Remaining logs in generation order
+    ///
+    /// # Examples
+    /// ```
+    /// // 1000 logs with 500 CRITICAL and CRITICAL=0.01 => 500 INFO/WARN/DEBUG + 5 CRITICAL
+    /// let logs = distribution.resample(logs, &mut rng);
+    /// ´´´
+    pub fn resample(&self, logs: Vec<Log>, rng: &mut impl Rng) -> Vec<Log> {
+        // Unlisted levels form one group sharing the remaining proportion
+        let group_of = |log: &Log| {
+            let level = log.level.to_string();
+            if self.targets.contains_key(&level) { Some(level) } else { None }
+        };
+        let rest_share = 1.0 - self.targets.values().sum::<f64>();
+
+        // Ordered => groups draw from the rng in the same order on every run with --seed
+        let mut groups: BTreeMap<Option<String>, Vec<usize>> = BTreeMap::new();
+        for (i, log) in logs.iter().enumerate() {
+            groups.entry(group_of(log)).or_default().push(i);
+        }
+        let share_of = |group: &Option<String>| match group {
+            Some(level) => self.targets[level],
+            None => rest_share,
+        };
+
+        // Largest total for which every group still has enough logs
+        let mut total = logs.len() as f64;
+        for (group, members) in &groups {
+            let share = share_of(group);
+            if share > 0.0 {
+                total = total.min(members.len() as f64 / share);
+            }
+        }
+        // Groups without any generated log can't reach their share
+        for (level, share) in &self.targets {
+            if *share > 0.0 && !groups.contains_key(&Some(level.clone())) {
+                total = 0.0;
+            }
+        }
+        if rest_share > 1e-9 && !groups.contains_key(&None) {
+            total = 0.0;
+        }
+
+        let mut keep = vec![false; logs.len()];
+        for (group, members) in &groups {
+            let count = ((share_of(group) * total).round() as usize).min(members.len());
+            for index in sample(rng, members.len(), count) {
+                keep[members[index]] = true;
+            }
+        }
+
+        logs.into_iter()
+            .zip(keep)
+            .filter_map(|(log, keep)| keep.then_some(log))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging_types::log_types::{Device, Level, Measurement, Message};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn log(level: Level, index: usize) -> Log {
+        Log {
+            timestamp: format!("2025-01-01T00:00:{:02}.000Z", index % 60),
+            level,
+            temperatur: 21.5,
+            humidity: 40.0,
+            msg: Message {
+                device: Device::Arduino0,
+                msg: index.to_string(),
+                exceeded_values: [false, false],
+                exceeded_fields: [Measurement::Temperature, Measurement::Humidity],
+            },
+        }
+    }
+
+    fn count(logs: &[Log], level: &str) -> usize {
+        logs.iter().filter(|log| log.level.to_string() == level).count()
+    }
+
+    #[test]
+    fn from_str_reads_shares_case_insensitive() {
+        let distribution: LevelDistribution = " critical=0.01, WARN = 0.1 ,".parse().unwrap();
+
+        assert_eq!(distribution.targets.len(), 2);
+        assert_eq!(distribution.targets["CRITICAL"], 0.01);
+        assert_eq!(distribution.targets["WARN"], 0.1);
+    }
+
+    #[test]
+    fn from_str_rejects_invalid_distributions() {
+        assert!("CRITICAL".parse::<LevelDistribution>().is_err());
+        assert!("ERROR=0.1".parse::<LevelDistribution>().is_err());
+        assert!("WARN=a lot".parse::<LevelDistribution>().is_err());
+        assert!("WARN=1.5".parse::<LevelDistribution>().is_err());
+        assert!("WARN=0.6,CRITICAL=0.6".parse::<LevelDistribution>().is_err());
+    }
+
+    #[test]
+    fn resample_thins_out_overrepresented_levels() {
+        // 500 CRITICAL, 500 INFO => CRITICAL=0.01 keeps all INFO and 5 CRITICAL
+        let logs: Vec<Log> = (0..1000)
+            .map(|i| log(if i % 2 == 0 { Level::CRITICAL } else { Level::INFO }, i))
+            .collect();
+        let distribution: LevelDistribution = "CRITICAL=0.01".parse().unwrap();
+
+        let resampled = distribution.resample(logs, &mut StdRng::seed_from_u64(42));
+
+        assert_eq!(count(&resampled, "CRITICAL"), 5);
+        assert_eq!(count(&resampled, "INFO"), 500);
+        // Generation order is kept
+        let positions: Vec<usize> = resampled.iter().map(|log| log.msg.msg.parse().unwrap()).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn resample_is_reproducible_with_seed() {
+        let logs = || (0..200).map(|i| log(if i % 4 == 0 { Level::WARN } else { Level::DEBUG }, i)).collect();
+        let distribution: LevelDistribution = "WARN=0.1".parse().unwrap();
+
+        let first = distribution.resample(logs(), &mut StdRng::seed_from_u64(7));
+        let second = distribution.resample(logs(), &mut StdRng::seed_from_u64(7));
+
+        let messages = |logs: &[Log]| logs.iter().map(|log| log.msg.msg.clone()).collect::<Vec<_>>();
+        assert_eq!(messages(&first), messages(&second));
+    }
+
+    #[test]
+    fn resample_drops_everything_if_a_target_level_is_missing() {
+        let logs: Vec<Log> = (0..100).map(|i| log(Level::INFO, i)).collect();
+        let distribution: LevelDistribution = "CRITICAL=0.1".parse().unwrap();
+
+        assert!(distribution.resample(logs, &mut StdRng::seed_from_u64(1)).is_empty());
+    }
+}
@@ -1,22 +1,50 @@
 use chrono::{NaiveDateTime};
 use polars::prelude::*;
-use serde_json::to_string;
+use serde_json::{Value, json, to_string};
+use std::io::{BufWriter, Write};
 
-use crate::{log_generator::log_gen::LogGen, logging_types::log_types::Log};
+use crate::logging_types::log_types::Log;
+
+/// Value of the source column => marks generated logs so they can be separated from real sensor data.
+pub const SYNTHETIC_SOURCE: &str = "synthetic";
+
+/// Converts a log into a json object with typed values => numbers stay numbers and `msg` stays a nested object.
+/// Field names match the csv columns and the /send_log endpoint of the log forwarding api.
+///
+/// # Examples
+/// ```
+/// let record: Value = to_json_record(&log);
+/// ´´´
+pub fn to_json_record(log: &Log) -> Value {
+    json!({
+        "timestamp": log.timestamp,
+        "level": log.level,
+        "temperature": log.temperatur,
+        "humidity": log.humidity,
+        "msg": log.msg,
+        "source": SYNTHETIC_SOURCE,
+    })
+}
 
 /// Returns a Dataframe containing logs with timestamps row-wise by a runtime-optimized algorithm
 ///
 /// # Examples
 /// ```
-/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year)).expect("Error on log");
+/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year), args.debug_rate, args.seed).expect("Error on log");
 /// let mut collected_df: Dataframe = runtime_optimized_df_collector(log_gen);
 /// ´´´
-pub fn runtime_optimized_df_collector(log_gen: LogGen) -> DataFrame {
+pub fn runtime_optimized_df_collector(logs: impl Iterator<Item = Log>) -> DataFrame {
     // Dataframes to concatenate via lazy optimiter after loop
     let mut chunked_lazy_df = Vec::new();
+    let logs = logs.collect::<Vec<Log>>();
+
+    // Sampling may drop every log => concat can't handle an empty list, so start from the empty schema instead
+    if logs.is_empty() {
+        return memory_optimized_df_collector(logs.into_iter());
+    }
 
     // Collect all rows in seperate dataframes at once in order to make use of the lazyframe optimizer
-    for chunk in log_gen.collect::<Vec<Log>>().chunks(1000) {
+    for chunk in logs.chunks(1000) {
         // Extract each datapoint for column-wise alignment
         let timestamps: Vec<&str> = chunk.iter().map(|log| log.timestamp.as_str()).collect();
         let levels: Vec<String> = chunk.iter().map(|log| log.level.to_string()).collect();
@@ -34,6 +62,7 @@ pub fn runtime_optimized_df_collector(log_gen: LogGen) -> DataFrame {
             Series::new("temperature".into(), temperatures).into(),
             Series::new("humidity".into(), humidities).into(),
             Series::new("msg".into(), msgs).into(),
+            Series::new("source".into(), vec![SYNTHETIC_SOURCE; chunk.len()]).into(),
         ])
         .unwrap()
         .lazy();
@@ -53,10 +82,10 @@ pub fn runtime_optimized_df_collector(log_gen: LogGen) -> DataFrame {
 ///
 /// # Examples
 /// ```
-/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year)).expect("Error on log");
+/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year), args.debug_rate, args.seed).expect("Error on log");
 /// let mut collected_df: Dataframe = runtime_optimized_df_collector(log_gen);
 /// ´´´
-pub fn memory_optimized_df_collector(log_gen: LogGen) -> DataFrame {
+pub fn memory_optimized_df_collector(logs: impl Iterator<Item = Log>) -> DataFrame {
     // Initialize LazyFrame with needed schema
     let mut df = DataFrame::new(vec![
         Series::new("timestamp".into(), Vec::<NaiveDateTime>::new()).into(),
@@ -64,12 +93,13 @@ pub fn memory_optimized_df_collector(log_gen: LogGen) -> DataFrame {
         Series::new("temperature".into(), Vec::<f32>::new()).into(),
         Series::new("humidity".into(), Vec::<f32>::new()).into(),
         Series::new("msg".into(), Vec::<String>::new()).into(),
+        Series::new("source".into(), Vec::<String>::new()).into(),
     ])
     .unwrap()
     .lazy();
 
     // Convert each chunk to lazyframe and concatenate them in each iteration => This does not collect all lazyframes before concat
-    for chunk in log_gen.collect::<Vec<Log>>().chunks(1000) {
+    for chunk in logs.collect::<Vec<Log>>().chunks(1000) {
         // Extract each datapoint for column-wise alignment
         let timestamps: Vec<&str> = chunk.iter().map(|log| log.timestamp.as_str()).collect();
         let levels: Vec<String> = chunk.iter().map(|log| log.level.to_string()).collect();
@@ -87,6 +117,7 @@ pub fn memory_optimized_df_collector(log_gen: LogGen) -> DataFrame {
             Series::new("temperature".into(), temperatures).into(),
             Series::new("humidity".into(), humidities).into(),
             Series::new("msg".into(), msgs).into(),
+            Series::new("source".into(), vec![SYNTHETIC_SOURCE; chunk.len()]).into(),
         ])
         .unwrap()
         .lazy();
@@ -98,3 +129,174 @@ pub fn memory_optimized_df_collector(log_gen: LogGen) -> DataFrame {
     let materialized_df = df.collect().expect("Failed to collect LazyFrame");
     return materialized_df;
 }
+
+/// Writes logs to the csv file batch by batch while they are generated and returns the number of written logs.
+/// Only one batch is held in memory at once, so memory usage stays flat regardless of the log count.
+/// The header is written with the first batch, every following batch is appended to the file.
+/// With `flush_every` the writer is flushed after at least that many rows, so partial output of long runs is on disk.
+///
+/// # Examples
+/// ```
+/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year), args.debug_rate, args.seed).expect("Error on log");
+/// let written: usize = save_logs_streaming(log_gen, &mut writer, 100000, Some(10000));
+/// ´´´
+pub fn save_logs_streaming(
+    mut logs: impl Iterator<Item = Log>,
+    writer: &mut impl Write,
+    batch_size: usize,
+    flush_every: Option<usize>,
+) -> usize {
+    // Batches larger than the flush interval would delay flushes until the batch is complete
+    let batch_size = flush_every.map_or(batch_size, |every| batch_size.min(every)).max(1);
+    let mut written = 0;
+    let mut unflushed = 0;
+
+    loop {
+        let batch: Vec<Log> = logs.by_ref().take(batch_size).collect();
+
+        // Extract each datapoint for column-wise alignment
+        let timestamps: Vec<&str> = batch.iter().map(|log| log.timestamp.as_str()).collect();
+        let levels: Vec<String> = batch.iter().map(|log| log.level.to_string()).collect();
+        let temperatures: Vec<f32> = batch.iter().map(|log| log.temperatur).collect();
+        let humidities: Vec<f32> = batch.iter().map(|log| log.humidity).collect();
+        let msgs: Vec<String> = batch
+            .iter()
+            .map(|log| to_string(&log.msg).unwrap())
+            .collect();
+
+        let mut batch_df = DataFrame::new(vec![
+            Series::new("timestamp".into(), timestamps).into(),
+            Series::new("level".into(), levels).into(),
+            Series::new("temperature".into(), temperatures).into(),
+            Series::new("humidity".into(), humidities).into(),
+            Series::new("msg".into(), msgs).into(),
+            Series::new("source".into(), vec![SYNTHETIC_SOURCE; batch.len()]).into(),
+        ])
+        .unwrap();
+
+        // The writer stays at the end => each batch is appended after the previous one
+        CsvWriter::new(&mut *writer)
+            .include_header(written == 0)
+            .with_separator(b',')
+            .finish(&mut batch_df)
+            .expect("Could not write batch to csv file!");
+
+        written += batch.len();
+        unflushed += batch.len();
+        if flush_every.is_some_and(|every| unflushed >= every) {
+            writer.flush().expect("Could not flush csv file!");
+            unflushed = 0;
+        }
+        // A partial batch means the iterator is exhausted, an empty first batch still yields the header
+        if batch.len() < batch_size {
+            break;
+        }
+    }
+
+    writer.flush().expect("Could not flush csv file!");
+    written
+}
+
+/// Writes logs as newline-delimited json (one object per line) and returns the number of written logs.
+/// Logs are written while they are generated, so memory usage stays flat like with --streaming.
+/// With `flush_every` the writer is flushed every that many lines, so partial output of long runs is on disk.
+///
+/// # Examples
+/// ```
+/// let log_gen = LogGen::new(args.count, (args.start_year, args.end_year), args.debug_rate, args.seed).expect("Error on log");
+/// let written: usize = save_logs_to_ndjson(log_gen, &mut writer, None);
+/// ´´´
+pub fn save_logs_to_ndjson(logs: impl Iterator<Item = Log>, writer: &mut impl Write, flush_every: Option<usize>) -> usize {
+    let mut writer = BufWriter::new(writer);
+    let mut written = 0;
+
+    for log in logs {
+        serde_json::to_writer(&mut writer, &to_json_record(&log)).expect("Could not serialize log to json!");
+        writer.write_all(b"\n").expect("Could not write to ndjson file!");
+        written += 1;
+        if flush_every.is_some_and(|every| written % every == 0) {
+            writer.flush().expect("Could not flush ndjson file!");
+        }
+    }
+
+    writer.flush().expect("Could not write to ndjson file!");
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_generator::log_gen::LogGen;
+
+    fn logs(count: usize) -> impl Iterator<Item = Log> {
+        LogGen::new(count, (2025, 2026), 0.0, Some(42)).unwrap()
+    }
+
+    #[test]
+    fn generated_logs_are_marked_synthetic() {
+        assert!(logs(10).all(|log| to_json_record(&log)["source"] == SYNTHETIC_SOURCE));
+
+        let df = runtime_optimized_df_collector(logs(2500));
+        let sources = df.column("source").unwrap().as_materialized_series().str().unwrap().clone();
+        assert_eq!(sources.len(), 2500);
+        assert!(sources.into_iter().all(|source| source == Some(SYNTHETIC_SOURCE)));
+    }
+
+    /// Path in the temp dir, unique per test
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("log-generator-{}-{}", std::process::id(), name))
+    }
+
+    /// Yields `count` logs and snapshots the file content before log `at` is generated => the
+    /// snapshot is what a reader sees if the run is interrupted at that point.
+    fn logs_with_snapshot<'a>(
+        count: usize,
+        at: usize,
+        path: &'a std::path::Path,
+        snapshot: &'a std::cell::RefCell<String>,
+    ) -> impl Iterator<Item = Log> + 'a {
+        logs(count).enumerate().map(move |(i, log)| {
+            if i == at {
+                *snapshot.borrow_mut() = std::fs::read_to_string(path).unwrap();
+            }
+            log
+        })
+    }
+
+    /// Lines terminated by a newline, a partially written last line is left out
+    fn complete_lines(content: &str) -> Vec<&str> {
+        content.rsplit_once('\n').map_or(Vec::new(), |(complete, _)| complete.lines().collect())
+    }
+
+    #[test]
+    fn save_logs_to_ndjson_flushes_partial_output() {
+        let path = temp_file("flush.ndjson");
+        let snapshot = std::cell::RefCell::new(String::new());
+        let mut file = std::fs::File::create(&path).unwrap();
+
+        let written = save_logs_to_ndjson(logs_with_snapshot(300, 250, &path, &snapshot), &mut file, Some(100));
+
+        let lines = complete_lines(&snapshot.borrow()).into_iter().map(str::to_string).collect::<Vec<_>>();
+        assert!(lines.len() >= 200, "{} lines on disk", lines.len());
+        assert!(lines.iter().all(|line| serde_json::from_str::<Value>(line).is_ok()));
+        assert_eq!(written, 300);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn save_logs_streaming_flushes_partial_output() {
+        let path = temp_file("flush.csv");
+        let snapshot = std::cell::RefCell::new(String::new());
+        let mut file = std::fs::File::create(&path).unwrap();
+
+        let written = save_logs_streaming(logs_with_snapshot(300, 250, &path, &snapshot), &mut file, 100_000, Some(100));
+
+        // Batches are cut at the flush interval => header and the first two batches are on disk
+        let snapshot = snapshot.borrow();
+        let lines = complete_lines(&snapshot);
+        assert_eq!(lines.len(), 201);
+        assert!(lines[0].starts_with("timestamp,level,temperature,humidity,msg,source"));
+        assert_eq!(written, 300);
+        std::fs::remove_file(path).unwrap();
+    }
+}
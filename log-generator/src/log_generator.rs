@@ -1,7 +1,35 @@
 pub mod log_gen {
-    use crate::logging_types::log_types::{Device, Level, Log, Message};
-    use chrono::{Duration, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+    use crate::logging_types::log_types::{Device, Level, Log, Measurement, Message};
+    use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc, Weekday};
+    use clap::ValueEnum;
+    use std::f64::consts::PI;
     use rand::prelude::*;
+    use rand::rngs::StdRng;
+    use rayon::prelude::*;
+    use rayon::{ThreadPool, ThreadPoolBuilder};
+
+    /// Number of logs generated by one parallel task, every chunk gets its own generator
+    const PARALLEL_CHUNK_SIZE: usize = 10_000;
+    /// Share of logs placed within business hours with TimeDistribution::BusinessHours
+    const BUSINESS_HOURS_SHARE: f64 = 0.8;
+    /// Relative weight of the quietest hour (midnight) with TimeDistribution::Diurnal => nights are not empty
+    const DIURNAL_MIN_WEIGHT: f64 = 0.1;
+
+    /// Distribution of the generated timestamps over the day.
+    ///
+    /// # Examples
+    /// ```
+    /// let log_gen = LogGen::new(1000, (2025, 2026), 0.0, None)?.with_time_distribution(TimeDistribution::Diurnal);
+    /// ´´´
+    #[derive(Clone, Copy, PartialEq, ValueEnum)]
+    pub enum TimeDistribution {
+        /// Every time of every day is equally likely
+        Uniform,
+        /// Most logs on weekdays between 08:00 and 18:00, the rest spread uniformly
+        BusinessHours,
+        /// Sinusoidal weighting across the day, peaking at noon and lowest at midnight
+        Diurnal,
+    }
 
     /// Creates a log generator used as iterator to generate random chunks of datapoints.
     ///
@@ -18,16 +46,25 @@ pub mod log_gen {
     ///
     /// # Examples
     /// ```
-    /// let log_gen = LogGen::new(1000, (2025, 2026)).expect("Error on log");
+    /// let log_gen = LogGen::new(1000, (2025, 2026), 0.1, Some(42)).expect("Error on log");
     /// let mut collected_df: Dataframe = runtime_optimized_df_collector(log_gen);
     /// ´´´
     pub struct LogGen {
         count: usize,
         years: (NaiveDate, NaiveDate),
+        /// Fraction of normal (non threshold crossing) logs emitted as DEBUG instead of INFO
+        debug_rate: f64,
+        /// Source of all random values, seeded for reproducible datasets
+        rng: StdRng,
+        time_distribution: TimeDistribution,
     }
 
     impl LogGen {
-        pub fn new(count: usize, years: (i32, i32)) -> Result<LogGen, String> {
+        /// Without seed the generator is seeded from the OS, a fixed seed yields identical logs across runs.
+        pub fn new(count: usize, years: (i32, i32), debug_rate: f64, seed: Option<u64>) -> Result<LogGen, String> {
+            if !(0.0..=1.0).contains(&debug_rate) {
+                return Err(format!("Debug rate invalid: should be within 0.0 and 1.0, got {}", debug_rate));
+            }
             if years.1 - years.0 > 0 && count > 0 {
                 return Ok(Self {
                     count: count,
@@ -35,6 +72,12 @@ pub mod log_gen {
                         NaiveDate::from_yo_opt(years.0, 1).unwrap(),
                         NaiveDate::from_yo_opt(years.1, 1).unwrap(),
                     ),
+                    debug_rate: debug_rate,
+                    rng: match seed {
+                        Some(seed) => StdRng::seed_from_u64(seed),
+                        None => StdRng::from_os_rng(),
+                    },
+                    time_distribution: TimeDistribution::Uniform,
                 });
             } else {
                 return Err(
@@ -43,21 +86,48 @@ pub mod log_gen {
             }
         }
 
+        /// Sets the distribution of the generated timestamps over the day (default: uniform).
+        pub fn with_time_distribution(mut self, time_distribution: TimeDistribution) -> LogGen {
+            self.time_distribution = time_distribution;
+            self
+        }
+
         // Date Generation found in: https://stackoverflow.com/questions/77434585/generate-random-date-in-rust-from-date-interval
-        pub fn _generate_log(&self) -> Log {
-            // First create random values for each datapoint
-            let mut rng = rand::rng();
+        /// Generates a random timestamp within the year range following the configured time distribution.
+        pub fn generate_datetime(&mut self) -> NaiveDateTime {
+            let rng = &mut self.rng;
             let days_in_range = (self.years.1 - self.years.0).num_days();
-            let random_days: i64 = rng.random_range(0..days_in_range);
-            let naive: NaiveDateTime = (self.years.0 + Duration::days(random_days))
-            .and_hms_opt(
-                    rng.random_range(0..23),
-                    rng.random_range(0..59),
-                    rng.random_range(0..59),
-                )
+            let mut date = self.years.0 + Duration::days(rng.random_range(0..days_in_range));
+
+            let hour = match self.time_distribution {
+                TimeDistribution::Uniform => rng.random_range(0..23),
+                TimeDistribution::BusinessHours if rng.random_bool(BUSINESS_HOURS_SHARE) => {
+                    // Year ranges always contain weekdays => redraw the day until it is one
+                    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                        date = self.years.0 + Duration::days(rng.random_range(0..days_in_range));
+                    }
+                    rng.random_range(8..18)
+                }
+                TimeDistribution::BusinessHours => rng.random_range(0..24),
+                TimeDistribution::Diurnal => loop {
+                    // Rejection sampling => each hour is kept proportional to its weight
+                    let hour = rng.random_range(0..24);
+                    let weight = (1.0 - (2.0 * PI * hour as f64 / 24.0).cos()) / 2.0;
+                    if rng.random_bool(weight.max(DIURNAL_MIN_WEIGHT)) {
+                        break hour;
+                    }
+                },
+            };
+
+            date.and_hms_opt(hour, rng.random_range(0..59), rng.random_range(0..59))
                 .unwrap()
-                .into();
+        }
+
+        pub fn _generate_log(&mut self) -> Log {
+            // First create random values for each datapoint
+            let naive: NaiveDateTime = self.generate_datetime();
             let timestamp: String = Utc.from_utc_datetime(&naive).to_rfc3339_opts(SecondsFormat::Millis, true);
+            let rng = &mut self.rng;
 
 
             let temperature = rng.random_range(15.0..35.0);
@@ -72,6 +142,9 @@ pub mod log_gen {
                 Level::CRITICAL
             } else if temperature_exceeded_25 || humidity_exceeded_60 {
                 Level::WARN
+            } else if rng.random_bool(self.debug_rate) {
+                // Part of the normal events is emitted as debug noise
+                Level::DEBUG
             } else {
                 Level::INFO
             };
@@ -112,6 +185,7 @@ pub mod log_gen {
                 device: device,
                 msg: info_msg,
                 exceeded_values: [temperature_exceeded_25, humidity_exceeded_60],
+                exceeded_fields: [Measurement::Temperature, Measurement::Humidity],
             };
 
             Log {
@@ -124,6 +198,99 @@ pub mod log_gen {
         }
     }
 
+    impl LogGen {
+        /// Generates the logs in parallel on `threads` worker threads instead of one by one.
+        ///
+        /// Logs are generated in chunks of PARALLEL_CHUNK_SIZE, each with its own generator seeded from
+        /// this generator and the chunk index. Chunks are yielded in order, so a seeded run produces
+        /// identical output for any number of threads. At most `threads` chunks are held at once.
+        ///
+        /// # Examples
+        /// ```
+        /// let log_gen = LogGen::new(10_000_000, (2025, 2026), 0.0, Some(42)).expect("Error on log");
+        /// let logs = log_gen.parallel(8).expect("Error on thread pool");
+        /// ´´´
+        pub fn parallel(mut self, threads: usize) -> Result<ParallelLogGen, String> {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads.max(1))
+                .build()
+                .map_err(|e| format!("Thread pool could not be created: {}", e))?;
+
+            Ok(ParallelLogGen {
+                base_seed: self.rng.next_u64(),
+                next_chunk: 0,
+                buffer: Vec::new().into_iter(),
+                threads: threads.max(1),
+                pool,
+                log_gen: self,
+            })
+        }
+    }
+
+    /// Parallel log generator created by [`LogGen::parallel`], used as iterator like LogGen.
+    pub struct ParallelLogGen {
+        log_gen: LogGen,
+        base_seed: u64,
+        next_chunk: u64,
+        buffer: std::vec::IntoIter<Log>,
+        threads: usize,
+        pool: ThreadPool,
+    }
+
+    impl ParallelLogGen {
+        /// Generates the next round of up to `threads` chunks in parallel, preserving the chunk order.
+        fn generate_round(&mut self) -> Vec<Log> {
+            let mut chunks = Vec::new();
+            while chunks.len() < self.threads && self.log_gen.count > 0 {
+                let size = self.log_gen.count.min(PARALLEL_CHUNK_SIZE);
+                self.log_gen.count -= size;
+                // Golden ratio increment => well spread seeds for neighbouring chunks
+                let seed = self
+                    .base_seed
+                    .wrapping_add(self.next_chunk.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                self.next_chunk += 1;
+                chunks.push((size, seed));
+            }
+
+            let (years, debug_rate, time_distribution) =
+                (self.log_gen.years, self.log_gen.debug_rate, self.log_gen.time_distribution);
+            self.pool.install(|| {
+                chunks
+                    .into_par_iter()
+                    .map(|(size, seed)| {
+                        LogGen {
+                            count: size,
+                            years,
+                            debug_rate,
+                            rng: StdRng::seed_from_u64(seed),
+                            time_distribution,
+                        }
+                        .collect::<Vec<Log>>()
+                    })
+                    .collect::<Vec<Vec<Log>>>()
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            })
+        }
+    }
+
+    impl Iterator for ParallelLogGen {
+        type Item = Log;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if let Some(log) = self.buffer.next() {
+                return Some(log);
+            }
+            if self.log_gen.count == 0 {
+                return None;
+            }
+
+            self.buffer = self.generate_round().into_iter();
+            self.buffer.next()
+        }
+    }
+
     impl Iterator for LogGen {
         type Item = Log;
 
@@ -136,4 +303,49 @@ pub mod log_gen {
             Some(self._generate_log())
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Logs below both thresholds, the only ones that can become DEBUG
+        fn normal_levels(debug_rate: f64) -> Vec<String> {
+            LogGen::new(10_000, (2025, 2026), debug_rate, Some(42))
+                .unwrap()
+                .filter(|log| log.msg.exceeded_values == [false, false])
+                .map(|log| log.level.to_string())
+                .collect()
+        }
+
+        #[test]
+        fn debug_rate_zero_emits_no_debug_logs() {
+            let levels = normal_levels(0.0);
+
+            assert!(!levels.is_empty());
+            assert!(levels.iter().all(|level| level == "INFO"));
+        }
+
+        #[test]
+        fn debug_rate_one_emits_every_normal_log_as_debug() {
+            let levels = normal_levels(1.0);
+
+            assert!(!levels.is_empty());
+            assert!(levels.iter().all(|level| level == "DEBUG"));
+        }
+
+        #[test]
+        fn debug_rate_applies_to_share_of_normal_logs() {
+            let levels = normal_levels(0.3);
+
+            let debug_share = levels.iter().filter(|level| *level == "DEBUG").count() as f64 / levels.len() as f64;
+            assert!((0.25..=0.35).contains(&debug_share), "DEBUG share {}", debug_share);
+        }
+
+        #[test]
+        fn new_rejects_debug_rate_out_of_range() {
+            assert!(LogGen::new(10, (2025, 2026), -0.1, None).is_err());
+            assert!(LogGen::new(10, (2025, 2026), 1.5, None).is_err());
+            assert!(LogGen::new(10, (2025, 2026), 1.0, None).is_ok());
+        }
+    }
 }
@@ -6,10 +6,11 @@ pub mod log_types {
     ///
     /// # Examples
     /// ```
-    /// let level = if temperature_exceeded_30 || humidity_exceeded_70 { Level::CRITICAL } else if temperature_exceeded_25 || humidity_exceeded_60 { Level::WARN } else { Level::INFO };
+    /// let level = if temperature_exceeded_30 || humidity_exceeded_70 { Level::CRITICAL } else if temperature_exceeded_25 || humidity_exceeded_60 { Level::WARN } else if rng.random_bool(self.debug_rate) { Level::DEBUG } else { Level::INFO };
     /// ´´´
     #[derive(Serialize, Deserialize)]
     pub enum Level {
+        DEBUG,
         INFO,
         WARN,
         CRITICAL,
@@ -18,6 +19,7 @@ pub mod log_types {
     impl fmt::Display for Level {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             match self {
+                Level::DEBUG => write!(f, "DEBUG"),
                 Level::INFO => write!(f, "INFO"),
                 Level::WARN => write!(f, "WARN"),
                 Level::CRITICAL => write!(f, "CRITICAL"),
@@ -55,6 +57,7 @@ pub mod log_types {
     /// let temp_str = Measurement::Temperature.to_string()
     /// ´´´
     #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
     pub enum Measurement {
         Temperature,
         Humidity,
@@ -70,6 +73,7 @@ pub mod log_types {
     }
 
     /// Struct representing info msg within each log. This is serializable in order to be represented within a dataframe as string.
+    /// `exceeded_fields` names the measurement each flag of `exceeded_values` belongs to (same index).
     ///
     /// # Examples
     /// ```
@@ -80,7 +84,8 @@ pub mod log_types {
     ///            exceeded_values: [
     ///                temperature_exceeded_25,
     ///                humidity_exceeded_60
-    ///            ]
+    ///            ],
+    ///            exceeded_fields: [Measurement::Temperature, Measurement::Humidity]
     ///        };
     /// let msg_json: String = to_string(&msg).unwrap()
     /// ´´´
@@ -89,6 +94,7 @@ pub mod log_types {
         pub device: Device,
         pub msg: String,
         pub exceeded_values: [bool; 2],
+        pub exceeded_fields: [Measurement; 2],
     }
 
     /// Struct representing the whole log as struct. This is serializable in order to be represented within a dataframe as string.
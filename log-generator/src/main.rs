@@ -1,13 +1,48 @@
+mod level_distribution;
 mod log_collector;
 mod log_generator;
 mod logging_types;
+mod manifest;
+mod report;
+mod sender;
 mod utility;
-use clap::Parser;
-use log_collector::{memory_optimized_df_collector, runtime_optimized_df_collector};
-use log_generator::log_gen::LogGen;
-use polars::{frame::DataFrame, io::SerWriter, prelude::CsvWriter};
-use std::{fs::File, path::PathBuf};
-use utility::default_path;
+use clap::{Parser, ValueEnum};
+use indicatif::{ProgressBar, ProgressIterator, ProgressStyle};
+use level_distribution::LevelDistribution;
+use log_collector::{
+    memory_optimized_df_collector, runtime_optimized_df_collector, save_logs_streaming, save_logs_to_ndjson,
+};
+use log_generator::log_gen::{LogGen, TimeDistribution};
+use logging_types::log_types::Log;
+use manifest::{Checksum, Manifest, ManifestFile, TimeRange, TimeRangeTracker};
+use polars::{frame::DataFrame, io::SerWriter, prelude::{CsvWriter, ParquetWriter}};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use report::{GenerationStats, print_cardinality_report};
+use sender::send_logs;
+use std::path::Path;
+use utility::{create_output_file, default_path};
+
+/// Output file formats, the extension of --path is switched accordingly.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// Comma separated with `msg` as embedded json string
+    Csv,
+    /// Newline-delimited json with typed values and `msg` as nested object
+    Ndjson,
+    /// Columnar parquet with typed columns, written from the collected DataFrame
+    Parquet,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Parquet => "parquet",
+        }
+    }
+}
 
 /// CLI Arguments to Parse via clap refer to documentation of clap for more information.
 #[derive(Parser)]
@@ -28,39 +63,399 @@ struct Args {
     /// Path to save csv to.
     #[arg(short, long, default_value_t = default_path())]
     path: String,
+    /// Fraction of generated logs to keep (0.0 - 1.0). All `count` logs are still generated so the distribution over the year range is preserved.
+    #[arg(long, default_value_t = 1.0)]
+    sample_rate: f64,
+    /// Keep only every Nth generated log. Applied before the sample rate.
+    #[arg(long, default_value_t = 1)]
+    every: usize,
+    /// Fraction of normal (non threshold crossing) logs emitted as DEBUG instead of INFO (0.0 - 1.0).
+    #[arg(long, default_value_t = 0.0)]
+    debug_rate: f64,
+    /// Print per-field cardinality statistics (distinct values, nulls, min / max) after generation.
+    #[arg(long, default_value_t = false)]
+    report: bool,
+    /// Additionally send the generated logs directly to this /send_log endpoint of the log forwarding api.
+    #[arg(long)]
+    send_to: Option<String>,
+    /// Api key used for --send-to. Falls back to the SECRET_API_KEY environment variable.
+    #[arg(long)]
+    secret: Option<String>,
+    /// Number of worker threads used for --send-to.
+    #[arg(long, default_value_t = 4)]
+    send_concurrency: usize,
+    /// Seed for the random generators => identical output across runs. Random if not set.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Write logs to the csv in batches while generating instead of collecting a DataFrame first => flat memory usage for huge counts.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["send_to", "report", "memory_optimized", "sort_timestamps", "level_distribution"])]
+    streaming: bool,
+    /// Number of logs written per batch with --streaming.
+    #[arg(long, default_value_t = 100000)]
+    batch_size: usize,
+    /// Output format. ndjson is always written while generating, --memory-optimized and --report only apply to csv and parquet.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+    /// Generate logs in parallel on this many threads. With --seed the output is identical for any thread count (but differs from a run without --threads).
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Compress the output file with gzip (`.csv.gz` / `.ndjson.gz`). Parquet is compressed internally and can't be combined with it.
+    #[arg(long, default_value_t = false)]
+    gzip: bool,
+    /// Distribution of the timestamps over the day, the year range applies to every distribution.
+    #[arg(long, value_enum, default_value_t = TimeDistribution::Uniform)]
+    time_distribution: TimeDistribution,
+    /// Flush written rows to disk every N rows with --streaming or --format ndjson => partial output of interrupted runs stays usable.
+    #[arg(long)]
+    flush_every: Option<usize>,
+    /// Sort the logs by timestamp ascending before writing / sending. Requires all logs in memory, so it can't be combined with --streaming.
+    #[arg(long, default_value_t = false)]
+    sort_timestamps: bool,
+    /// Target share per level after generation, e.g. `CRITICAL=0.01,WARN=0.1`. Logs are dropped until the shares match, unlisted levels share the rest. Can't be combined with --streaming.
+    #[arg(long)]
+    level_distribution: Option<LevelDistribution>,
+    /// Don't show the generation progress bar (drawn to stderr).
+    #[arg(short, long, default_value_t = false)]
+    quiet: bool,
+    /// Write a json manifest of the generated file (rows, seed, time range, crc32 checksum) to this path.
+    #[arg(long)]
+    manifest: Option<String>,
+    /// Estimated memory (MiB) above which the in-memory path switches to --streaming, or aborts if the other options require all logs in memory. 0 disables the guard.
+    #[arg(long, default_value_t = 4096)]
+    memory_limit_mb: u64,
+}
+
+/// Rough footprint of a single collected log: the `Log` with its heap allocated strings plus its
+/// row in the DataFrame (msg serialized as json string). Deliberately on the high side.
+const ESTIMATED_BYTES_PER_LOG: f64 = 600.0;
+
+/// Estimates the peak memory (MiB) of the in-memory path for the logs kept after --every and --sample-rate.
+///
+/// # Examples
+/// ```
+/// // 100_000_000 logs => ~57220 MiB
+/// let estimate: u64 = estimate_memory_mb(&args);
+/// ´´´
+fn estimate_memory_mb(args: &Args) -> u64 {
+    let kept = (args.count / args.every) as f64 * args.sample_rate;
+    (kept * ESTIMATED_BYTES_PER_LOG / (1024.0 * 1024.0)).ceil() as u64
+}
+
+/// Whether the run can be written with --streaming, see the conflicts of `Args::streaming`.
+fn streaming_compatible(args: &Args) -> bool {
+    args.send_to.is_none()
+        && !args.report
+        && !args.memory_optimized
+        && !args.sort_timestamps
+        && args.level_distribution.is_none()
+        && args.format != OutputFormat::Parquet
+}
+
+/// Keeps every `every`th log and of those a `sample_rate` share drawn from `rng`, see --every and --sample-rate.
+///
+/// # Examples
+/// ```
+/// // 100_000 logs, every 10th, half of them sampled => ~5000 logs
+/// let kept: Vec<Log> = sample_logs(log_gen, 10, 0.5, &mut rng).collect();
+/// ´´´
+fn sample_logs<'a>(
+    logs: impl Iterator<Item = Log> + 'a,
+    every: usize,
+    sample_rate: f64,
+    rng: &'a mut StdRng,
+) -> impl Iterator<Item = Log> + 'a {
+    logs.enumerate()
+        .filter(move |(i, _)| i % every == 0)
+        .filter(move |_| sample_rate >= 1.0 || rng.random_bool(sample_rate))
+        .map(|(_, log)| log)
+}
+
+/// Writes the --manifest of the generated output file. Must be called after the writer was dropped,
+/// otherwise the checksum misses the bytes still buffered (e.g. the gzip trailer).
+///
+/// # Examples
+/// ```
+/// drop(writer);
+/// write_manifest(&args, &file_path, &checksum, written, time_range.range());
+/// ´´´
+fn write_manifest(args: &Args, file_path: &Path, checksum: &Checksum, rows: usize, time_range: Option<TimeRange>) {
+    let Some(manifest_path) = &args.manifest else {
+        return;
+    };
+    let format = if args.gzip {
+        format!("{}.gz", args.format.extension())
+    } else {
+        args.format.extension().to_string()
+    };
+    let manifest = Manifest {
+        seed: args.seed,
+        requested_count: args.count,
+        files: vec![ManifestFile {
+            path: file_path.display().to_string(),
+            format,
+            // LogGen only generates sensor logs
+            message_type: "sensor".to_string(),
+            rows,
+            bytes: std::fs::metadata(file_path).map(|metadata| metadata.len()).unwrap_or(0),
+            checksum: checksum.hex(),
+            time_range,
+        }],
+    };
+    manifest.write(Path::new(manifest_path));
+}
+
+/// Creates the progress bar of the generation, counting generated logs before sampling.
+/// Drawn to stderr => output written to stdout stays intact.
+///
+/// # Examples
+/// ```
+/// let progress: ProgressBar = create_progress_bar(args.count, args.quiet);
+/// ´´´
+fn create_progress_bar(count: usize, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+    let progress = ProgressBar::new(count as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {human_pos}/{human_len} logs | {per_sec} | ETA {eta}")
+            .expect("Invalid progress bar template"),
+    );
+    progress
 }
 
 fn main() {
-    let args = Args::parse();
-    let log_gen = LogGen::new(args.count, (args.start_year, args.end_year)).expect("Error on log generation");
+    let mut args = Args::parse();
+    let log_gen = LogGen::new(args.count, (args.start_year, args.end_year), args.debug_rate, args.seed)
+        .expect("Error on log generation")
+        .with_time_distribution(args.time_distribution);
     let mut collected_df: DataFrame;
 
-    if args.memory_optimized {
-        collected_df = memory_optimized_df_collector(log_gen);
-    } else {
-        collected_df = runtime_optimized_df_collector(log_gen);
+    if !(0.0..=1.0).contains(&args.sample_rate) {
+        panic!("Sample rate must be within 0.0 and 1.0: {}", args.sample_rate);
+    }
+    if args.every == 0 {
+        panic!("Every must be greater than 0!");
+    }
+    if args.flush_every == Some(0) {
+        panic!("Flush every must be greater than 0!");
+    }
+    // Parquet writes its footer after all row groups => needs the whole DataFrame
+    if args.format == OutputFormat::Parquet && args.streaming {
+        panic!("Parquet output can't be combined with --streaming!");
+    }
+    if args.format == OutputFormat::Parquet && args.gzip {
+        panic!("Parquet output is already compressed, --gzip is not supported!");
+    }
+    // Guard against an OOM kill halfway through => switch to the flat memory path or fail upfront
+    if !args.streaming && args.memory_limit_mb > 0 {
+        let estimate = estimate_memory_mb(&args);
+        if estimate > args.memory_limit_mb {
+            if !streaming_compatible(&args) {
+                panic!(
+                    "Estimated memory usage of {} MiB exceeds --memory-limit-mb {}! Use --streaming (without --send-to, --report, --memory-optimized, --sort-timestamps, --level-distribution and parquet), lower --count or raise the limit.",
+                    estimate, args.memory_limit_mb
+                );
+            }
+            println!(
+                "Estimated memory usage of {} MiB exceeds --memory-limit-mb {}, switching to --streaming",
+                estimate, args.memory_limit_mb
+            );
+            args.streaming = true;
+        }
+    }
+
+    // Iterate the full count and decimate afterwards => kept logs still span the whole year range
+    // Sampling gets its own stream => kept logs don't depend on whether sampling draws numbers
+    let mut rng: StdRng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(1)),
+        None => StdRng::from_os_rng(),
+    };
+    let stats = GenerationStats::new();
+    // Boxed => both generators feed the same sampling pipeline
+    let logs_iter: Box<dyn Iterator<Item = Log>> = match args.threads {
+        Some(threads) => Box::new(log_gen.parallel(threads).expect("Error on log generation")),
+        None => Box::new(log_gen),
+    };
+    let progress = create_progress_bar(args.count, args.quiet);
+    let time_range = TimeRangeTracker::default();
+    let sampled_logs = sample_logs(logs_iter.progress_with(progress.clone()), args.every, args.sample_rate, &mut rng)
+        .inspect(|log| stats.record(log));
+
+    // Streaming never holds more than one batch => skips the DataFrame preview, report and sending
+    if args.streaming {
+        let (mut writer, file_path, checksum) = create_output_file(&args.path, args.format.extension(), args.gzip);
+        let sampled_logs = sampled_logs.inspect(|log| time_range.record(log));
+        let written = match args.format {
+            OutputFormat::Csv => save_logs_streaming(sampled_logs, &mut writer, args.batch_size, args.flush_every),
+            OutputFormat::Ndjson => save_logs_to_ndjson(sampled_logs, &mut writer, args.flush_every),
+            OutputFormat::Parquet => unreachable!("Parquet is rejected with --streaming"),
+        };
+        progress.finish();
+        drop(writer);
+        println!("Wrote {} logs to {}", written, file_path.display());
+        write_manifest(&args, &file_path, &checksum, written, time_range.range());
+        stats.print_summary();
+        return;
+    }
+
+    let mut logs: Vec<Log> = sampled_logs.collect();
+    progress.finish();
+    if let Some(distribution) = &args.level_distribution {
+        // Own stream => the kept logs don't shift the sampling stream above
+        let mut resample_rng: StdRng = match args.seed {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(2)),
+            None => StdRng::from_os_rng(),
+        };
+        let generated = logs.len();
+        logs = distribution.resample(logs, &mut resample_rng);
+        println!("Kept {}/{} logs to match the level distribution", logs.len(), generated);
+    }
+    if args.sort_timestamps {
+        // RFC 3339 timestamps in UTC with fixed precision => lexical order is chronological order
+        logs.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    }
+    logs.iter().for_each(|log| time_range.record(log));
+
+    if let Some(endpoint) = &args.send_to {
+        let secret = args
+            .secret
+            .clone()
+            .or_else(|| std::env::var("SECRET_API_KEY").ok())
+            .expect("--send-to requires --secret or SECRET_API_KEY to be set!");
+        let sent = send_logs(&logs, endpoint, &secret, args.send_concurrency);
+        println!("Sent {}/{} logs to {}", sent, logs.len(), endpoint);
     }
 
-    // Save DataFrame to CSV if csv already exists, append index to filename
-    let mut file_path = PathBuf::from(&args.path);
-    if !("csv" == file_path.extension().unwrap()) {
-        panic!("Path must end with .csv: {}", file_path.display());
+    if args.format == OutputFormat::Ndjson {
+        let (mut writer, file_path, checksum) = create_output_file(&args.path, args.format.extension(), args.gzip);
+        let written = save_logs_to_ndjson(logs.into_iter(), &mut writer, args.flush_every);
+        drop(writer);
+        println!("Wrote {} logs to {}", written, file_path.display());
+        write_manifest(&args, &file_path, &checksum, written, time_range.range());
+        stats.print_summary();
+        return;
     }
 
-    let mut index = 0;
-    while file_path.exists() {
-        file_path.pop();
-        index += 1;
-        file_path.push(format!("log_gen_output_{index}.csv"));
+    if args.memory_optimized {
+        collected_df = memory_optimized_df_collector(logs.into_iter());
+    } else {
+        collected_df = runtime_optimized_df_collector(logs.into_iter());
     }
 
-    let mut file = File::create(file_path).expect("Could not create blank csv file!");
+    // Save DataFrame to CSV / Parquet if the file already exists, append index to filename
+    let (mut writer, file_path, checksum) = create_output_file(&args.path, args.format.extension(), args.gzip);
 
     //Show dataframe for info
     println!("{}", collected_df);
-    CsvWriter::new(&mut file)
-        .include_header(true)
-        .with_separator(b',')
-        .finish(&mut collected_df)
-        .expect("Could not create csv file from dataframe!");
+    if args.report {
+        print_cardinality_report(&collected_df);
+    }
+    match args.format {
+        OutputFormat::Parquet => {
+            // Same column order as the csv, temperature and humidity stay float columns
+            ParquetWriter::new(&mut writer)
+                .finish(&mut collected_df)
+                .expect("Could not create parquet file from dataframe!");
+        }
+        _ => {
+            CsvWriter::new(&mut writer)
+                .include_header(true)
+                .with_separator(b',')
+                .finish(&mut collected_df)
+                .expect("Could not create csv file from dataframe!");
+        }
+    }
+    drop(writer);
+    write_manifest(&args, &file_path, &checksum, collected_df.height(), time_range.range());
+
+    stats.print_summary();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn args(extra: &[&str]) -> Args {
+        Args::parse_from(["log-generator"].iter().chain(extra))
+    }
+
+    #[test]
+    fn estimate_memory_mb_scales_with_kept_logs() {
+        assert_eq!(estimate_memory_mb(&args(&["--count", "100000000"])), 57221);
+        // Every 10th log, half of them sampled => 50000 logs kept
+        assert_eq!(
+            estimate_memory_mb(&args(&["--count", "1000000", "--every", "10", "--sample-rate", "0.5"])),
+            29
+        );
+        assert_eq!(estimate_memory_mb(&args(&["--count", "0"])), 0);
+    }
+
+    #[test]
+    fn streaming_compatible_only_without_in_memory_options() {
+        assert!(streaming_compatible(&args(&[])));
+        assert!(streaming_compatible(&args(&["--format", "ndjson"])));
+        assert!(!streaming_compatible(&args(&["--sort-timestamps"])));
+        assert!(!streaming_compatible(&args(&["--level-distribution", "CRITICAL=0.01"])));
+        assert!(!streaming_compatible(&args(&["--format", "parquet"])));
+    }
+
+    fn log_gen(count: usize, seed: u64) -> LogGen {
+        LogGen::new(count, (2025, 2026), 0.0, Some(seed)).unwrap()
+    }
+
+    #[test]
+    fn sample_logs_keeps_roughly_the_sample_rate() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let kept = sample_logs(log_gen(100_000, 7), 1, 0.25, &mut rng).count();
+
+        // Binomial(100000, 0.25) => standard deviation ~137
+        assert!((24_000..=26_000).contains(&kept), "kept {} logs", kept);
+    }
+
+    #[test]
+    fn sample_logs_applies_every_before_sample_rate() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert_eq!(sample_logs(log_gen(1000, 7), 10, 1.0, &mut rng).count(), 100);
+        assert_eq!(sample_logs(log_gen(1000, 7), 1, 0.0, &mut rng).count(), 0);
+        let kept = sample_logs(log_gen(100_000, 7), 10, 0.5, &mut rng).count();
+        assert!((4_700..=5_300).contains(&kept), "kept {} logs", kept);
+    }
+
+    #[test]
+    fn write_manifest_records_generated_file() {
+        let dir = std::env::temp_dir();
+        let manifest_path = dir.join(format!("log-generator-{}-manifest.json", std::process::id()));
+        let output_path = dir.join(format!("log-generator-{}-manifest.csv", std::process::id()));
+        let args = args(&[
+            "--count", "10", "--seed", "42", "--gzip",
+            "--path", &output_path.display().to_string(),
+            "--manifest", &manifest_path.display().to_string(),
+        ]);
+        let (mut writer, file_path, checksum) = create_output_file(&args.path, args.format.extension(), args.gzip);
+        writer.write_all(b"timestamp,level\n").unwrap();
+        drop(writer);
+        let time_range = TimeRange {
+            start: "2025-01-01T00:00:00.000Z".to_string(),
+            end: "2025-06-01T00:00:00.000Z".to_string(),
+        };
+
+        write_manifest(&args, &file_path, &checksum, 10, Some(time_range));
+
+        let manifest: serde_json::Value = serde_json::from_reader(std::fs::File::open(&manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest["seed"], 42);
+        assert_eq!(manifest["requested_count"], 10);
+        let file = &manifest["files"][0];
+        assert_eq!(file["path"], file_path.display().to_string());
+        assert_eq!(file["format"], "csv.gz");
+        assert_eq!(file["message_type"], "sensor");
+        assert_eq!(file["rows"], 10);
+        assert_eq!(file["bytes"], std::fs::metadata(&file_path).unwrap().len());
+        assert_eq!(file["checksum"], checksum.hex());
+        assert_eq!(file["time_range"]["end"], "2025-06-01T00:00:00.000Z");
+        std::fs::remove_file(file_path).unwrap();
+        std::fs::remove_file(manifest_path).unwrap();
+    }
 }
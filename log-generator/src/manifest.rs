@@ -0,0 +1,201 @@
+use flate2::Crc;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::logging_types::log_types::Log;
+
+/// Crc32 over all bytes written to an output file. Shared with its [`ChecksumWriter`], so it can
+/// still be read after the writer was dropped (gzip streams are only finished on drop).
+#[derive(Clone, Default)]
+pub struct Checksum(Arc<Mutex<Crc>>);
+
+impl Checksum {
+    /// Checksum of the bytes written so far, e.g. `crc32:8a9136aa`
+    pub fn hex(&self) -> String {
+        format!("crc32:{:08x}", self.0.lock().expect("Checksum lock poisoned").sum())
+    }
+}
+
+/// Writer updating a [`Checksum`] with every byte passed on to the inner writer.
+///
+/// # Examples
+/// ```
+/// let checksum = Checksum::default();
+/// let writer = ChecksumWriter::new(File::create(&file_path)?, checksum.clone());
+/// ...
+/// drop(writer);
+/// println!("{}", checksum.hex());
+/// ´´´
+pub struct ChecksumWriter<W> {
+    inner: W,
+    checksum: Checksum,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    pub fn new(inner: W, checksum: Checksum) -> Self {
+        Self { inner, checksum }
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.checksum
+            .0
+            .lock()
+            .expect("Checksum lock poisoned")
+            .update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Earliest and latest timestamp of the written logs.
+#[derive(Clone, Serialize)]
+pub struct TimeRange {
+    pub start: String,
+    pub end: String,
+}
+
+/// Tracks the [`TimeRange`] of logs passing through an iterator, e.g. via `.inspect()`.
+#[derive(Default)]
+pub struct TimeRangeTracker(RefCell<Option<TimeRange>>);
+
+impl TimeRangeTracker {
+    pub fn record(&self, log: &Log) {
+        let mut range = self.0.borrow_mut();
+        match range.as_mut() {
+            // RFC 3339 timestamps in UTC with fixed precision => lexical order is chronological order
+            Some(range) => {
+                if log.timestamp < range.start {
+                    range.start = log.timestamp.clone();
+                }
+                if log.timestamp > range.end {
+                    range.end = log.timestamp.clone();
+                }
+            }
+            None => {
+                *range = Some(TimeRange {
+                    start: log.timestamp.clone(),
+                    end: log.timestamp.clone(),
+                })
+            }
+        }
+    }
+
+    /// `None` if no log was recorded.
+    pub fn range(&self) -> Option<TimeRange> {
+        self.0.borrow().clone()
+    }
+}
+
+/// Entry of a single generated file within the [`Manifest`].
+#[derive(Serialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub format: String,
+    pub message_type: String,
+    pub rows: usize,
+    pub bytes: u64,
+    pub checksum: String,
+    pub time_range: Option<TimeRange>,
+}
+
+/// Machine-readable record of a generation run, written as json with --manifest.
+///
+/// # Examples
+/// ```
+/// {
+///   "seed": 42,
+///   "requested_count": 1000,
+///   "files": [{
+///     "path": "/data/log_gen_output.csv", "format": "csv", "message_type": "sensor", "rows": 1000,
+///     "bytes": 187423, "checksum": "crc32:8a9136aa",
+///     "time_range": { "start": "2025-01-01T00:12:41.000Z", "end": "2025-12-31T23:50:02.000Z" }
+///   }]
+/// }
+/// ´´´
+#[derive(Serialize)]
+pub struct Manifest {
+    pub seed: Option<u64>,
+    pub requested_count: usize,
+    pub files: Vec<ManifestFile>,
+}
+
+impl Manifest {
+    /// Writes the manifest as pretty printed json, an existing file at `path` is overwritten.
+    pub fn write(&self, path: &Path) {
+        let file = File::create(path).expect("Could not create manifest file!");
+        serde_json::to_writer_pretty(file, self).expect("Could not write manifest file!");
+        println!("Wrote manifest to {}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_generator::log_gen::LogGen;
+    use crate::utility::create_output_file;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    /// Checksum in the manifest format computed from the bytes on disk
+    fn checksum_of_file(path: &Path) -> String {
+        let mut crc = Crc::new();
+        crc.update(&std::fs::read(path).unwrap());
+        format!("crc32:{:08x}", crc.sum())
+    }
+
+    fn temp_csv_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("log-generator-{}-{}.csv", std::process::id(), name))
+            .display()
+            .to_string()
+    }
+
+    #[test]
+    fn checksum_matches_bytes_on_disk() {
+        let (mut writer, file_path, checksum) = create_output_file(&temp_csv_path("checksum"), "csv", false);
+        writer.write_all(b"timestamp,level\n2025-01-01T00:00:00.000Z,INFO\n").unwrap();
+        drop(writer);
+
+        assert_eq!(checksum.hex(), checksum_of_file(&file_path));
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn checksum_covers_compressed_bytes_with_gzip() {
+        let content = "timestamp,level\n".repeat(1000);
+        let (mut writer, file_path, checksum) = create_output_file(&temp_csv_path("checksum_gzip"), "csv", true);
+        writer.write_all(content.as_bytes()).unwrap();
+        // The gzip trailer is only written on drop
+        drop(writer);
+
+        assert!(file_path.display().to_string().ends_with(".csv.gz"));
+        assert_eq!(checksum.hex(), checksum_of_file(&file_path));
+        let mut decompressed = String::new();
+        GzDecoder::new(File::open(&file_path).unwrap()).read_to_string(&mut decompressed).unwrap();
+        assert_eq!(decompressed, content);
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn time_range_tracker_keeps_earliest_and_latest_timestamp() {
+        let tracker = TimeRangeTracker::default();
+        assert!(tracker.range().is_none());
+
+        let logs: Vec<Log> = LogGen::new(100, (2025, 2026), 0.0, Some(42)).unwrap().collect();
+        logs.iter().for_each(|log| tracker.record(log));
+
+        let range = tracker.range().unwrap();
+        assert_eq!(range.start, logs.iter().map(|log| &log.timestamp).min().unwrap().as_str());
+        assert_eq!(range.end, logs.iter().map(|log| &log.timestamp).max().unwrap().as_str());
+    }
+}
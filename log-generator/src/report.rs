@@ -0,0 +1,258 @@
+use polars::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use crate::logging_types::log_types::{Level, Log};
+
+/// Thread-safe counters of the generated logs per level. Recording only needs a shared reference,
+/// so the same stats can be used by sequential and parallel generation.
+///
+/// # Examples
+/// ```
+/// let stats = GenerationStats::new();
+/// let logs = log_gen.inspect(|log| stats.record(log));
+/// ...
+/// stats.print_summary();
+/// ´´´
+pub struct GenerationStats {
+    started: Instant,
+    debug: AtomicU64,
+    info: AtomicU64,
+    warn: AtomicU64,
+    critical: AtomicU64,
+}
+
+impl GenerationStats {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            debug: AtomicU64::new(0),
+            info: AtomicU64::new(0),
+            warn: AtomicU64::new(0),
+            critical: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a single generated log by its level.
+    pub fn record(&self, log: &Log) {
+        let counter = match log.level {
+            Level::DEBUG => &self.debug,
+            Level::INFO => &self.info,
+            Level::WARN => &self.warn,
+            Level::CRITICAL => &self.critical,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Total number of recorded logs over all levels.
+    pub fn total(&self) -> u64 {
+        [&self.debug, &self.info, &self.warn, &self.critical]
+            .iter()
+            .map(|counter| counter.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Prints the final summary, e.g. "Generated 1000 logs (DEBUG: 0, INFO: 900, WARN: 80, CRITICAL: 20) in 0.42s"
+    pub fn print_summary(&self) {
+        println!(
+            "Generated {} logs ({}: {}, {}: {}, {}: {}, {}: {}) in {:.2}s",
+            self.total(),
+            Level::DEBUG,
+            self.debug.load(Ordering::Relaxed),
+            Level::INFO,
+            self.info.load(Ordering::Relaxed),
+            Level::WARN,
+            self.warn.load(Ordering::Relaxed),
+            Level::CRITICAL,
+            self.critical.load(Ordering::Relaxed),
+            self.started.elapsed().as_secs_f64()
+        );
+    }
+}
+
+/// Cardinality statistics of a single column, min / max only for numeric columns.
+#[derive(Debug, PartialEq)]
+pub struct FieldStats {
+    pub name: String,
+    pub distinct: usize,
+    pub nulls: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Cardinality statistics of the generated logs, see [`cardinality_report`].
+#[derive(Debug, PartialEq)]
+pub struct CardinalityReport {
+    pub rows: usize,
+    pub fields: Vec<FieldStats>,
+    /// Number of logs per device, `<unparseable>` for msg values without device
+    pub devices: BTreeMap<String, usize>,
+}
+
+/// Computes per-field cardinality statistics of the generated logs.
+///
+/// For each column the number of distinct values and null values is counted, numeric columns
+/// additionally get their min / max. The device of each log is nested within the msg json,
+/// so it is counted separately per device.
+///
+/// # Examples
+/// ```
+/// let collected_df: Dataframe = runtime_optimized_df_collector(log_gen);
+/// let report: CardinalityReport = cardinality_report(&collected_df);
+/// ´´´
+pub fn cardinality_report(df: &DataFrame) -> CardinalityReport {
+    let fields = df
+        .get_columns()
+        .iter()
+        .map(|column| {
+            let series = column.as_materialized_series();
+            // Min / Max only make sense for numeric columns
+            let numeric = series.dtype().is_float() || series.dtype().is_integer();
+            FieldStats {
+                name: series.name().to_string(),
+                distinct: series.n_unique().unwrap_or(0),
+                nulls: series.null_count(),
+                min: numeric.then(|| series.min::<f64>().ok().flatten()).flatten(),
+                max: numeric.then(|| series.max::<f64>().ok().flatten()).flatten(),
+            }
+        })
+        .collect();
+
+    // Device is part of the serialized msg => extract it from the json strings
+    let mut devices: BTreeMap<String, usize> = BTreeMap::new();
+    if let Ok(msgs) = df.column("msg").and_then(|column| column.as_materialized_series().str()) {
+        for msg in msgs.into_iter().flatten() {
+            let device = serde_json::from_str::<Value>(msg)
+                .ok()
+                .and_then(|json| json["device"].as_str().map(str::to_string))
+                .unwrap_or_else(|| "<unparseable>".to_string());
+            *devices.entry(device).or_insert(0) += 1;
+        }
+    }
+
+    CardinalityReport {
+        rows: df.height(),
+        fields,
+        devices,
+    }
+}
+
+/// Prints the [`cardinality_report`] of the generated logs to stdout.
+///
+/// # Examples
+/// ```
+/// let collected_df: Dataframe = runtime_optimized_df_collector(log_gen);
+/// print_cardinality_report(&collected_df);
+/// ´´´
+pub fn print_cardinality_report(df: &DataFrame) {
+    let report = cardinality_report(df);
+    println!("Cardinality report ({} rows)", report.rows);
+    println!("{:<12} {:>10} {:>8} {:>12} {:>12}", "field", "distinct", "nulls", "min", "max");
+
+    for field in &report.fields {
+        println!(
+            "{:<12} {:>10} {:>8} {:>12} {:>12}",
+            field.name,
+            field.distinct,
+            field.nulls,
+            format_stat(field.min),
+            format_stat(field.max)
+        );
+    }
+
+    println!("{:<12} {:>10}", "msg.device", report.devices.len());
+    for (device, count) in &report.devices {
+        println!("  {:<10} {:>10}", device, count);
+    }
+}
+
+fn format_stat(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.3}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_generator::log_gen::LogGen;
+    use polars::df;
+
+    fn field<'a>(report: &'a CardinalityReport, name: &str) -> &'a FieldStats {
+        report.fields.iter().find(|field| field.name == name).unwrap()
+    }
+
+    #[test]
+    fn cardinality_report_counts_distinct_nulls_and_range() {
+        let df = df!(
+            "level" => ["INFO", "WARN", "INFO", "CRITICAL"],
+            "temperature" => [Some(18.5f32), None, Some(31.0), Some(22.0)],
+            "msg" => [
+                r#"{"device":"arduino0","msg":"a"}"#,
+                r#"{"device":"arduino1","msg":"b"}"#,
+                r#"{"device":"arduino0","msg":"c"}"#,
+                "not json",
+            ]
+        )
+        .unwrap();
+
+        let report = cardinality_report(&df);
+
+        assert_eq!(report.rows, 4);
+        assert_eq!(
+            field(&report, "level"),
+            &FieldStats { name: "level".to_string(), distinct: 3, nulls: 0, min: None, max: None }
+        );
+        let temperature = field(&report, "temperature");
+        assert_eq!((temperature.nulls, temperature.min, temperature.max), (1, Some(18.5), Some(31.0)));
+        assert_eq!(
+            report.devices,
+            BTreeMap::from([
+                ("<unparseable>".to_string(), 1),
+                ("arduino0".to_string(), 2),
+                ("arduino1".to_string(), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn cardinality_report_of_empty_frame() {
+        let df = df!("level" => Vec::<String>::new(), "msg" => Vec::<String>::new()).unwrap();
+
+        let report = cardinality_report(&df);
+
+        assert_eq!(report.rows, 0);
+        assert_eq!(field(&report, "level").distinct, 0);
+        assert!(report.devices.is_empty());
+    }
+
+    #[test]
+    fn generation_stats_count_per_level_and_total() {
+        let stats = GenerationStats::new();
+        let logs: Vec<Log> = LogGen::new(1000, (2025, 2026), 0.2, Some(42)).unwrap().collect();
+
+        logs.iter().for_each(|log| stats.record(log));
+
+        let count = |level: &str| logs.iter().filter(|log| log.level.to_string() == level).count() as u64;
+        assert_eq!(stats.debug.load(Ordering::Relaxed), count("DEBUG"));
+        assert_eq!(stats.info.load(Ordering::Relaxed), count("INFO"));
+        assert_eq!(stats.warn.load(Ordering::Relaxed), count("WARN"));
+        assert_eq!(stats.critical.load(Ordering::Relaxed), count("CRITICAL"));
+        assert_eq!(stats.total(), 1000);
+    }
+
+    #[test]
+    fn generation_stats_are_shared_between_threads() {
+        let stats = GenerationStats::new();
+        let logs: Vec<Log> = LogGen::new(4000, (2025, 2026), 0.0, Some(7)).unwrap().collect();
+
+        std::thread::scope(|scope| {
+            for chunk in logs.chunks(1000) {
+                let stats = &stats;
+                scope.spawn(move || chunk.iter().for_each(|log| stats.record(log)));
+            }
+        });
+
+        assert_eq!(stats.total(), 4000);
+    }
+}
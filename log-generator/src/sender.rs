@@ -0,0 +1,131 @@
+use reqwest::blocking::Client;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::log_collector::to_json_record;
+use crate::logging_types::log_types::Log;
+
+/// Sends all logs directly to the log forwarding api, skipping the csv + log-sender round trip.
+/// Logs are split into `concurrency` chunks which are sent by one worker thread each.
+/// Returns the number of logs that were accepted by the api.
+///
+/// # Examples
+/// ```
+/// let sent: usize = send_logs(&logs, "http://localhost:8080/send_log", "123456", 4);
+/// ´´´
+pub fn send_logs(logs: &[Log], endpoint: &str, secret: &str, concurrency: usize) -> usize {
+    let client = Client::new();
+    let sent = AtomicUsize::new(0);
+    let chunk_size = logs.len().div_ceil(concurrency.max(1)).max(1);
+
+    // Scoped threads can borrow the logs and the client without cloning them
+    thread::scope(|scope| {
+        for chunk in logs.chunks(chunk_size) {
+            let (client, sent) = (&client, &sent);
+            scope.spawn(move || {
+                for log in chunk {
+                    let result = client
+                        .post(endpoint)
+                        .header("X-Api-Key", secret)
+                        .json(&to_json_record(log))
+                        .send()
+                        .and_then(|response| response.error_for_status());
+
+                    match result {
+                        Ok(_) => {
+                            sent.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(err) => eprintln!("Failed to send log: {}", err),
+                    }
+                }
+            });
+        }
+    });
+
+    sent.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_generator::log_gen::LogGen;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal stand-in for the api: answers with 503 for CRITICAL logs and 201 otherwise,
+    /// the api keys of all requests are collected. Returns the endpoint and the collected keys.
+    fn mock_api() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/send_log", listener.local_addr().unwrap());
+        let api_keys = Arc::new(Mutex::new(Vec::new()));
+
+        let keys = api_keys.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let keys = keys.clone();
+                thread::spawn(move || serve(stream, &keys));
+            }
+        });
+        (endpoint, api_keys)
+    }
+
+    /// Answers the requests of a single keep-alive connection until the client closes it.
+    fn serve(stream: TcpStream, api_keys: &Mutex<Vec<String>>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut stream = stream;
+        loop {
+            let (mut content_length, mut line) = (0, String::new());
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                let Some((name, value)) = line.trim_end().split_once(':') else {
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    continue;
+                };
+                match name.to_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap(),
+                    "x-api-key" => api_keys.lock().unwrap().push(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let status = if String::from_utf8_lossy(&body).contains(r#""level":"CRITICAL""#) {
+                "503 Service Unavailable"
+            } else {
+                "201 Created"
+            };
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).unwrap();
+        }
+    }
+
+    #[test]
+    fn send_logs_counts_only_accepted_logs() {
+        let (endpoint, api_keys) = mock_api();
+        let logs: Vec<Log> = LogGen::new(200, (2025, 2026), 0.0, Some(42)).unwrap().collect();
+        let critical = logs.iter().filter(|log| log.level.to_string() == "CRITICAL").count();
+        assert!(critical > 0);
+
+        let sent = send_logs(&logs, &endpoint, "123456", 4);
+
+        assert_eq!(sent, logs.len() - critical);
+        let api_keys = api_keys.lock().unwrap();
+        assert_eq!(api_keys.len(), logs.len());
+        assert!(api_keys.iter().all(|key| key == "123456"));
+    }
+
+    #[test]
+    fn send_logs_counts_nothing_without_api() {
+        // Bound and dropped => nothing listens on the port
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let logs: Vec<Log> = LogGen::new(5, (2025, 2026), 0.0, Some(42)).unwrap().collect();
+
+        assert_eq!(send_logs(&logs, &format!("http://{}/send_log", addr), "123456", 2), 0);
+    }
+}
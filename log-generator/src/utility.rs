@@ -1,7 +1,54 @@
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::manifest::{Checksum, ChecksumWriter};
+
 pub fn default_path() -> String {
     std::path::Path::new(&std::env::current_dir().unwrap())
     .join("log_gen_output.csv")
     .to_str()
     .unwrap()
     .to_string()
-}
\ No newline at end of file
+}
+/// Resolves the output path without overwriting existing files and creates the output writer.
+/// The extension of the path is switched to the one of the output format (e.g. `.ndjson`), `.gz` is appended with gzip.
+/// If the path already exists, `log_gen_output_{index}.{extension}` with the next free index is used instead.
+/// Gzip streams are finished when the writer is dropped. The returned checksum covers the bytes written to disk
+/// (after compression) and is complete once the writer is dropped.
+///
+/// # Examples
+/// ```
+/// let (writer, file_path, checksum): (Box<dyn Write>, PathBuf, Checksum) = create_output_file(&args.path, "csv", true);
+/// ´´´
+pub fn create_output_file(path: &str, extension: &str, gzip: bool) -> (Box<dyn Write>, PathBuf, Checksum) {
+    let extension = if gzip { format!("{extension}.gz") } else { extension.to_string() };
+    let mut file_path = PathBuf::from(path);
+    if !path.ends_with(&format!(".{extension}")) {
+        if !("csv" == file_path.extension().unwrap()) {
+            panic!("Path must end with .csv or .{extension}: {}", file_path.display());
+        }
+        file_path.set_extension(&extension);
+    }
+
+    let mut index = 0;
+    while file_path.exists() {
+        file_path.pop();
+        index += 1;
+        file_path.push(format!("log_gen_output_{index}.{extension}"));
+    }
+
+    let checksum = Checksum::default();
+    let file = ChecksumWriter::new(
+        File::create(&file_path).expect("Could not create blank output file!"),
+        checksum.clone(),
+    );
+    let writer: Box<dyn Write> = if gzip {
+        Box::new(GzEncoder::new(file, Compression::default()))
+    } else {
+        Box::new(file)
+    };
+    (writer, file_path, checksum)
+}
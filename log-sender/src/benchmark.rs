@@ -0,0 +1,262 @@
+use crate::{Config, InnerMsg, LogEntry, default_exceeded_fields};
+use chrono::{SecondsFormat, Utc};
+use hdrhistogram::Histogram;
+use std::env;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Settings of the benchmark mode.
+///
+/// Loads settings from environment variables:
+/// - BENCHMARK_COUNT: Number of log entries to send (usize, default 10000)
+/// - BENCHMARK_CONCURRENCY: Number of concurrent senders (usize, default 32)
+/// - BENCHMARK_DURATION_SECS: Optional time limit, stops early once reached (u64)
+struct BenchmarkConfig {
+    count: usize,
+    concurrency: usize,
+    duration: Option<Duration>,
+}
+
+impl BenchmarkConfig {
+    fn load() -> Self {
+        Self {
+            count: env::var("BENCHMARK_COUNT")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            concurrency: env::var("BENCHMARK_CONCURRENCY")
+                .unwrap_or_else(|_| "32".to_string())
+                .parse::<usize>()
+                .unwrap_or(32)
+                .max(1),
+            duration: env::var("BENCHMARK_DURATION_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .map(Duration::from_secs),
+        }
+    }
+}
+
+/// Creates an in-memory log entry, so the benchmark does not depend on a generated file.
+///
+/// # Arguments
+/// * `i` - Sequence number, used to vary the values
+///
+/// # Returns
+/// * `LogEntry` - Synthetic log entry
+fn synthetic_log_entry(i: usize) -> LogEntry {
+    LogEntry {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        level: "INFO".to_string(),
+        temperature: 20.0 + (i % 100) as f64 / 10.0,
+        humidity: 40.0 + (i % 200) as f64 / 10.0,
+        msg: InnerMsg {
+            device: format!("Arduino{}", i % 3),
+            msg: format!("INFO: benchmark log {}", i),
+            exceeded_values: vec![false, false],
+            exceeded_fields: default_exceeded_fields(),
+        },
+        source: Some("synthetic".to_string()),
+    }
+}
+
+/// Latency percentiles in microseconds.
+///
+/// # Arguments
+/// * `histogram` - Recorded latencies in microseconds
+///
+/// # Returns
+/// * `(u64, u64, u64)` - p50, p95 and p99
+fn percentiles(histogram: &Histogram<u64>) -> (u64, u64, u64) {
+    (
+        histogram.value_at_quantile(0.50),
+        histogram.value_at_quantile(0.95),
+        histogram.value_at_quantile(0.99),
+    )
+}
+
+/// Measured outcome of a benchmark run
+struct BenchmarkResult {
+    /// Latencies of the successful requests in microseconds
+    histogram: Histogram<u64>,
+    /// Number of failed requests
+    errors: usize,
+    /// Wall time of the measured requests
+    elapsed: Duration,
+}
+
+/// Runs the benchmark mode and prints latency percentiles and throughput.
+///
+/// Sends synthetic log entries to the primary endpoint with BENCHMARK_CONCURRENCY concurrent
+/// senders until BENCHMARK_COUNT entries were sent or BENCHMARK_DURATION_SECS elapsed.
+///
+/// # Arguments
+/// * `config` - Configuration containing endpoint URLs, API secret and extra headers
+pub async fn run_benchmark(config: &Config) {
+    let benchmark = BenchmarkConfig::load();
+    let client = reqwest::Client::builder()
+        .default_headers(config.extra_headers.clone())
+        .pool_idle_timeout(config.pool_idle_timeout)
+        .build()
+        .expect("Failed to create HTTP client");
+    // Before the clock starts => connection setup doesn't skew the first latencies
+    crate::warm_up(&client, config).await;
+
+    println!(
+        "Benchmarking {} with {} log entries, concurrency {}{}",
+        config.endpoints[0],
+        benchmark.count,
+        benchmark.concurrency,
+        benchmark
+            .duration
+            .map(|duration| format!(", time limit {}s", duration.as_secs()))
+            .unwrap_or_default()
+    );
+
+    let BenchmarkResult { histogram, errors, elapsed } = measure(&client, config, &benchmark).await;
+
+    let (p50, p95, p99) = percentiles(&histogram);
+    println!("Sent: {} ok, {} failed in {:.2}s", histogram.len(), errors, elapsed.as_secs_f64());
+    println!("Throughput: {:.1} logs/s", histogram.len() as f64 / elapsed.as_secs_f64());
+    println!(
+        "Latency: p50 {:.2}ms | p95 {:.2}ms | p99 {:.2}ms | max {:.2}ms",
+        p50 as f64 / 1000.0,
+        p95 as f64 / 1000.0,
+        p99 as f64 / 1000.0,
+        histogram.max() as f64 / 1000.0
+    );
+}
+
+/// Sends the benchmark requests and measures them.
+///
+/// Every sender records its request latencies into its own histogram, which are merged at the end.
+/// Only the requests sent here are measured, a preceding warm-up doesn't show up in the result.
+///
+/// # Arguments
+/// * `client` - HTTP client, possibly with connections established by the warm-up
+/// * `config` - Configuration containing endpoint URLs and API secret
+/// * `benchmark` - Number of entries, concurrency and time limit
+///
+/// # Returns
+/// * `BenchmarkResult` - Latencies, error count and elapsed time of the measured requests
+async fn measure(client: &reqwest::Client, config: &Config, benchmark: &BenchmarkConfig) -> BenchmarkResult {
+    let endpoint = Arc::new(config.endpoints[0].clone());
+    let secret = Arc::new(config.secret.clone());
+    let next = Arc::new(AtomicUsize::new(0));
+    let errors = Arc::new(AtomicUsize::new(0));
+
+    let started = Instant::now();
+    let deadline = benchmark.duration.map(|duration| started + duration);
+
+    let mut workers = Vec::new();
+    for _ in 0..benchmark.concurrency {
+        let (client, endpoint, secret) = (client.clone(), endpoint.clone(), secret.clone());
+        let (next, errors) = (next.clone(), errors.clone());
+        let count = benchmark.count;
+
+        workers.push(tokio::spawn(async move {
+            // Latencies from 1µs up to 60s with 3 significant digits
+            let mut histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3)
+                .expect("Invalid histogram bounds");
+
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= count || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    break;
+                }
+
+                let request_started = Instant::now();
+                let result = client
+                    .post(endpoint.as_str())
+                    .header("X-Api-Key", secret.as_str())
+                    .json(&synthetic_log_entry(i))
+                    .send()
+                    .await
+                    .and_then(|res| res.error_for_status());
+
+                match result {
+                    Ok(_) => {
+                        histogram.saturating_record(request_started.elapsed().as_micros() as u64);
+                    }
+                    Err(_) => {
+                        errors.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+
+            histogram
+        }));
+    }
+
+    let mut histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).expect("Invalid histogram bounds");
+    for worker in workers {
+        let worker_histogram = worker.await.expect("Benchmark worker panicked");
+        histogram.add(worker_histogram).expect("Failed to merge histograms");
+    }
+
+    BenchmarkResult {
+        histogram,
+        errors: errors.load(Ordering::Relaxed),
+        elapsed: started.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram() -> Histogram<u64> {
+        Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap()
+    }
+
+    #[test]
+    fn percentiles_of_known_latencies() {
+        let mut latencies = histogram();
+        for latency in 1..=100 {
+            latencies.record(latency).unwrap();
+        }
+
+        assert_eq!(percentiles(&latencies), (50, 95, 99));
+    }
+
+    #[test]
+    fn percentiles_are_dominated_by_outliers_only_at_the_tail() {
+        let mut latencies = histogram();
+        latencies.record_n(1_000, 97).unwrap();
+        latencies.record_n(50_000, 3).unwrap();
+
+        let (p50, p95, p99) = percentiles(&latencies);
+        assert_eq!((p50, p95), (1_000, 1_000));
+        assert!(latencies.equivalent(p99, 50_000));
+    }
+
+    #[test]
+    fn percentiles_of_empty_histogram_are_zero() {
+        assert_eq!(percentiles(&histogram()), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn warm_up_requests_are_not_measured() {
+        let (url, requests) = crate::tests::mock_api(0);
+        let config = Config {
+            warmup_requests: 3,
+            ..crate::tests::config("logs.csv", vec![format!("{}/send_log", url)])
+        };
+        let client = reqwest::Client::new();
+        let benchmark = BenchmarkConfig {
+            count: 5,
+            concurrency: 2,
+            duration: None,
+        };
+
+        crate::warm_up(&client, &config).await;
+        let result = measure(&client, &config, &benchmark).await;
+
+        assert_eq!(result.histogram.len(), 5);
+        assert_eq!(result.errors, 0);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.iter().filter(|request| request.starts_with("GET /health ")).count(), 3);
+        assert_eq!(requests.iter().filter(|request| request.starts_with("POST /send_log ")).count(), 5);
+    }
+}
@@ -1,11 +1,20 @@
+mod benchmark;
+mod pacing;
+
 use dotenv::dotenv;
+use pacing::Pacer;
 use polars::prelude::*;
 use polars::frame::row::Row;
 use reqwest;
 use reqwest::Error;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use std::{env, f64};
 
+/// Number of times a log entry is resent after the API answered with 429
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
 /// Configuration for the log sender application.
 ///
 /// Loads settings from environment variables:
@@ -13,12 +22,28 @@ use std::{env, f64};
 /// - REPETITIONS: Number of times to process the log file (i32)
 /// - LOGFILE_PATH: Path to the log file to read from (String)
 /// - ENDPOINT: HTTP endpoint to send logs to (String)
+/// - ENDPOINTS: Optional comma-separated endpoints tried in order on connection failure, replaces ENDPOINT
+/// - SECRET_API_KEY / SECRET_API_KEY_FILE: API key inline or read from a file (e.g. docker secret)
+/// - EXTRA_HEADERS: Optional headers attached to every request, e.g. "X-Tenant: foo, X-Env: prod"
+/// - MAX_RETRY_AFTER_SECS: Upper bound for waiting on a Retry-After header of a 429 response (u64, default 30)
+/// - TARGET_RATE: Optional logs per second to pace sending to, unset or 0 sends as fast as possible (f64)
+/// - RAMP_UP_SECS: Seconds to linearly increase from 1 log/s to TARGET_RATE before holding it (u64, default 0)
+/// - WARMUP_REQUESTS: Throwaway requests sent to WARMUP_PATH before the run to establish connections (usize, default 0)
+/// - WARMUP_PATH: Path on the endpoint host answering warm-up requests (String, default "/health" of the load balancer)
+/// - POOL_IDLE_TIMEOUT_SECS: Idle keep-alive connections are closed after this many seconds, below the server's keep-alive timeout (u64, default 30)
 struct Config {
     endless: bool,
     repetitions: i32,
     logfile_path: String,
-    endpoint: String,
+    endpoints: Vec<String>,
     secret: String,
+    extra_headers: HeaderMap,
+    max_retry_after: Duration,
+    target_rate: Option<f64>,
+    ramp_up: Duration,
+    warmup_requests: usize,
+    warmup_path: String,
+    pool_idle_timeout: Duration,
 }
 
 impl Config {
@@ -42,20 +67,121 @@ impl Config {
                 .map_err(|_| "REPETITIONS must be an integer")?,
             logfile_path: env::var("LOGFILE_PATH")
                 .map_err(|_| "LOGFILE_PATH environment variable is missing")?,
-            endpoint: env::var("ENDPOINT")
-                .map_err(|_| "ENDPOINT environment variable is missing")?,
-            secret: env::var("SECRET_API_KEY")
-                .map_err(|_| "SECRET_API_KEY environment variable is missing")?,
+            endpoints: parse_endpoints(
+                env::var("ENDPOINTS").ok(),
+                env::var("ENDPOINT").ok(),
+            )?,
+            secret: load_secret()?,
+            extra_headers: parse_extra_headers(&env::var("EXTRA_HEADERS").unwrap_or_default())?,
+            max_retry_after: Duration::from_secs(
+                env::var("MAX_RETRY_AFTER_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+            ),
+            target_rate: env::var("TARGET_RATE")
+                .ok()
+                .and_then(|rate| rate.parse::<f64>().ok())
+                .filter(|rate| *rate > 0.0),
+            ramp_up: Duration::from_secs(
+                env::var("RAMP_UP_SECS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .unwrap_or(0),
+            ),
+            warmup_requests: env::var("WARMUP_REQUESTS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| "WARMUP_REQUESTS must be an integer")?,
+            warmup_path: env::var("WARMUP_PATH").unwrap_or_else(|_| "/health".to_string()),
+            pool_idle_timeout: Duration::from_secs(
+                env::var("POOL_IDLE_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+            ),
         })
     }
 }
 
+/// Loads the API secret.
+///
+/// SECRET_API_KEY_FILE takes precedence over SECRET_API_KEY, which allows mounting
+/// the secret as file (docker / kubernetes secrets). Surrounding whitespace of the file is trimmed.
+///
+/// Returns:
+/// - Ok(String) containing the secret
+/// - Err(String) if the file can't be read or neither variable is set
+fn load_secret() -> Result<String, String> {
+    if let Ok(path) = env::var("SECRET_API_KEY_FILE") {
+        return std::fs::read_to_string(&path)
+            .map(|secret| secret.trim().to_string())
+            .map_err(|e| format!("SECRET_API_KEY_FILE '{}' can't be read: {}", path, e));
+    }
+    env::var("SECRET_API_KEY").map_err(|_| "SECRET_API_KEY environment variable is missing".to_string())
+}
+
+/// Determines the endpoints to send logs to.
+///
+/// ENDPOINTS takes precedence over ENDPOINT. The first endpoint is the primary one,
+/// the following ones are used as fallback on connection failures.
+///
+/// Returns:
+/// - Ok(Vec<String>) with at least one endpoint
+/// - Err(String) if neither variable contains an endpoint
+fn parse_endpoints(endpoints: Option<String>, endpoint: Option<String>) -> Result<Vec<String>, String> {
+    let endpoints: Vec<String> = endpoints
+        .filter(|endpoints| !endpoints.trim().is_empty())
+        .or(endpoint)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if endpoints.is_empty() {
+        return Err("ENDPOINT or ENDPOINTS environment variable is missing".to_string());
+    }
+    Ok(endpoints)
+}
+
+/// Parses a comma-separated header list into a HeaderMap.
+///
+/// Expects the format "Name: Value, Other-Name: Other Value". Empty input yields an empty map.
+/// Header values can't contain commas, since the list is split at every comma.
+///
+/// Returns:
+/// - Ok(HeaderMap) containing all parsed headers
+/// - Err(String) if an entry is missing the colon or contains an invalid name / value
+fn parse_extra_headers(raw: &str) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (name, value) = entry
+            .split_once(':')
+            .ok_or_else(|| format!("EXTRA_HEADERS entry '{}' must be in the format 'Name: Value'", entry))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|e| format!("EXTRA_HEADERS contains invalid header name '{}': {}", name.trim(), e))?;
+        let value = HeaderValue::from_str(value.trim())
+            .map_err(|e| format!("EXTRA_HEADERS contains invalid value for '{}': {}", name, e))?;
+        headers.insert(name, value);
+    }
+
+    Ok(headers)
+}
+
+/// Measurements the flags of `exceeded_values` belong to, used for files generated before `exceeded_fields` existed
+const DEFAULT_EXCEEDED_FIELDS: [&str; 2] = ["temperature", "humidity"];
+
 /// Inner message structure containing device information and exceeded threshold values.
+/// `exceeded_fields` names the measurement of the flag at the same index in `exceeded_values`.
 #[derive(Serialize, Clone)]
 struct InnerMsg {
     device: String,
     msg: String,
     exceeded_values: Vec<bool>,
+    exceeded_fields: Vec<String>,
 }
 
 /// Temporary structure to parse the JSON from CSV that matches the log generator's Message structure
@@ -64,6 +190,8 @@ struct CsvMessage {
     device: String,  // Device enum gets serialized as string
     msg: String,
     exceeded_values: [bool; 2],  // Array from log generator
+    #[serde(default)]
+    exceeded_fields: Vec<String>,  // Missing in files of older generator versions
 }
 
 /// Complete log entry structure for serialization to JSON.
@@ -76,6 +204,8 @@ struct LogEntry {
     temperature: f64,
     humidity: f64,
     msg: InnerMsg,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>, // Optional, e.g. "synthetic" for generator output
 }
 
 /// Main application entry point.
@@ -83,19 +213,55 @@ struct LogEntry {
 /// Loads configuration, reads and parses the CSV file once, then either runs endlessly 
 /// or for a specified number of repetitions, sending the same log entries each time.
 /// This approach optimizes performance by avoiding repeated CSV parsing.
+/// Started with `--benchmark`, synthetic log entries are sent instead and latency / throughput is reported.
+/// Invalid or missing environment variables and an unreadable log file are reported without panicking and exit with code 1.
 #[tokio::main]
 async fn main() {
-    let config = Config::load().expect("Failed to load environment variables");
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            // Configuration errors are user errors => no panic backtrace, just the reason
+            eprintln!("Configuration error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let log_entries = process_file(&config);
+    // Benchmark mode sends synthetic entries instead of the log file
+    if env::args().any(|arg| arg == "--benchmark") {
+        benchmark::run_benchmark(&config).await;
+        return;
+    }
+
+    let log_entries = match process_file(&config) {
+        Ok(log_entries) => log_entries,
+        Err(e) => {
+            eprintln!("Log file error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Nothing to send => endless mode would otherwise spin without doing any work
+    if log_entries.is_empty() {
+        return;
+    }
+
+    // One client for all repetitions => connections established by the warm-up stay in the pool
+    let client = reqwest::Client::builder()
+        .default_headers(config.extra_headers.clone())
+        .pool_idle_timeout(config.pool_idle_timeout)
+        .build()
+        .expect("Failed to create HTTP client");
+    warm_up(&client, &config).await;
+
+    let mut pacer = Pacer::new(config.target_rate, config.ramp_up);
 
     if config.endless {
         loop {
-            process_log_entries(&config, &log_entries).await;
+            process_log_entries(&client, &config, &log_entries, &mut pacer).await;
         }
     } else {
         for _n in 0..config.repetitions {
-            process_log_entries(&config, &log_entries).await;
+            process_log_entries(&client, &config, &log_entries, &mut pacer).await;
         }
     }
 }
@@ -109,68 +275,168 @@ async fn main() {
 /// # Arguments
 /// * `config` - Configuration containing file path
 ///
+/// Empty files and files only containing a header yield an empty vector and a warning,
+/// since Polars refuses to read a file without any content.
+///
 /// # Returns
-/// * `Vec<LogEntry>` - Vector of parsed log entries ready for sending
-fn process_file(config: &Config) -> Vec<LogEntry> {
-    
+/// * `Ok(Vec<LogEntry>)` - Vector of parsed log entries ready for sending
+/// * `Err(String)` - If the file can't be opened or isn't valid CSV
+fn process_file(config: &Config) -> Result<Vec<LogEntry>, String> {
+    let file_size = std::fs::metadata(&config.logfile_path)
+        .map_err(|e| format!("Failed to open CSV file '{}': {}", config.logfile_path, e))?
+        .len();
+    if file_size == 0 {
+        eprintln!("Warning: no data rows in {} (file is empty)", config.logfile_path);
+        return Ok(Vec::new());
+    }
+
     // Read CSV using Polars with proper escaping handling
     let df = CsvReadOptions::default()
             .with_has_header(true)
             .try_into_reader_with_file_path(Some(config.logfile_path.clone().into()))
-            .expect("Failed to open CSV file")
+            .map_err(|e| format!("Failed to open CSV file '{}': {}", config.logfile_path, e))?
             .finish()
-            .expect("Failed to read CSV file");
+            .map_err(|e| format!("Failed to read CSV file '{}': {}", config.logfile_path, e))?;
+
+    if df.height() == 0 {
+        eprintln!("Warning: no data rows in {}", config.logfile_path);
+        return Ok(Vec::new());
+    }
 
-    
     // Process all rows into LogEntry structs first
     let mut log_entries = Vec::new();
     for i in 0..df.height() {
-        let row = df.get_row(i).expect("Failed to get row");
-        let log_entry = create_log_entry(row);
-        log_entries.push(log_entry);
+        let row = df.get_row(i).map_err(|e| format!("Failed to get row {}: {}", i + 1, e))?;
+        match create_log_entry(row) {
+            Ok(log_entry) => log_entries.push(log_entry),
+            Err(e) => eprintln!("Skipping row {}: {}", i + 1, e),
+        }
     }
 
-    return log_entries;
-    
+    Ok(log_entries)
+}
+
+/// Sends WARMUP_REQUESTS throwaway requests to the warm-up path of every endpoint host.
+///
+/// The first request to a host pays for connection setup (and TLS handshake), which would otherwise
+/// show up as latency spike in the first sent batch. Failed warm-up requests are only reported.
+///
+/// # Arguments
+/// * `client` - HTTP client used for the following run, its pool keeps the established connections
+/// * `config` - Configuration containing endpoint URLs, API secret and warm-up settings
+async fn warm_up(client: &reqwest::Client, config: &Config) {
+    if config.warmup_requests == 0 {
+        return;
+    }
+
+    for endpoint in &config.endpoints {
+        let mut url = match reqwest::Url::parse(endpoint) {
+            Ok(url) => url,
+            Err(e) => {
+                eprintln!("Skipping warm-up of {}: {}", endpoint, e);
+                continue;
+            }
+        };
+        url.set_path(&config.warmup_path);
+
+        let mut ok = 0;
+        for _ in 0..config.warmup_requests {
+            match client.get(url.clone()).header("X-Api-Key", &config.secret).send().await {
+                Ok(res) if res.status().is_success() => ok += 1,
+                Ok(res) => eprintln!("Warm-up request to {} returned {}", url, res.status()),
+                Err(e) => eprintln!("Warm-up request to {} failed: {}", url, e),
+            }
+        }
+        println!("Warm-up: {}/{} requests to {} succeeded", ok, config.warmup_requests, url);
+    }
 }
 
 /// Sends all log entries to the configured HTTP endpoint.
 ///
-/// Creates an HTTP client and sends each log entry sequentially to the endpoint.
+/// Sends each log entry sequentially to the endpoint using the shared HTTP client.
+/// The active endpoint starts at the primary one and only changes on connection failures.
 /// This function can be called multiple times with the same log entries for
 /// repeated sending scenarios (endless mode or multiple repetitions).
 ///
 /// # Arguments
-/// * `config` - Configuration containing endpoint URL and API secret
+/// * `client` - HTTP client carrying the configured extra headers, reused across repetitions
+/// * `config` - Configuration containing endpoint URLs and API secret
 /// * `log_entries` - Vector of pre-created LogEntry structs to send
-async fn process_log_entries(config: &Config, log_entries: &Vec<LogEntry>) {
-    let client = reqwest::Client::new();
+/// * `pacer` - Limits the send rate if TARGET_RATE is configured, shared across repetitions
+async fn process_log_entries(
+    client: &reqwest::Client,
+    config: &Config,
+    log_entries: &Vec<LogEntry>,
+    pacer: &mut Pacer,
+) {
+    let mut active_endpoint = 0;
 
     // Then send each log entry
     for log_entry in log_entries {
-        send_value(&client, &config.endpoint, &config.secret, log_entry.clone())
+        pacer.wait().await;
+        send_value(
+            client,
+            &config.endpoints,
+            &mut active_endpoint,
+            &config.secret,
+            config.max_retry_after,
+            log_entry.clone(),
+        )
             .await
             .expect("Failed to establish a connection")
     }
 }
 
-/// Sends a single log entry to the HTTP endpoint.
+/// Sends a single log entry to the HTTP endpoint, failing over to the next endpoint on connection errors.
 ///
 /// Serializes the LogEntry to JSON and sends it via POST.
-/// Prints the response status. Handles HTTP errors gracefully.
+/// Prints the response status and the endpoint used. Handles HTTP errors gracefully.
+/// If an endpoint can't be reached, the following endpoints are tried in order (wrapping around)
+/// and the first reachable one stays active for subsequent log entries.
+/// A request on a keep-alive connection the server closed meanwhile is retried once on a fresh connection.
+/// If the API answers with 429, the entry is resent up to MAX_RATE_LIMIT_RETRIES times after waiting
+/// for the duration given by the Retry-After header (1s without header), capped at `max_retry_after`.
 ///
 /// # Arguments
 /// * `client` - HTTP client for making requests
-/// * `endpoint` - URL to send the log entry to
+/// * `endpoints` - URLs to send the log entry to, in failover order
+/// * `active_endpoint` - Index of the endpoint to try first, updated on failover
 /// * `secret` - API secret key for authentication
+/// * `max_retry_after` - Longest wait before resending a rate limited entry
 /// * `log_entry` - Pre-created LogEntry ready for sending
 ///
 /// # Returns
-/// * `Result<(), Error>` - Ok if successful, Error if HTTP request fails
-async fn send_value(client: &reqwest::Client, endpoint: &str, secret: &str, log_entry: LogEntry) -> Result<(), Error> {
-    let res = client.post(endpoint).header("X-Api-Key", secret).json(&log_entry).send().await?;
+/// * `Result<(), Error>` - Ok if successful, Error if no endpoint could be reached
+async fn send_value(
+    client: &reqwest::Client,
+    endpoints: &[String],
+    active_endpoint: &mut usize,
+    secret: &str,
+    max_retry_after: Duration,
+    log_entry: LogEntry,
+) -> Result<(), Error> {
+    let mut retries = 0;
+    let res = loop {
+        let res = post_with_failover(client, endpoints, active_endpoint, secret, &log_entry).await?;
+        if res.status() != reqwest::StatusCode::TOO_MANY_REQUESTS || retries >= MAX_RATE_LIMIT_RETRIES {
+            break res;
+        }
+
+        retries += 1;
+        let wait = parse_retry_after(res.headers())
+            .unwrap_or(Duration::from_secs(1))
+            .min(max_retry_after);
+        println!(
+            "429 ({}), retrying in {:.1}s ({}/{})",
+            endpoints[*active_endpoint],
+            wait.as_secs_f64(),
+            retries,
+            MAX_RATE_LIMIT_RETRIES
+        );
+        tokio::time::sleep(wait).await;
+    };
 
-    println!("{}", res.status());
+    println!("{} ({})", res.status(), endpoints[*active_endpoint]);
 
     match res.error_for_status() {
         Ok(_) => (),
@@ -182,33 +448,150 @@ async fn send_value(client: &reqwest::Client, endpoint: &str, secret: &str, log_
     Ok(())
 }
 
+/// Posts a log entry to the active endpoint, failing over to the following endpoints if it is unreachable.
+///
+/// A request failing on a pooled connection the server already closed is retried once,
+/// the retry transparently uses a fresh connection.
+///
+/// # Returns
+/// * `Result<Response, Error>` - Response of the first reachable endpoint, Error if none could be reached
+async fn post_with_failover(
+    client: &reqwest::Client,
+    endpoints: &[String],
+    active_endpoint: &mut usize,
+    secret: &str,
+    log_entry: &LogEntry,
+) -> Result<reqwest::Response, Error> {
+    let mut attempt = 0;
+    let mut stale_retried = false;
+    loop {
+        let endpoint = &endpoints[*active_endpoint];
+        match client.post(endpoint).header("X-Api-Key", secret).json(log_entry).send().await {
+            Ok(res) => return Ok(res),
+            Err(err) if !stale_retried && is_stale_connection(&err) => {
+                stale_retried = true;
+                println!("Connection to {} was closed ({}), retrying once", endpoint, err);
+            }
+            // Only unreachable endpoints are failed over, HTTP errors are answers of a living API
+            Err(err) if (err.is_connect() || err.is_timeout()) && attempt + 1 < endpoints.len() => {
+                attempt += 1;
+                *active_endpoint = (*active_endpoint + 1) % endpoints.len();
+                println!(
+                    "{} unreachable ({}), failing over to {}",
+                    endpoint, err, endpoints[*active_endpoint]
+                );
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Whether a request failed because the server closed a pooled keep-alive connection.
+///
+/// Walks the error sources for resets / broken pipes and hyper's incomplete message error,
+/// which both occur when a connection is reused right after the server closed it.
+fn is_stale_connection(err: &Error) -> bool {
+    let mut source: Option<&dyn std::error::Error> = Some(err);
+    while let Some(error) = source {
+        if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+            if matches!(
+                io_error.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            ) {
+                return true;
+            }
+        }
+        if error.to_string().contains("connection closed before message completed") {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Reads the Retry-After header of a response.
+///
+/// Supports both formats of the header: delay in seconds ("120") and HTTP-date
+/// ("Wed, 21 Oct 2015 07:28:00 GMT"). Dates in the past yield a zero wait.
+///
+/// Returns:
+/// - Some(Duration) to wait before retrying
+/// - None if the header is missing or malformed
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
 /// Creates a LogEntry from Polars Row data.
 ///
-/// Expects CSV data in the format: timestamp,level,temperature,humidity,msg
-/// where msg is a JSON string created by the log generator
+/// Expects CSV data in the format: timestamp,level,temperature,humidity,msg[,source]
+/// where msg is a JSON string created by the log generator and source is an optional origin marker
 ///
 /// # Arguments
 /// * `row` - Polars Row containing CSV fields
 ///
 /// # Returns
-/// * `LogEntry` - Structured log entry ready for serialization
-fn create_log_entry(row: Row<'_>) -> LogEntry {
+/// * `Ok(LogEntry)` - Structured log entry ready for serialization
+/// * `Err(String)` - If temperature or humidity is not numeric
+fn create_log_entry(row: Row<'_>) -> Result<LogEntry, String> {
     let timestamp = row.0[0].get_str().expect("Failed to get timestamp").to_string();
     let level = row.0[1].get_str().expect("Failed to get level").to_string();
-    let temperature = row.0[2].try_extract::<f64>().expect("Failed to parse temperature");
-    let humidity = row.0[3].try_extract::<f64>().expect("Failed to parse humidity");
+    let temperature = extract_number(&row.0[2], "temperature")?;
+    let humidity = extract_number(&row.0[3], "humidity")?;
     
     // The last field is the JSON-serialized Message struct
     let msg_json = row.0[4].get_str().expect("Failed to get msg");
     let msg: InnerMsg = parse_message_json(msg_json);
 
-    LogEntry {
+    // Older CSV files don't contain the source column
+    let source = row.0.get(5).and_then(|value| value.get_str()).map(str::to_string);
+
+    Ok(LogEntry {
         timestamp,
         level,
         temperature,
         humidity,
         msg,
+        source,
+    })
+}
+
+/// Extracts a numeric CSV value as f64.
+///
+/// Depending on the generator path, numeric columns are inferred as float, integer or string.
+/// Numeric values are converted directly, strings are parsed after trimming whitespace and quotes.
+///
+/// # Arguments
+/// * `value` - Polars value of the column
+/// * `field` - Column name used in the error message
+///
+/// # Returns
+/// * `Ok(f64)` - Parsed number
+/// * `Err(String)` - If the value is neither numeric nor a parseable string
+fn extract_number(value: &AnyValue<'_>, field: &str) -> Result<f64, String> {
+    if let Some(text) = value.get_str() {
+        return text
+            .trim()
+            .trim_matches('"')
+            .parse::<f64>()
+            .map_err(|e| format!("{} '{}' is not numeric: {}", field, text, e));
     }
+
+    value
+        .try_extract::<f64>()
+        .map_err(|e| format!("{} {:?} is not numeric: {}", field, value, e))
 }
 
 /// Parses a JSON string from CSV into InnerMsg.
@@ -231,6 +614,11 @@ fn parse_message_json(msg_json: &str) -> InnerMsg {
             device: csv_msg.device,
             msg: csv_msg.msg,
             exceeded_values: csv_msg.exceeded_values.to_vec(), // Convert [bool; 2] to Vec<bool>
+            exceeded_fields: if csv_msg.exceeded_fields.is_empty() {
+                default_exceeded_fields()
+            } else {
+                csv_msg.exceeded_fields
+            },
         },
         Err(e) => {
             eprintln!("Failed to parse message JSON '{}': {}", unescaped_json, e);
@@ -239,7 +627,361 @@ fn parse_message_json(msg_json: &str) -> InnerMsg {
                 device: "Unknown".to_string(),
                 msg: "Failed to parse message".to_string(),
                 exceeded_values: vec![false, false],
+                exceeded_fields: default_exceeded_fields(),
             }
         }
     }
 }
+
+/// Labels of the exceeded flags as written by the log generator.
+///
+/// # Returns
+/// * `Vec<String>` - Measurement names in the order of `exceeded_values`
+fn default_exceeded_fields() -> Vec<String> {
+    DEFAULT_EXCEEDED_FIELDS.iter().map(|field| field.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Minimal stand-in for the api answering every request with 201. The first `closed_requests`
+    /// requests are read but answered by closing the connection, like a server dropping a keep-alive
+    /// connection. Returns the base url and the request lines of all received requests.
+    pub(crate) fn mock_api(closed_requests: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let received = received.clone();
+                thread::spawn(move || serve(stream, &received, closed_requests));
+            }
+        });
+        (url, requests)
+    }
+
+    /// Answers the requests of a single keep-alive connection until the client closes it.
+    fn serve(stream: TcpStream, requests: &Mutex<Vec<String>>, closed_requests: usize) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut stream = stream;
+        loop {
+            let (mut request_line, mut content_length, mut line) = (String::new(), 0, String::new());
+            if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                return;
+            }
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                let Some((name, value)) = line.split_once(':') else {
+                    continue;
+                };
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let mut requests = requests.lock().unwrap();
+            requests.push(request_line.trim_end().to_string());
+            if requests.len() <= closed_requests {
+                // Dropping both handles closes the connection without an answer
+                return;
+            }
+            drop(requests);
+            stream
+                .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        }
+    }
+
+    /// Url of a port nobody listens on => connections are refused
+    fn unreachable_endpoint() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/send_log", listener.local_addr().unwrap())
+    }
+
+    /// Configuration sending to a single endpoint, independent of the environment
+    pub(crate) fn config(logfile_path: &str, endpoints: Vec<String>) -> Config {
+        Config {
+            endless: false,
+            repetitions: 1,
+            logfile_path: logfile_path.to_string(),
+            endpoints,
+            secret: "123456".to_string(),
+            extra_headers: HeaderMap::new(),
+            max_retry_after: Duration::from_secs(30),
+            target_rate: None,
+            ramp_up: Duration::ZERO,
+            warmup_requests: 0,
+            warmup_path: "/health".to_string(),
+            pool_idle_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Writes a log file into the temp directory, unique per test process and name
+    fn log_file(name: &str, content: &str) -> String {
+        let path = env::temp_dir().join(format!("log-sender-{}-{}.csv", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path.display().to_string()
+    }
+
+    fn log_entry() -> LogEntry {
+        LogEntry {
+            timestamp: "2025-06-01T12:00:00.000Z".to_string(),
+            level: "INFO".to_string(),
+            temperature: 21.5,
+            humidity: 40.0,
+            msg: InnerMsg {
+                device: "Arduino0".to_string(),
+                msg: "INFO: all values in range".to_string(),
+                exceeded_values: vec![false, false],
+                exceeded_fields: default_exceeded_fields(),
+            },
+            source: None,
+        }
+    }
+
+    #[test]
+    fn parse_extra_headers_reads_comma_separated_list() {
+        let headers = parse_extra_headers(" X-Tenant: foo , X-Env:prod,").unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-tenant"], "foo");
+        assert_eq!(headers["x-env"], "prod");
+        assert!(parse_extra_headers("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_extra_headers_rejects_invalid_entries() {
+        assert!(parse_extra_headers("X-Tenant foo").is_err());
+        assert!(parse_extra_headers("Bad Name: foo").is_err());
+        // Commas always separate entries => the part after a comma within a value is no header
+        assert!(parse_extra_headers("X-List: a, b").is_err());
+    }
+
+    #[test]
+    fn parse_endpoints_prefers_endpoints_list() {
+        assert_eq!(
+            parse_endpoints(Some(" http://a:8080 ,,http://b:8080 ".to_string()), Some("http://c".to_string())),
+            Ok(vec!["http://a:8080".to_string(), "http://b:8080".to_string()])
+        );
+    }
+
+    #[test]
+    fn parse_endpoints_falls_back_to_single_endpoint() {
+        assert_eq!(
+            parse_endpoints(Some("  ".to_string()), Some("http://c".to_string())),
+            Ok(vec!["http://c".to_string()])
+        );
+        assert_eq!(parse_endpoints(None, Some("http://c".to_string())), Ok(vec!["http://c".to_string()]));
+    }
+
+    #[test]
+    fn parse_endpoints_requires_an_endpoint() {
+        assert!(parse_endpoints(None, None).is_err());
+        assert!(parse_endpoints(Some(",".to_string()), None).is_err());
+    }
+
+    fn retry_after(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn parse_retry_after_reads_seconds() {
+        assert_eq!(parse_retry_after(&retry_after(" 3 ")), Some(Duration::from_secs(3)));
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+        assert_eq!(parse_retry_after(&retry_after("soon")), None);
+    }
+
+    #[test]
+    fn parse_retry_after_reads_http_dates() {
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = parse_retry_after(&retry_after(&later)).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        // Dates in the past => retry right away
+        assert_eq!(
+            parse_retry_after(&retry_after("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn post_with_failover_lands_on_secondary_if_primary_is_unreachable() {
+        let (url, requests) = mock_api(0);
+        let endpoints = vec![unreachable_endpoint(), format!("{}/send_log", url)];
+        let mut active_endpoint = 0;
+
+        let res = post_with_failover(&reqwest::Client::new(), &endpoints, &mut active_endpoint, "123456", &log_entry())
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), reqwest::StatusCode::CREATED);
+        assert_eq!(active_endpoint, 1);
+        assert_eq!(*requests.lock().unwrap(), vec!["POST /send_log HTTP/1.1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn post_with_failover_reports_error_if_no_endpoint_is_reachable() {
+        let endpoints = vec![unreachable_endpoint(), unreachable_endpoint()];
+        let mut active_endpoint = 0;
+
+        let error = post_with_failover(&reqwest::Client::new(), &endpoints, &mut active_endpoint, "123456", &log_entry())
+            .await
+            .unwrap_err();
+
+        assert!(error.is_connect());
+    }
+
+    #[test]
+    fn process_file_reads_rows() {
+        let path = log_file(
+            "rows",
+            "timestamp,level,temperature,humidity,msg\n\
+             2025-06-01T12:00:00.000Z,INFO,21.5,40.0,\"{\"\"device\"\":\"\"Arduino0\"\",\"\"msg\"\":\"\"ok\"\",\"\"exceeded_values\"\":[false,false]}\"\n",
+        );
+
+        let log_entries = process_file(&config(&path, Vec::new())).unwrap();
+
+        assert_eq!(log_entries.len(), 1);
+        assert_eq!(log_entries[0].msg.device, "Arduino0");
+        assert_eq!(log_entries[0].temperature, 21.5);
+    }
+
+    #[test]
+    fn process_file_yields_no_entries_for_empty_file() {
+        let path = log_file("empty", "");
+
+        assert!(process_file(&config(&path, Vec::new())).unwrap().is_empty());
+    }
+
+    #[test]
+    fn process_file_yields_no_entries_for_header_only_file() {
+        let path = log_file("header", "timestamp,level,temperature,humidity,msg\n");
+
+        assert!(process_file(&config(&path, Vec::new())).unwrap().is_empty());
+    }
+
+    #[test]
+    fn process_file_reports_missing_file() {
+        let error = process_file(&config("/nonexistent/logs.csv", Vec::new())).unwrap_err();
+
+        assert!(error.starts_with("Failed to open CSV file '/nonexistent/logs.csv'"));
+    }
+
+    #[test]
+    fn extract_number_reads_numeric_values() {
+        assert_eq!(extract_number(&AnyValue::Float64(21.5), "temperature"), Ok(21.5));
+        assert_eq!(extract_number(&AnyValue::Int64(40), "humidity"), Ok(40.0));
+        assert_eq!(extract_number(&AnyValue::Float64(-12.25), "temperature"), Ok(-12.25));
+    }
+
+    #[test]
+    fn extract_number_parses_strings() {
+        assert_eq!(extract_number(&AnyValue::String(" 21.5 "), "temperature"), Ok(21.5));
+        assert_eq!(extract_number(&AnyValue::String("\"-3\""), "temperature"), Ok(-3.0));
+    }
+
+    #[test]
+    fn extract_number_rejects_missing_and_malformed_values() {
+        assert!(extract_number(&AnyValue::Null, "temperature").is_err());
+        assert!(extract_number(&AnyValue::String(""), "temperature").is_err());
+
+        let error = extract_number(&AnyValue::String("warm"), "temperature").unwrap_err();
+        assert!(error.starts_with("temperature 'warm' is not numeric"));
+    }
+
+    /// Valid configuration, DEPLOYMENT=PROD keeps a local .env file out of the tests
+    const VALID_ENV: [(&str, &str); 6] = [
+        ("DEPLOYMENT", "PROD"),
+        ("ENDLESS", "false"),
+        ("REPETITIONS", "1"),
+        ("LOGFILE_PATH", "logs.csv"),
+        ("ENDPOINT", "http://localhost:8080/send_log"),
+        ("SECRET_API_KEY", "123456"),
+    ];
+
+    /// Loads the valid configuration with a single variable changed (None removes it)
+    fn load_with(name: &str, value: Option<&str>) -> Result<Config, String> {
+        // SAFETY: only `config_load_reports_invalid_variables` touches these variables
+        unsafe {
+            for variable in ["ENDPOINTS", "SECRET_API_KEY_FILE", "EXTRA_HEADERS", "WARMUP_REQUESTS"] {
+                env::remove_var(variable);
+            }
+            for (variable, valid) in VALID_ENV {
+                env::set_var(variable, valid);
+            }
+            match value {
+                Some(value) => env::set_var(name, value),
+                None => env::remove_var(name),
+            }
+        }
+        Config::load()
+    }
+
+    // Single test => the cases don't race on the shared environment variables
+    #[test]
+    fn config_load_reports_invalid_variables() {
+        let cases = [
+            ("ENDLESS", None, "ENDLESS environment variable is missing"),
+            ("ENDLESS", Some("yes"), "ENDLESS must be a boolean"),
+            ("REPETITIONS", None, "REPETITIONS environment variable is missing"),
+            ("REPETITIONS", Some("1.5"), "REPETITIONS must be an integer"),
+            ("LOGFILE_PATH", None, "LOGFILE_PATH environment variable is missing"),
+            ("ENDPOINT", None, "ENDPOINT or ENDPOINTS environment variable is missing"),
+            ("SECRET_API_KEY", None, "SECRET_API_KEY environment variable is missing"),
+            ("SECRET_API_KEY_FILE", Some("/nonexistent/secret"), "SECRET_API_KEY_FILE '/nonexistent/secret' can't be read"),
+            ("EXTRA_HEADERS", Some("X-Tenant foo"), "EXTRA_HEADERS entry 'X-Tenant foo' must be in the format 'Name: Value'"),
+            ("WARMUP_REQUESTS", Some("some"), "WARMUP_REQUESTS must be an integer"),
+        ];
+
+        for (name, value, message) in cases {
+            let error = load_with(name, value).err().unwrap();
+            assert!(error.starts_with(message), "{}={:?}: {}", name, value, error);
+        }
+        assert!(load_with("ENDPOINTS", Some("http://a:8080,http://b:8080")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn post_with_failover_retries_closed_connection_once() {
+        let (url, requests) = mock_api(1);
+        let endpoints = vec![format!("{}/send_log", url)];
+        let mut active_endpoint = 0;
+
+        let res = post_with_failover(&reqwest::Client::new(), &endpoints, &mut active_endpoint, "123456", &log_entry())
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), reqwest::StatusCode::CREATED);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn post_with_failover_gives_up_if_retry_is_closed_as_well() {
+        let (url, requests) = mock_api(2);
+        let endpoints = vec![format!("{}/send_log", url)];
+        let mut active_endpoint = 0;
+
+        let error = post_with_failover(&reqwest::Client::new(), &endpoints, &mut active_endpoint, "123456", &log_entry())
+            .await
+            .unwrap_err();
+
+        assert!(is_stale_connection(&error));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Lowest rate the ramp starts with, so the first log entries don't wait forever
+const MIN_RATE: f64 = 1.0;
+/// Interval in which the scheduled and achieved rate are reported during the ramp-up
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Paces sending to a target rate with an optional linear ramp-up (soak testing).
+///
+/// Starts at 1 log/s and increases linearly to the target rate within the ramp-up duration,
+/// afterwards the target rate is held. The ramp starts with the first send and spans all repetitions.
+pub struct Pacer {
+    target_rate: Option<f64>,
+    ramp_up: Duration,
+    started: Option<Instant>,
+    next_send: Instant,
+    last_report: Instant,
+    sent_since_report: u64,
+    ramp_finished: bool,
+}
+
+impl Pacer {
+    /// Creates a pacer, without target rate log entries are sent as fast as possible.
+    ///
+    /// # Arguments
+    /// * `target_rate` - Logs per second reached at the end of the ramp-up (TARGET_RATE)
+    /// * `ramp_up` - Duration of the linear ramp-up (RAMP_UP_SECS)
+    pub fn new(target_rate: Option<f64>, ramp_up: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            target_rate,
+            ramp_up,
+            started: None,
+            next_send: now,
+            last_report: now,
+            sent_since_report: 0,
+            ramp_finished: false,
+        }
+    }
+
+    /// Scheduled rate after `elapsed` time since the first send.
+    ///
+    /// # Arguments
+    /// * `target_rate` - Rate that is reached at the end of the ramp-up
+    /// * `ramp_up` - Duration of the linear ramp-up, zero starts at the target rate
+    /// * `elapsed` - Time since the first send
+    ///
+    /// # Returns
+    /// * `f64` - Logs per second, between MIN_RATE (or the target if lower) and the target rate
+    fn rate_at(target_rate: f64, ramp_up: Duration, elapsed: Duration) -> f64 {
+        if elapsed >= ramp_up {
+            return target_rate;
+        }
+        let progress = elapsed.as_secs_f64() / ramp_up.as_secs_f64();
+        (target_rate * progress).max(MIN_RATE.min(target_rate))
+    }
+
+    /// Waits until the next log entry may be sent according to the current rate.
+    ///
+    /// Returns immediately if no TARGET_RATE is configured. While ramping up, the scheduled
+    /// and achieved rate are printed every second.
+    pub async fn wait(&mut self) {
+        let Some(target_rate) = self.target_rate else {
+            return;
+        };
+
+        let now = Instant::now();
+        let started = match self.started {
+            Some(started) => started,
+            None => {
+                println!(
+                    "Pacing to {:.1} logs/s with {}s ramp-up",
+                    target_rate,
+                    self.ramp_up.as_secs()
+                );
+                self.started = Some(now);
+                self.last_report = now;
+                now
+            }
+        };
+
+        let elapsed = now.duration_since(started);
+        let rate = Self::rate_at(target_rate, self.ramp_up, elapsed);
+        self.report(rate, elapsed);
+
+        if self.next_send > now {
+            tokio::time::sleep_until(self.next_send).await;
+        }
+        // Falling behind doesn't result in bursts, the schedule restarts from now
+        self.next_send = self.next_send.max(now) + Duration::from_secs_f64(1.0 / rate);
+        self.sent_since_report += 1;
+    }
+
+    /// Prints the scheduled and achieved rate once per REPORT_INTERVAL during the ramp-up.
+    fn report(&mut self, rate: f64, elapsed: Duration) {
+        if self.ramp_finished {
+            return;
+        }
+        if elapsed >= self.ramp_up {
+            self.ramp_finished = true;
+            println!("Ramp-up finished, holding {:.1} logs/s", rate);
+            return;
+        }
+
+        let since_report = self.last_report.elapsed();
+        if since_report >= REPORT_INTERVAL {
+            println!(
+                "Ramp-up {:>4}s: scheduled {:.1} logs/s, achieved {:.1} logs/s",
+                elapsed.as_secs(),
+                rate,
+                self.sent_since_report as f64 / since_report.as_secs_f64()
+            );
+            self.last_report = Instant::now();
+            self.sent_since_report = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_at_ramps_linearly_to_target() {
+        let ramp_up = Duration::from_secs(10);
+
+        assert_eq!(Pacer::rate_at(100.0, ramp_up, Duration::ZERO), MIN_RATE);
+        assert_eq!(Pacer::rate_at(100.0, ramp_up, Duration::from_secs(5)), 50.0);
+        assert_eq!(Pacer::rate_at(100.0, ramp_up, Duration::from_secs(30)), 100.0);
+    }
+
+    #[test]
+    fn rate_at_without_ramp_up_or_below_min_rate() {
+        assert_eq!(Pacer::rate_at(100.0, Duration::ZERO, Duration::ZERO), 100.0);
+        assert_eq!(Pacer::rate_at(0.5, Duration::from_secs(10), Duration::ZERO), 0.5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_holds_target_rate() {
+        let mut pacer = Pacer::new(Some(10.0), Duration::ZERO);
+        let start = Instant::now();
+
+        for _ in 0..11 {
+            pacer.wait().await;
+        }
+
+        // First send right away, then one every 100ms (the paused clock advances in whole milliseconds)
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_millis(1020));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_without_target_rate_returns_immediately() {
+        let mut pacer = Pacer::new(None, Duration::ZERO);
+        let start = Instant::now();
+
+        for _ in 0..1000 {
+            pacer.wait().await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
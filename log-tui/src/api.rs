@@ -1,7 +1,10 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Response,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "UPPERCASE")]
@@ -9,6 +12,7 @@ pub enum LogLevel {
     Critical,
     Warn,
     Info,
+    Debug,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -16,6 +20,38 @@ pub struct InnerMsg {
     pub device: String,
     pub msg: String,
     pub exceeded_values: Vec<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exceeded_fields: Vec<String>,
+}
+
+/// Measurement names of the exceeded flags for logs sent before `exceeded_fields` existed
+const DEFAULT_EXCEEDED_FIELDS: [&str; 2] = ["temperature", "humidity"];
+
+impl InnerMsg {
+    /// Labels every exceeded flag with the measurement it belongs to.
+    ///
+    /// Falls back to the generator's order (temperature, humidity) if the log
+    /// doesn't name its fields.
+    ///
+    /// # Returns
+    ///
+    /// Readable summary, e.g. "temperature: exceeded, humidity: ok"
+    pub fn exceeded_summary(&self) -> String {
+        self.exceeded_values
+            .iter()
+            .enumerate()
+            .map(|(i, exceeded)| {
+                let field = self
+                    .exceeded_fields
+                    .get(i)
+                    .map(String::as_str)
+                    .or(DEFAULT_EXCEEDED_FIELDS.get(i).copied())
+                    .unwrap_or("unknown");
+                format!("{}: {}", field, if *exceeded { "exceeded" } else { "ok" })
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -25,6 +61,8 @@ pub struct LogEntry {
     pub temperature: f64,
     pub humidity: f64,
     pub msg: InnerMsg,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingested_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -32,11 +70,16 @@ pub struct ContainerLogEntry {
     pub timestamp: DateTime<Utc>,
     pub container_name: String,
     pub log_message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingested_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct LogsResponse {
     pub logs: Vec<LogEntry>,
+    /// Number of all matching logs, only returned by `/logs` (not by search or older APIs)
+    #[serde(default)]
+    pub total: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,6 +87,96 @@ pub struct ContainerLogsResponse {
     pub logs: Vec<ContainerLogEntry>,
 }
 
+/// Parses a successful API response or surfaces the API's error message.
+///
+/// Checks the HTTP status before deserializing so that error bodies returned by
+/// the log forwarding API don't end up as opaque deserialization failures.
+///
+/// # Arguments
+///
+/// * `response` - Response returned by the log forwarding API
+///
+/// # Returns
+///
+/// `Ok(T)` for 2xx responses with a valid body, otherwise an error containing
+/// the status code and the message reported by the API
+async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("API returned {}: {}", status, extract_error_message(&body)));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Extracts a human-readable message from an API error body.
+///
+/// The log forwarding API answers with `{ "error": "..." }` (see its `ServerError`),
+/// a `message` field is accepted as well. Other bodies (e.g. of a proxy in front of
+/// the API) are returned as trimmed plain text.
+///
+/// # Arguments
+///
+/// * `body` - Raw error response body
+///
+/// # Returns
+///
+/// The error message or `"No error details provided"` for empty bodies
+fn extract_error_message(body: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+        for key in ["error", "message"] {
+            if let Some(message) = json.get(key).and_then(|value| value.as_str()) {
+                return message.to_string();
+            }
+        }
+    }
+
+    let body = body.trim();
+    if body.is_empty() {
+        "No error details provided".to_string()
+    } else {
+        body.to_string()
+    }
+}
+
+/// Parses a comma-separated list of extra HTTP headers.
+///
+/// Used for the `EXTRA_HEADERS` environment variable so the TUI can talk to
+/// APIs behind gateways that require additional headers (e.g. tenant headers).
+///
+/// # Arguments
+///
+/// * `raw` - Header list in the format "Name: Value, Other-Name: Other Value".
+///   Header values can't contain commas, since the list is split at every comma
+///
+/// # Returns
+///
+/// `Ok(HeaderMap)` with all parsed headers (empty for empty input), or an error
+/// describing the first invalid entry
+///
+/// # Example
+///
+/// ```rust
+/// let headers = parse_extra_headers("X-Tenant: foo, X-Env: prod")?;
+/// ```
+pub fn parse_extra_headers(raw: &str) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (name, value) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("EXTRA_HEADERS entry '{}' must be in the format 'Name: Value'", entry))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|e| anyhow!("EXTRA_HEADERS contains invalid header name '{}': {}", name.trim(), e))?;
+        let value = HeaderValue::from_str(value.trim())
+            .map_err(|e| anyhow!("EXTRA_HEADERS contains invalid value for '{}': {}", name, e))?;
+        headers.insert(name, value);
+    }
+
+    Ok(headers)
+}
+
 pub struct ApiClient {
     client: Client,
     base_url: String,
@@ -60,6 +193,7 @@ impl ApiClient {
     /// # Arguments
     ///
     /// * `base_url` - Base URL of the log forwarding API (e.g., "http://localhost:8080")
+    /// * `extra_headers` - Additional headers attached to every request
     ///
     /// # Returns
     ///
@@ -68,11 +202,14 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust
-    /// let client = ApiClient::new("http://localhost:8080".to_string());
+    /// let client = ApiClient::new("http://localhost:8080".to_string(), HeaderMap::new());
     /// ```
-    pub fn new(base_url: String) -> Self {
+    pub fn new(base_url: String, extra_headers: HeaderMap) -> Self {
         Self {
-            client: Client::new(),
+            client: Client::builder()
+                .default_headers(extra_headers)
+                .build()
+                .expect("Failed to create HTTP client"),
             base_url,
             api_key: None,
         }
@@ -110,21 +247,22 @@ impl ApiClient {
     ///
     /// * `limit` - Maximum number of logs to retrieve (default: server-defined)
     /// * `offset` - Number of logs to skip for pagination (default: 0)
-    /// * `level` - Filter by log level ("CRITICAL", "WARN", "INFO")
+    /// * `level` - Filter by log level ("CRITICAL", "WARN", "INFO", "DEBUG")
     /// * `device` - Filter by device name (URL-encoded automatically)
     /// * `from` - Start of date range filter (RFC3339 format)
     /// * `to` - End of date range filter (RFC3339 format)
     ///
     /// # Returns
     ///
-    /// `Ok(Vec<LogEntry>)` on success, containing the filtered log entries.
+    /// `Ok(LogsResponse)` on success, containing the filtered log entries and the total number of matches.
     /// Returns an error if the request fails or authentication is invalid.
+    /// Non-2xx responses surface the error message reported by the API.
     ///
     /// # Example
     ///
     /// ```rust
     /// // Fetch latest 50 critical logs
-    /// let logs = client.fetch_logs(
+    /// let response = client.fetch_logs(
     ///     Some(50),
     ///     Some(0),
     ///     Some("CRITICAL"),
@@ -141,7 +279,7 @@ impl ApiClient {
         device: Option<&str>,
         from: Option<DateTime<Utc>>,
         to: Option<DateTime<Utc>>,
-    ) -> Result<Vec<LogEntry>> {
+    ) -> Result<LogsResponse> {
         let mut url = format!("{}/logs", self.base_url);
         let mut params = Vec::new();
 
@@ -176,8 +314,8 @@ impl ApiClient {
     }
     
     let response = request.send().await?;
-    let logs_response: LogsResponse = response.json().await?;
-    Ok(logs_response.logs)
+    let logs_response: LogsResponse = parse_response(response).await?;
+    Ok(logs_response)
     }
 
     /// Performs full-text search on sensor logs.
@@ -196,6 +334,7 @@ impl ApiClient {
     ///
     /// `Ok(Vec<LogEntry>)` containing matching log entries sorted by relevance.
     /// Returns an error if the request fails or authentication is invalid.
+    /// Non-2xx responses surface the error message reported by the API.
     ///
     /// # Example
     ///
@@ -229,7 +368,7 @@ impl ApiClient {
         }
         
         let response = request.send().await?;
-        let logs_response: LogsResponse = response.json().await?;
+        let logs_response: LogsResponse = parse_response(response).await?;
         Ok(logs_response.logs)
     }
 
@@ -282,7 +421,7 @@ impl ApiClient {
         }
         
         let response = request.send().await?;
-        let logs_response: ContainerLogsResponse = response.json().await?;
+        let logs_response: ContainerLogsResponse = parse_response(response).await?;
         Ok(logs_response.logs)
     }
 
@@ -362,7 +501,85 @@ impl ApiClient {
         }
         
         let response = request.send().await?;
-        let logs_response: ContainerLogsResponse = response.json().await?;
+        let logs_response: ContainerLogsResponse = parse_response(response).await?;
         Ok(logs_response.logs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves a single raw HTTP response on a random local port and returns its url.
+    async fn mock_response(status_line: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await;
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn extract_error_message_reads_server_error_json() {
+        assert_eq!(extract_error_message(r#"{"error":"Invalid api key"}"#), "Invalid api key");
+        assert_eq!(extract_error_message(r#"{"message":"Bad gateway"}"#), "Bad gateway");
+    }
+
+    #[test]
+    fn extract_error_message_falls_back_to_plain_text() {
+        assert_eq!(extract_error_message("  upstream timed out \n"), "upstream timed out");
+        assert_eq!(extract_error_message(""), "No error details provided");
+    }
+
+    #[tokio::test]
+    async fn parse_response_surfaces_error_of_500() {
+        let url = mock_response("500 Internal Server Error", r#"{"error":"Searching logs failed!"}"#).await;
+        let response = reqwest::get(&url).await.unwrap();
+
+        let error = parse_response::<LogsResponse>(response).await.unwrap_err().to_string();
+        assert!(error.contains("500"), "{}", error);
+        assert!(error.contains("Searching logs failed!"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn parse_response_accepts_empty_logs() {
+        let url = mock_response("200 OK", r#"{"logs":[]}"#).await;
+        let response = reqwest::get(&url).await.unwrap();
+
+        let logs = parse_response::<LogsResponse>(response).await.unwrap();
+        assert!(logs.logs.is_empty());
+        assert_eq!(logs.total, None);
+    }
+
+    #[test]
+    fn parse_extra_headers_reads_comma_separated_list() {
+        let headers = parse_extra_headers(" X-Tenant: foo , X-Env:prod,").unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-tenant"], "foo");
+        assert_eq!(headers["x-env"], "prod");
+        assert!(parse_extra_headers("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_extra_headers_rejects_invalid_entries() {
+        assert!(parse_extra_headers("X-Tenant foo").is_err());
+        assert!(parse_extra_headers("Bad Name: foo").is_err());
+        // Commas always separate entries => the part after a comma within a value is no header
+        assert!(parse_extra_headers("X-List: a, b").is_err());
+    }
+}
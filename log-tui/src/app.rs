@@ -1,5 +1,15 @@
 use crate::api::{ApiClient, LogEntry, LogLevel, ContainerLogEntry};
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow, bail};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use chrono::{DateTime, Utc};
+use ratatui::style::Color;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs, io, io::Write, path::PathBuf, process::Command, str::FromStr};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -9,6 +19,8 @@ pub enum Mode {
     Search,
     Limit,
     Details,
+    Diff,
+    Bookmarks,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -38,6 +50,249 @@ pub enum LogEntryType {
     Container(ContainerLogEntry),
 }
 
+impl LogEntryType {
+    /// Returns all displayable fields of the entry as (name, value) pairs.
+    ///
+    /// Used by the diff view to compare two entries field by field.
+    ///
+    /// # Returns
+    ///
+    /// Ordered list of field names and their string representation
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            LogEntryType::Regular(log_entry) => vec![
+                ("Timestamp", log_entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+                ("Level", format!("{:?}", log_entry.level)),
+                ("Device", log_entry.msg.device.clone()),
+                ("Temperature", format!("{:.2}°C", log_entry.temperature)),
+                ("Humidity", format!("{:.2}%", log_entry.humidity)),
+                ("Message", log_entry.msg.msg.clone()),
+                ("Exceeded Values", log_entry.msg.exceeded_summary()),
+            ],
+            LogEntryType::Container(log_entry) => vec![
+                ("Timestamp", log_entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+                ("Container", log_entry.container_name.clone()),
+                ("Message", log_entry.log_message.clone()),
+            ],
+        }
+    }
+
+    /// Returns the raw entry as pretty printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        let json = match self {
+            LogEntryType::Regular(log_entry) => serde_json::to_string_pretty(log_entry)?,
+            LogEntryType::Container(log_entry) => serde_json::to_string_pretty(log_entry)?,
+        };
+        Ok(json)
+    }
+
+    /// Returns the timestamp of the entry.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            LogEntryType::Regular(log_entry) => log_entry.timestamp,
+            LogEntryType::Container(log_entry) => log_entry.timestamp,
+        }
+    }
+
+    /// Returns `true` if any threshold of a sensor log was exceeded, container logs have no thresholds.
+    pub fn has_exceeded_values(&self) -> bool {
+        match self {
+            LogEntryType::Regular(log_entry) => log_entry.msg.exceeded_values.iter().any(|exceeded| *exceeded),
+            LogEntryType::Container(_) => false,
+        }
+    }
+
+    /// Returns the key two entries are considered duplicates by: (device / container, message).
+    pub fn duplicate_key(&self) -> (&str, &str) {
+        match self {
+            LogEntryType::Regular(log_entry) => (&log_entry.msg.device, &log_entry.msg.msg),
+            LogEntryType::Container(log_entry) => (&log_entry.container_name, &log_entry.log_message),
+        }
+    }
+}
+
+/// Parses a level color mapping like `critical=magenta, warn=#ffaa00`.
+///
+/// Level names are case insensitive, colors may be given as name (`red`, `lightblue`),
+/// hex value (`#ff0000`) or ANSI index (`208`).
+///
+/// # Arguments
+///
+/// * `raw` - Comma separated `level=color` pairs, empty input yields an empty map
+///
+/// # Returns
+///
+/// Map from lowercase level name to color, or an error naming the invalid entry
+pub fn parse_level_colors(raw: &str) -> Result<HashMap<String, Color>> {
+    let mut colors = HashMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (level, color) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow!("LEVEL_COLORS entry '{}' must be in the format 'level=color'", entry))?;
+        let color = Color::from_str(color.trim())
+            .map_err(|_| anyhow!("LEVEL_COLORS contains invalid color '{}'", color.trim()))?;
+        colors.insert(level.trim().to_lowercase(), color);
+    }
+
+    Ok(colors)
+}
+
+/// A bookmarked point in the log stream, identified by timestamp and device (or container).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub timestamp: DateTime<Utc>,
+    pub source: String,
+}
+
+impl Bookmark {
+    /// Creates a bookmark pointing to the given log entry.
+    pub fn from_log(log: &LogEntryType) -> Self {
+        Self {
+            timestamp: log.timestamp(),
+            source: log.duplicate_key().0.to_string(),
+        }
+    }
+
+    /// Returns `true` if the bookmark points to the given log entry.
+    pub fn matches(&self, log: &LogEntryType) -> bool {
+        self.timestamp == log.timestamp() && self.source == log.duplicate_key().0
+    }
+}
+
+/// A row of the log list covering `count` consecutive logs starting at `start`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogGroup {
+    pub start: usize,
+    pub count: usize,
+}
+
+impl LogGroup {
+    /// Returns `true` if the log at `index` is part of this group.
+    pub fn contains(&self, index: usize) -> bool {
+        index >= self.start && index < self.start + self.count
+    }
+}
+
+/// Collapses consecutive duplicate logs into groups, like `uniq -c`.
+///
+/// Logs are duplicates if they share the same device (or container) and message.
+///
+/// # Arguments
+///
+/// * `logs` - The logs in display order
+///
+/// # Returns
+///
+/// One `LogGroup` per run of consecutive duplicates, in display order
+pub fn collapse_duplicates(logs: &[LogEntryType]) -> Vec<LogGroup> {
+    let mut groups: Vec<LogGroup> = Vec::new();
+
+    for (index, log) in logs.iter().enumerate() {
+        match groups.last_mut() {
+            Some(group) if logs[group.start].duplicate_key() == log.duplicate_key() => {
+                group.count += 1;
+            }
+            _ => groups.push(LogGroup { start: index, count: 1 }),
+        }
+    }
+
+    groups
+}
+
+/// A single compared field of the diff view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub name: &'static str,
+    pub marked: String,
+    pub selected: String,
+}
+
+impl FieldDiff {
+    /// Returns `true` if the field value differs between both entries.
+    pub fn differs(&self) -> bool {
+        self.marked != self.selected
+    }
+}
+
+/// Computes a field-by-field diff between two log entries.
+///
+/// Fields are listed in the order of the marked entry, followed by fields
+/// only present on the selected entry. If both entries are of different types,
+/// fields missing on one side are shown as `-`.
+///
+/// # Arguments
+///
+/// * `marked` - The log entry marked via `space`
+/// * `selected` - The currently selected log entry
+///
+/// # Returns
+///
+/// List of `FieldDiff` entries, one per field present on either side
+pub fn diff_fields(marked: &LogEntryType, selected: &LogEntryType) -> Vec<FieldDiff> {
+    let marked_fields = marked.fields();
+    let selected_fields = selected.fields();
+
+    let lookup = |fields: &[(&'static str, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let mut names: Vec<&'static str> = marked_fields.iter().map(|(name, _)| *name).collect();
+    for (name, _) in &selected_fields {
+        if !names.contains(name) {
+            names.push(*name);
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| FieldDiff {
+            name,
+            marked: lookup(&marked_fields, name),
+            selected: lookup(&selected_fields, name),
+        })
+        .collect()
+}
+
+/// Computes the bounds of a time window centered on a timestamp.
+///
+/// # Arguments
+///
+/// * `timestamp` - Center of the window, e.g. the timestamp of the selected log
+/// * `window` - Span before and after the timestamp
+///
+/// # Returns
+///
+/// `(from, to)` with `from = timestamp - window` and `to = timestamp + window`
+pub fn time_window_around(timestamp: DateTime<Utc>, window: Duration) -> (DateTime<Utc>, DateTime<Utc>) {
+    let window = chrono::Duration::seconds(window.as_secs() as i64);
+    (timestamp - window, timestamp + window)
+}
+
+/// Decides whether a refresh keeps the selection on the newest log.
+///
+/// # Arguments
+///
+/// * `auto_follow` - Whether auto-follow is enabled
+/// * `rows` - Rows of the log list, see `App::visible_rows`
+/// * `newest` - Index of the newest log, `None` if there is no fixed newest position
+/// * `selected` - Index of the selected log
+///
+/// # Returns
+///
+/// `true` if auto-follow is enabled and the selection is on the row of the newest log,
+/// `false` once the user moved away from it
+pub fn sticks_to_newest(auto_follow: bool, rows: &[LogGroup], newest: Option<usize>, selected: usize) -> bool {
+    let Some(newest) = newest else {
+        return false;
+    };
+    auto_follow && rows.iter().any(|group| group.contains(newest) && group.contains(selected))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SortField {
     Timestamp,
@@ -86,6 +341,18 @@ pub struct App {
     pub error_message: Option<String>,
     pub api_key: Option<String>,
     pub auth_error: Option<String>,
+    pub marked_log: Option<LogEntryType>,
+    pub collapse_duplicates: bool,
+    pub bookmarks: Vec<Bookmark>,
+    pub selected_bookmark: usize,
+    pub bookmarks_file: Option<PathBuf>,
+    pub auto_follow: bool,
+    pub level_colors: HashMap<String, Color>,
+    pub exceeded_only: bool,
+    pub details_json: bool,
+    pub time_window: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    pub focus_window: Duration,
+    pub total_logs: Option<u64>,
 }
 
 impl App {
@@ -98,6 +365,10 @@ impl App {
     /// # Arguments
     ///
     /// * `api_base_url` - Base URL for the log forwarding API
+    /// * `extra_headers` - Additional headers attached to every API request
+    /// * `bookmarks_file` - Optional file bookmarks are loaded from and persisted to
+    /// * `level_colors` - Level color overrides, see `parse_level_colors`
+    /// * `focus_window` - Span before and after the selected log shown by `focus_around_selected`
     ///
     /// # Returns
     ///
@@ -107,7 +378,20 @@ impl App {
     /// - Default limit of 100 logs
     /// - Timestamp sorting in descending order
     /// - Sensor logs index selected
-    pub fn new(api_base_url: String) -> Self {
+    pub fn new(
+        api_base_url: String,
+        extra_headers: HeaderMap,
+        bookmarks_file: Option<PathBuf>,
+        level_colors: HashMap<String, Color>,
+        focus_window: Duration,
+    ) -> Self {
+        // A missing or unreadable file just starts without bookmarks
+        let bookmarks = bookmarks_file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
         Self {
             logs: Vec::new(),
             selected_index: 0,
@@ -118,7 +402,7 @@ impl App {
             sort_state: SortState::default(),
             log_limit: 100,
             input_buffer: String::new(),
-            api_client: ApiClient::new(api_base_url),
+            api_client: ApiClient::new(api_base_url, extra_headers),
             last_refresh: Instant::now(),
             auto_refresh: true,
             refresh_interval: Duration::from_secs(5),
@@ -126,6 +410,18 @@ impl App {
             error_message: None,
             api_key: None,
             auth_error: None,
+            marked_log: None,
+            collapse_duplicates: false,
+            bookmarks,
+            selected_bookmark: 0,
+            bookmarks_file,
+            auto_follow: true,
+            level_colors,
+            exceeded_only: false,
+            details_json: false,
+            time_window: None,
+            focus_window,
+            total_logs: None,
         }
     }
 
@@ -154,6 +450,8 @@ impl App {
     /// - For container logs: uses `fetch_container_logs` or `search_container_logs` endpoints
     /// - Applies current sort settings to retrieved data
     /// - Updates last refresh timestamp
+    /// - Keeps the selection on the newest log if it was there before (auto-follow)
+    /// - Otherwise keeps the selection on the previously selected log, if still present
     /// - Adjusts selection if current index is out of bounds
     ///
     /// # Returns
@@ -164,18 +462,25 @@ impl App {
         self.loading = true;
         self.error_message = None;
 
+        let (from, to) = self.time_window.unzip();
+        // Only listing sensor logs reports the number of all matches
         let result = match self.current_index_type {
             IndexType::Logs => {
                 if !self.search_query.is_empty() {
                     self.api_client
                         .search_logs(&self.search_query, Some(self.log_limit), Some(0))
                         .await
-                        .map(|logs| logs.into_iter().map(LogEntryType::Regular).collect())
+                        .map(|logs| (logs.into_iter().map(LogEntryType::Regular).collect(), None))
                 } else {
                     self.api_client
-                        .fetch_logs(Some(self.log_limit), Some(0), None, None, None, None)
+                        .fetch_logs(Some(self.log_limit), Some(0), None, None, from, to)
                         .await
-                        .map(|logs| logs.into_iter().map(LogEntryType::Regular).collect())
+                        .map(|response| {
+                            (
+                                response.logs.into_iter().map(LogEntryType::Regular).collect(),
+                                response.total,
+                            )
+                        })
                 }
             }
             IndexType::ContainerLogs => {
@@ -183,21 +488,44 @@ impl App {
                     self.api_client
                         .search_container_logs(&self.search_query, Some(self.log_limit), Some(0))
                         .await
-                        .map(|logs| logs.into_iter().map(LogEntryType::Container).collect())
+                        .map(|logs| (logs.into_iter().map(LogEntryType::Container).collect(), None))
                 } else {
                     self.api_client
-                        .fetch_container_logs(Some(self.log_limit), Some(0), None, None, None)
+                        .fetch_container_logs(Some(self.log_limit), Some(0), None, from, to)
                         .await
-                        .map(|logs| logs.into_iter().map(LogEntryType::Container).collect())
+                        .map(|logs| (logs.into_iter().map(LogEntryType::Container).collect(), None))
                 }
             }
         };
 
         match result {
-            Ok(mut logs) => {
+            Ok((mut logs, mut total)) => {
+                // Decided on the old logs => only follow if the user is looking at the newest log
+                let follow = self.is_following_newest();
+                let previous = self.get_selected_log().map(Bookmark::from_log);
+
+                // Client side => works for listing and search results alike
+                if self.exceeded_only && self.current_index_type == IndexType::Logs {
+                    logs.retain(LogEntryType::has_exceeded_values);
+                    // The server side total doesn't know about the client side filter
+                    total = None;
+                }
+                // Search endpoints don't take a time range => narrowed down client side
+                if let Some((from, to)) = self.time_window {
+                    logs.retain(|log| (from..=to).contains(&log.timestamp()));
+                }
                 self.sort_logs(&mut logs);
                 self.logs = logs;
+                self.total_logs = total;
                 self.last_refresh = Instant::now();
+
+                if follow {
+                    self.select_newest();
+                } else if let Some(index) = previous
+                    .and_then(|previous| self.logs.iter().position(|log| previous.matches(log)))
+                {
+                    self.selected_index = index;
+                }
                 if self.selected_index >= self.logs.len() && !self.logs.is_empty() {
                     self.selected_index = self.logs.len() - 1;
                 }
@@ -219,11 +547,21 @@ impl App {
     /// # Behavior
     ///
     /// - Decrements `selected_index` if not already at the top
+    /// - Jumps to the start of the previous group if duplicates are collapsed
     /// - Adjusts `scroll_offset` if selection moves above visible area
     /// - No-op if already at the first log entry
     pub fn move_selection_up(&mut self) {
         if self.selected_index > 0 {
-            self.selected_index -= 1;
+            self.selected_index = if self.collapse_duplicates {
+                self.visible_rows()
+                    .into_iter()
+                    .rev()
+                    .find(|group| group.start < self.selected_index && !group.contains(self.selected_index))
+                    .map(|group| group.start)
+                    .unwrap_or(self.selected_index)
+            } else {
+                self.selected_index - 1
+            };
             if self.selected_index < self.scroll_offset {
                 self.scroll_offset = self.selected_index;
             }
@@ -235,14 +573,63 @@ impl App {
     /// # Behavior
     ///
     /// - Increments `selected_index` if not already at the last log entry
+    /// - Jumps to the start of the next group if duplicates are collapsed
     /// - No-op if already at the bottom of the log list
     /// - Does not handle scroll offset (handled by UI rendering)
     pub fn move_selection_down(&mut self) {
-        if self.selected_index + 1 < self.logs.len() {
+        if self.collapse_duplicates {
+            if let Some(group) = self
+                .visible_rows()
+                .into_iter()
+                .find(|group| group.start > self.selected_index)
+            {
+                self.selected_index = group.start;
+            }
+        } else if self.selected_index + 1 < self.logs.len() {
             self.selected_index += 1;
         }
     }
 
+    /// Returns the rows of the log list.
+    ///
+    /// # Returns
+    ///
+    /// Groups of consecutive duplicates if `collapse_duplicates` is enabled,
+    /// otherwise one group per log
+    pub fn visible_rows(&self) -> Vec<LogGroup> {
+        if self.collapse_duplicates {
+            collapse_duplicates(&self.logs)
+        } else {
+            (0..self.logs.len())
+                .map(|start| LogGroup { start, count: 1 })
+                .collect()
+        }
+    }
+
+    /// Toggles showing only sensor logs with an exceeded threshold and refetches the logs.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or an error if the refresh fails
+    pub async fn toggle_exceeded_only(&mut self) -> Result<()> {
+        self.exceeded_only = !self.exceeded_only;
+        self.refresh_logs().await
+    }
+
+    /// Toggles collapsing of consecutive duplicate logs.
+    ///
+    /// The selection is moved to the start of its group so it stays on a visible row.
+    pub fn toggle_collapse_duplicates(&mut self) {
+        self.collapse_duplicates = !self.collapse_duplicates;
+        if let Some(group) = self
+            .visible_rows()
+            .into_iter()
+            .find(|group| group.contains(self.selected_index))
+        {
+            self.selected_index = group.start;
+        }
+    }
+
     /// Enters search mode and prepares for user input.
     ///
     /// Switches the application to Search mode and clears the input buffer
@@ -333,7 +720,7 @@ impl App {
     ///
     /// # Sensor Logs (IndexType::Logs)
     /// - **Timestamp**: Chronological ordering
-    /// - **Level**: Priority-based (Critical > Warn > Info)
+    /// - **Level**: Priority-based (Critical > Warn > Info > Debug)
     /// - **Device**: Alphabetical by device name
     /// - **Temperature/Humidity**: Numerical comparison
     ///
@@ -354,14 +741,16 @@ impl App {
                             SortField::Timestamp => a.timestamp.cmp(&b.timestamp),
                             SortField::Level => {
                                 let a_priority = match a.level {
-                                    LogLevel::Critical => 2,
-                                    LogLevel::Warn => 1,
-                                    LogLevel::Info => 0,
+                                    LogLevel::Critical => 3,
+                                    LogLevel::Warn => 2,
+                                    LogLevel::Info => 1,
+                                    LogLevel::Debug => 0,
                                 };
                                 let b_priority = match b.level {
-                                    LogLevel::Critical => 2,
-                                    LogLevel::Warn => 1,
-                                    LogLevel::Info => 0,
+                                    LogLevel::Critical => 3,
+                                    LogLevel::Warn => 2,
+                                    LogLevel::Info => 1,
+                                    LogLevel::Debug => 0,
                                 };
                                 a_priority.cmp(&b_priority)
                             }
@@ -420,14 +809,16 @@ impl App {
                             SortField::Timestamp => a.timestamp.cmp(&b.timestamp),
                             SortField::Level => {
                                 let a_priority = match a.level {
-                                    LogLevel::Critical => 2,
-                                    LogLevel::Warn => 1,
-                                    LogLevel::Info => 0,
+                                    LogLevel::Critical => 3,
+                                    LogLevel::Warn => 2,
+                                    LogLevel::Info => 1,
+                                    LogLevel::Debug => 0,
                                 };
                                 let b_priority = match b.level {
-                                    LogLevel::Critical => 2,
-                                    LogLevel::Warn => 1,
-                                    LogLevel::Info => 0,
+                                    LogLevel::Critical => 3,
+                                    LogLevel::Warn => 2,
+                                    LogLevel::Info => 1,
+                                    LogLevel::Debug => 0,
                                 };
                                 a_priority.cmp(&b_priority)
                             }
@@ -536,6 +927,265 @@ impl App {
         }
     }
 
+    /// Opens the raw JSON of the selected log in `$EDITOR`.
+    ///
+    /// The JSON is written to a freshly created temp file (random name, only readable by the user,
+    /// removed afterwards), the TUI is suspended (raw mode and alternate screen
+    /// are left) while the editor runs and restored afterwards - also if the editor fails.
+    /// The caller has to clear the terminal afterwards to force a full redraw.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` after the editor exited successfully (or if no log is selected),
+    /// an error if `$EDITOR` is not set, could not be started or exited with a failure
+    pub fn edit_selected(&mut self) -> Result<()> {
+        let Some(selected) = self.get_selected_log() else {
+            return Ok(());
+        };
+
+        // $EDITOR may contain arguments, e.g. "code --wait"
+        let editor = env::var("EDITOR").unwrap_or_default();
+        let mut editor_parts = editor.split_whitespace();
+        let program = editor_parts.next().ok_or_else(|| anyhow!("$EDITOR is not set"))?;
+
+        // Created exclusively with a random name => no other user can plant or read the file
+        let mut file = tempfile::Builder::new()
+            .prefix("log-tui-")
+            .suffix(".json")
+            .tempfile()
+            .context("Failed to create temp file")?;
+        file.write_all(selected.to_json()?.as_bytes())
+            .and_then(|_| file.flush())
+            .context("Failed to write temp file")?;
+
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+        let status = Command::new(program).args(editor_parts).arg(file.path()).status();
+
+        // Restore the TUI before evaluating the editor result
+        let restored = enable_raw_mode()
+            .and_then(|_| execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture));
+        // Also removed on early returns, since the file is deleted on drop
+        let _ = file.close();
+        restored?;
+
+        let status = status.with_context(|| format!("Failed to start editor '{}'", editor))?;
+        if !status.success() {
+            bail!("Editor '{}' exited with {}", editor, status);
+        }
+
+        Ok(())
+    }
+
+    /// Bookmarks the selected log, or removes its bookmark if it is already bookmarked.
+    ///
+    /// No-op if the log list is empty. Persists the bookmarks if a bookmarks file is configured.
+    pub fn toggle_bookmark(&mut self) {
+        let Some(selected) = self.get_selected_log() else {
+            return;
+        };
+
+        if let Some(position) = self.bookmarks.iter().position(|bookmark| bookmark.matches(selected)) {
+            self.bookmarks.remove(position);
+        } else {
+            self.bookmarks.push(Bookmark::from_log(selected));
+        }
+        self.save_bookmarks();
+    }
+
+    /// Returns `true` if the given log is bookmarked.
+    pub fn is_bookmarked(&self, log: &LogEntryType) -> bool {
+        self.bookmarks.iter().any(|bookmark| bookmark.matches(log))
+    }
+
+    /// Opens the bookmark list. No-op without bookmarks.
+    pub fn enter_bookmarks_mode(&mut self) {
+        if !self.bookmarks.is_empty() {
+            self.selected_bookmark = self.selected_bookmark.min(self.bookmarks.len() - 1);
+            self.mode = Mode::Bookmarks;
+        }
+    }
+
+    /// Moves the bookmark list selection up (`up == true`) or down.
+    pub fn move_bookmark_selection(&mut self, up: bool) {
+        if up {
+            self.selected_bookmark = self.selected_bookmark.saturating_sub(1);
+        } else if self.selected_bookmark + 1 < self.bookmarks.len() {
+            self.selected_bookmark += 1;
+        }
+    }
+
+    /// Jumps to the log of the selected bookmark and returns to Normal mode.
+    ///
+    /// The bookmarked log has to be within the currently loaded logs, otherwise
+    /// an error message is shown (e.g. adjust the limit or clear the search).
+    pub fn jump_to_bookmark(&mut self) {
+        let Some(bookmark) = self.bookmarks.get(self.selected_bookmark) else {
+            return;
+        };
+
+        match self.logs.iter().position(|log| bookmark.matches(log)) {
+            Some(index) => {
+                self.selected_index = index;
+                self.scroll_offset = self.scroll_offset.min(index);
+                // Keep the selection on a visible row if duplicates are collapsed
+                if let Some(group) = self.visible_rows().into_iter().find(|group| group.contains(index)) {
+                    self.selected_index = group.start;
+                }
+            }
+            None => {
+                self.error_message = Some(format!(
+                    "Bookmarked log {} ({}) is not loaded",
+                    bookmark.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    bookmark.source
+                ));
+            }
+        }
+        self.mode = Mode::Normal;
+    }
+
+    /// Removes the selected bookmark and leaves the list once it is empty.
+    pub fn remove_selected_bookmark(&mut self) {
+        if self.selected_bookmark < self.bookmarks.len() {
+            self.bookmarks.remove(self.selected_bookmark);
+            self.save_bookmarks();
+        }
+        if self.bookmarks.is_empty() {
+            self.mode = Mode::Normal;
+        } else {
+            self.selected_bookmark = self.selected_bookmark.min(self.bookmarks.len() - 1);
+        }
+    }
+
+    /// Writes the bookmarks to the bookmarks file, if configured.
+    fn save_bookmarks(&mut self) {
+        let Some(path) = &self.bookmarks_file else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&self.bookmarks)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| fs::write(path, content).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            self.error_message = Some(format!("Saving bookmarks failed: {}", e));
+        }
+    }
+
+    /// Marks the selected log or opens the diff view against the marked log.
+    ///
+    /// # Behavior
+    ///
+    /// - Without a marked log: marks the currently selected log
+    /// - With a marked log: switches to Diff mode comparing the marked and selected log
+    /// - No-op if the log list is empty
+    pub fn mark_or_diff(&mut self) {
+        let Some(selected) = self.get_selected_log().cloned() else {
+            return;
+        };
+
+        if self.marked_log.is_none() {
+            self.marked_log = Some(selected);
+        } else {
+            self.mode = Mode::Diff;
+        }
+    }
+
+    /// Clears the marked log and returns to Normal mode.
+    pub fn clear_mark(&mut self) {
+        self.marked_log = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Returns the field diff between the marked and the selected log.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Vec<FieldDiff>)` if a log is marked and selected, `None` otherwise
+    pub fn get_diff(&self) -> Option<Vec<FieldDiff>> {
+        match (&self.marked_log, self.get_selected_log()) {
+            (Some(marked), Some(selected)) => Some(diff_fields(marked, selected)),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the newest log in the current order.
+    ///
+    /// # Returns
+    ///
+    /// `Some(index)` if logs are sorted by timestamp and not empty, `None` otherwise,
+    /// since there is no fixed newest position for other sort fields
+    pub fn newest_index(&self) -> Option<usize> {
+        if self.logs.is_empty() || self.sort_state.field != SortField::Timestamp {
+            return None;
+        }
+        match self.sort_state.direction {
+            SortDirection::Descending => Some(0),
+            SortDirection::Ascending => Some(self.logs.len() - 1),
+        }
+    }
+
+    /// Returns `true` if auto-follow is enabled and the selection is on the newest log
+    /// (or on the collapsed row containing it).
+    pub fn is_following_newest(&self) -> bool {
+        sticks_to_newest(self.auto_follow, &self.visible_rows(), self.newest_index(), self.selected_index)
+    }
+
+    /// Moves the selection to the newest log (or the start of its collapsed row).
+    fn select_newest(&mut self) {
+        if let Some(newest) = self.newest_index() {
+            self.selected_index = self
+                .visible_rows()
+                .into_iter()
+                .find(|group| group.contains(newest))
+                .map(|group| group.start)
+                .unwrap_or(newest);
+            self.scroll_offset = self.scroll_offset.min(self.selected_index);
+        }
+    }
+
+    /// Toggles the details view between the formatted fields and the raw pretty printed JSON.
+    ///
+    /// The choice is kept when the details of another log are opened.
+    pub fn toggle_details_json(&mut self) {
+        self.details_json = !self.details_json;
+    }
+
+    /// Restricts the logs to a window around the selected log and refreshes.
+    ///
+    /// The window stays active when switching the index, so e.g. container logs around a
+    /// sensor event can be inspected. Auto-follow is disabled to keep the selected log in view.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Span before and after the timestamp of the selected log
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success or without selected log, or an error if the refresh fails
+    pub async fn focus_around_selected(&mut self, window: Duration) -> Result<()> {
+        let Some(log) = self.get_selected_log() else {
+            return Ok(());
+        };
+        self.time_window = Some(time_window_around(log.timestamp(), window));
+        self.auto_follow = false;
+        self.refresh_logs().await
+    }
+
+    /// Removes the time window set by `focus_around_selected` and refreshes.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or an error if the refresh fails
+    pub async fn clear_time_window(&mut self) -> Result<()> {
+        self.time_window = None;
+        self.refresh_logs().await
+    }
+
+    /// Toggles auto-follow of the newest log on refresh.
+    pub fn toggle_auto_follow(&mut self) {
+        self.auto_follow = !self.auto_follow;
+    }
+
     /// Toggles the auto-refresh functionality on/off.
     ///
     /// When auto-refresh is enabled, the application will automatically
@@ -562,15 +1212,19 @@ impl App {
     ///
     /// # Returns
     ///
+    /// The color configured via `LEVEL_COLORS`, otherwise the default:
     /// * `Red` for Critical level
     /// * `Yellow` for Warn level  
     /// * `Blue` for Info level
-    pub fn get_log_level_color(&self, level: &LogLevel) -> ratatui::style::Color {
-        match level {
-            LogLevel::Critical => ratatui::style::Color::Red,
-            LogLevel::Warn => ratatui::style::Color::Yellow,
-            LogLevel::Info => ratatui::style::Color::Blue,
-        }
+    /// * `Gray` for Debug level
+    pub fn get_log_level_color(&self, level: &LogLevel) -> Color {
+        let (name, default) = match level {
+            LogLevel::Critical => ("critical", Color::Red),
+            LogLevel::Warn => ("warn", Color::Yellow),
+            LogLevel::Info => ("info", Color::Blue),
+            LogLevel::Debug => ("debug", Color::Gray),
+        };
+        self.level_colors.get(name).copied().unwrap_or(default)
     }
 
     /// Authenticates with the API using the provided API key.
@@ -675,3 +1329,312 @@ impl App {
         "*".repeat(self.input_buffer.len())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::InnerMsg;
+
+    fn sensor_log(device: &str, msg: &str) -> LogEntryType {
+        LogEntryType::Regular(LogEntry {
+            timestamp: "2025-01-01T00:00:00Z".parse().unwrap(),
+            level: LogLevel::Info,
+            temperature: 21.5,
+            humidity: 40.0,
+            msg: InnerMsg {
+                device: device.to_string(),
+                msg: msg.to_string(),
+                exceeded_values: vec![false, false],
+                exceeded_fields: Vec::new(),
+            },
+            ingested_at: None,
+        })
+    }
+
+    fn container_log(container: &str, message: &str) -> LogEntryType {
+        LogEntryType::Container(ContainerLogEntry {
+            timestamp: "2025-01-01T00:00:00Z".parse().unwrap(),
+            container_name: container.to_string(),
+            log_message: message.to_string(),
+            ingested_at: None,
+        })
+    }
+
+    #[test]
+    fn diff_fields_marks_only_changed_fields() {
+        let diff = diff_fields(&sensor_log("Arduino0", "ok"), &sensor_log("Arduino1", "ok"));
+
+        let changed: Vec<&str> = diff.iter().filter(|field| field.differs()).map(|field| field.name).collect();
+        assert_eq!(changed, vec!["Device"]);
+        assert_eq!(diff.len(), 7);
+    }
+
+    #[test]
+    fn diff_fields_of_different_types_fills_missing_fields() {
+        let diff = diff_fields(&sensor_log("Arduino0", "ok"), &container_log("web", "ok"));
+
+        let container = diff.iter().find(|field| field.name == "Container").unwrap();
+        assert_eq!(container.marked, "-");
+        assert_eq!(container.selected, "web");
+        let device = diff.iter().find(|field| field.name == "Device").unwrap();
+        assert_eq!(device.selected, "-");
+        // Fields of the marked entry first, fields only on the selected entry appended
+        assert_eq!(diff.last().unwrap().name, "Container");
+    }
+
+    #[test]
+    fn collapse_duplicates_groups_consecutive_duplicates_only() {
+        let logs = vec![
+            sensor_log("Arduino0", "ok"),
+            sensor_log("Arduino0", "ok"),
+            sensor_log("Arduino1", "ok"),
+            sensor_log("Arduino0", "ok"),
+            container_log("Arduino0", "ok"),
+        ];
+
+        assert_eq!(
+            collapse_duplicates(&logs),
+            vec![
+                LogGroup { start: 0, count: 2 },
+                LogGroup { start: 2, count: 1 },
+                // Container and device share the key => collapsed like duplicates of the same type
+                LogGroup { start: 3, count: 2 },
+            ]
+        );
+        assert!(collapse_duplicates(&[]).is_empty());
+    }
+
+    #[test]
+    fn log_group_contains_its_range() {
+        let group = LogGroup { start: 2, count: 3 };
+
+        assert!(!group.contains(1));
+        assert!(group.contains(2) && group.contains(4));
+        assert!(!group.contains(5));
+    }
+
+    #[test]
+    fn parse_level_colors_reads_names_hex_and_indices() {
+        let colors = parse_level_colors(" Critical=magenta, warn=#ffaa00,info = 208,").unwrap();
+
+        assert_eq!(colors.len(), 3);
+        assert_eq!(colors["critical"], Color::Magenta);
+        assert_eq!(colors["warn"], Color::Rgb(0xff, 0xaa, 0x00));
+        assert_eq!(colors["info"], Color::Indexed(208));
+        assert!(parse_level_colors("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_level_colors_rejects_invalid_entries() {
+        assert!(parse_level_colors("critical").is_err());
+        assert!(parse_level_colors("critical=notacolor").is_err());
+    }
+
+    /// App showing the given logs, optionally persisting bookmarks to a file
+    fn app(logs: Vec<LogEntryType>, bookmarks_file: Option<PathBuf>) -> App {
+        let mut app = App::new(
+            "http://localhost:8080".to_string(),
+            HeaderMap::new(),
+            bookmarks_file,
+            HashMap::new(),
+            Duration::from_secs(300),
+        );
+        app.logs = logs;
+        app.mode = Mode::Normal;
+        app
+    }
+
+    fn devices() -> Vec<LogEntryType> {
+        vec![sensor_log("Arduino0", "ok"), sensor_log("Arduino1", "ok"), sensor_log("Arduino2", "ok")]
+    }
+
+    #[test]
+    fn toggle_bookmark_adds_and_removes_bookmark_of_selected_log() {
+        let mut app = app(devices(), None);
+        app.selected_index = 1;
+
+        app.toggle_bookmark();
+        assert_eq!(app.bookmarks, vec![Bookmark::from_log(&app.logs[1])]);
+        assert!(app.is_bookmarked(&app.logs[1]));
+        assert!(!app.is_bookmarked(&app.logs[0]));
+
+        app.toggle_bookmark();
+        assert!(app.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn toggle_bookmark_persists_to_bookmarks_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut first = app(devices(), Some(file.path().to_path_buf()));
+        first.selected_index = 2;
+
+        first.toggle_bookmark();
+
+        let second = app(devices(), Some(file.path().to_path_buf()));
+        assert_eq!(second.bookmarks, first.bookmarks);
+        assert!(second.is_bookmarked(&second.logs[2]));
+    }
+
+    #[test]
+    fn jump_to_bookmark_selects_bookmarked_logs() {
+        let mut app = app(devices(), None);
+        app.selected_index = 2;
+        app.toggle_bookmark();
+        app.selected_index = 0;
+        app.toggle_bookmark();
+        app.selected_index = 1;
+
+        app.enter_bookmarks_mode();
+        assert_eq!(app.mode, Mode::Bookmarks);
+        app.jump_to_bookmark();
+        assert_eq!((app.selected_index, app.mode), (2, Mode::Normal));
+
+        app.enter_bookmarks_mode();
+        app.move_bookmark_selection(false);
+        app.jump_to_bookmark();
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn jump_to_bookmark_of_unloaded_log_keeps_selection() {
+        let mut app = app(devices(), None);
+        app.selected_index = 2;
+        app.toggle_bookmark();
+        app.logs.truncate(2);
+        app.selected_index = 1;
+
+        app.enter_bookmarks_mode();
+        app.jump_to_bookmark();
+
+        assert_eq!(app.selected_index, 1);
+        assert!(app.error_message.as_deref().is_some_and(|message| message.contains("Arduino2")));
+    }
+
+    #[test]
+    fn enter_bookmarks_mode_without_bookmarks_stays_in_normal_mode() {
+        let mut app = app(devices(), None);
+
+        app.enter_bookmarks_mode();
+
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    fn rows(count: usize) -> Vec<LogGroup> {
+        (0..count).map(|start| LogGroup { start, count: 1 }).collect()
+    }
+
+    #[test]
+    fn sticks_to_newest_only_while_selection_is_on_newest_row() {
+        // Ascending order => the newest log is at the bottom
+        assert!(sticks_to_newest(true, &rows(5), Some(4), 4));
+        assert!(!sticks_to_newest(true, &rows(5), Some(4), 3));
+        // Collapsed duplicates => any log of the newest row follows
+        let rows = vec![LogGroup { start: 0, count: 3 }, LogGroup { start: 3, count: 2 }];
+        assert!(sticks_to_newest(true, &rows, Some(4), 3));
+    }
+
+    #[test]
+    fn sticks_to_newest_never_without_auto_follow_or_newest_position() {
+        assert!(!sticks_to_newest(false, &rows(5), Some(4), 4));
+        assert!(!sticks_to_newest(true, &rows(5), None, 4));
+        assert!(!sticks_to_newest(true, &[], Some(0), 0));
+    }
+
+    #[test]
+    fn following_newest_unsticks_when_user_scrolls_up() {
+        let mut app = app(devices(), None);
+        app.sort_state.direction = SortDirection::Ascending;
+        app.selected_index = 2;
+        assert!(app.is_following_newest());
+
+        app.move_selection_up();
+        assert!(!app.is_following_newest());
+
+        app.move_selection_down();
+        assert!(app.is_following_newest());
+    }
+
+    /// Sensor log with the given exceeded flags
+    fn exceeded_log(exceeded_values: Vec<bool>) -> LogEntryType {
+        let mut log = sensor_log("Arduino0", "WARN: threshold exceeded");
+        if let LogEntryType::Regular(log_entry) = &mut log {
+            log_entry.msg.exceeded_values = exceeded_values;
+        }
+        log
+    }
+
+    #[test]
+    fn has_exceeded_values_detects_any_exceeded_threshold() {
+        assert!(exceeded_log(vec![true, false]).has_exceeded_values());
+        assert!(exceeded_log(vec![false, true]).has_exceeded_values());
+        assert!(!exceeded_log(vec![false, false]).has_exceeded_values());
+        assert!(!exceeded_log(Vec::new()).has_exceeded_values());
+        assert!(!container_log("web", "ok").has_exceeded_values());
+    }
+
+    #[test]
+    fn exceeded_filter_keeps_only_logs_with_exceeded_values() {
+        let mut logs = vec![
+            exceeded_log(vec![false, false]),
+            exceeded_log(vec![true, true]),
+            sensor_log("Arduino1", "ok"),
+            exceeded_log(vec![false, true]),
+        ];
+
+        logs.retain(LogEntryType::has_exceeded_values);
+
+        assert_eq!(logs.len(), 2);
+        assert!(logs.iter().all(LogEntryType::has_exceeded_values));
+    }
+
+    #[test]
+    fn to_json_round_trips_both_entry_types() {
+        let mut sensor = exceeded_log(vec![true, false]);
+        if let LogEntryType::Regular(log_entry) = &mut sensor {
+            log_entry.msg.exceeded_fields = vec!["temperature".to_string(), "humidity".to_string()];
+            log_entry.ingested_at = Some("2025-01-01T00:00:01Z".parse().unwrap());
+        }
+        let container = container_log("web", "GET /health 200");
+
+        let json = sensor.to_json().unwrap();
+        let parsed = LogEntryType::Regular(serde_json::from_str(&json).unwrap());
+        assert_eq!(parsed.to_json().unwrap(), json);
+        assert_eq!(parsed.fields(), sensor.fields());
+        // Pretty printed => one field per line
+        assert!(json.lines().any(|line| line.trim() == "\"temperature\": 21.5,"));
+
+        let json = container.to_json().unwrap();
+        let parsed = LogEntryType::Container(serde_json::from_str(&json).unwrap());
+        assert_eq!(parsed.to_json().unwrap(), json);
+        assert_eq!(parsed.fields(), container.fields());
+        // Fields the formatted view omits are part of the JSON
+        assert!(sensor.to_json().unwrap().contains("\"ingested_at\": \"2025-01-01T00:00:01Z\""));
+    }
+
+    #[test]
+    fn time_window_around_spans_window_before_and_after() {
+        let timestamp: DateTime<Utc> = "2025-01-01T12:00:00Z".parse().unwrap();
+
+        let (from, to) = time_window_around(timestamp, Duration::from_secs(300));
+
+        assert_eq!(from, "2025-01-01T11:55:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(to, "2025-01-01T12:05:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+
+    #[test]
+    fn time_window_around_with_empty_window_covers_only_the_timestamp() {
+        let timestamp: DateTime<Utc> = "2025-01-01T00:00:00Z".parse().unwrap();
+        let (from, to) = time_window_around(timestamp, Duration::ZERO);
+        assert_eq!((from, to), (timestamp, timestamp));
+        // Whole seconds only => sub-second windows are empty as well
+        assert_eq!(time_window_around(timestamp, Duration::from_millis(500)), (timestamp, timestamp));
+
+        let mut logs = vec![sensor_log("Arduino0", "ok"), exceeded_log(vec![true, false])];
+        if let LogEntryType::Regular(log_entry) = &mut logs[1] {
+            log_entry.timestamp = timestamp + chrono::Duration::seconds(1);
+        }
+        logs.retain(|log| (from..=to).contains(&log.timestamp()));
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].timestamp(), timestamp);
+    }
+}
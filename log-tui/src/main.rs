@@ -23,6 +23,15 @@ use std::{
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let api_base_url = env::var("LOG_API_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    // Validate before entering raw mode so syntax errors are printed readably
+    let extra_headers = api::parse_extra_headers(&env::var("EXTRA_HEADERS").unwrap_or_default())?;
+    let level_colors = app::parse_level_colors(&env::var("LEVEL_COLORS").unwrap_or_default())?;
+    let focus_window = Duration::from_secs(
+        env::var("FOCUS_WINDOW_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300),
+    );
     
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -30,7 +39,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(api_base_url);
+    let bookmarks_file = env::var("BOOKMARKS_FILE").ok().filter(|path| !path.is_empty()).map(Into::into);
+    let mut app = App::new(api_base_url, extra_headers, bookmarks_file, level_colors, focus_window);
 
     let res = run_app(&mut terminal, &mut app).await;
 
@@ -55,7 +65,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 /// - Rendering the terminal interface at regular intervals (250ms tick rate)
 /// - Processing user input events (keyboard)
 /// - Managing auto-refresh functionality for log data
-/// - Coordinating between different application modes (Auth, Normal, Search, Details, Limit)
+/// - Coordinating between different application modes (Auth, Normal, Search, Details, Diff, Bookmarks, Limit)
 ///
 /// # Arguments
 ///
@@ -91,12 +101,32 @@ async fn main() -> Result<(), Box<dyn Error>> {
 /// - `o` - Toggle sort direction
 /// - `l` - Enter limit mode
 /// - `a` - Toggle auto-refresh
+/// - `t` - Toggle auto-follow of the newest log
 /// - `c` - Clear search
+/// - `u` - Toggle collapsing of consecutive duplicate logs
+/// - `x` - Toggle showing only sensor logs with exceeded thresholds
+/// - `w` - Show only logs around the selected log / clear the time window
 /// - `i` - Switch between sensor/container logs
 /// - `Enter` - View log details
+/// - `Space` - Mark selected log / diff against marked log
+/// - `b` - Toggle bookmark of the selected log
+/// - `'` - Open bookmark list
 ///
 /// **Details Mode:**
 /// - `Esc/Enter` - Exit details view
+/// - `j` - Toggle between formatted fields and pretty printed JSON
+/// - `w` - Show only logs around this log and close the details view
+/// - `e` - Open the raw JSON in `$EDITOR`
+///
+/// **Diff Mode:**
+/// - `Esc/Enter` - Exit diff view
+/// - `Space` - Clear mark and exit diff view
+///
+/// **Bookmarks Mode:**
+/// - `Up/Down` - Navigate bookmarks
+/// - `Enter` - Jump to bookmarked log
+/// - `d` - Remove bookmark
+/// - `Esc` - Close bookmark list
 ///
 /// **Search/Limit Mode:**
 /// - `Enter` - Execute search/limit
@@ -162,12 +192,33 @@ async fn run_app<B: Backend>(
                              }
                             KeyCode::Char('a') => {
                                 app.toggle_auto_refresh();
+                            }
+                            KeyCode::Char('t') => {
+                                app.toggle_auto_follow();
                             }
                              KeyCode::Char('c') => {
                                 app.clear_search();
                                 if let Err(e) = app.refresh_logs().await {
                                     app.error_message = Some(format!("Refresh failed: {}", e));
                                 }
+                            }
+                             KeyCode::Char('u') => {
+                                 app.toggle_collapse_duplicates();
+                             }
+                            KeyCode::Char('x') => {
+                                if let Err(e) = app.toggle_exceeded_only().await {
+                                    app.error_message = Some(format!("Refresh failed: {}", e));
+                                }
+                            }
+                            KeyCode::Char('w') => {
+                                let result = if app.time_window.is_some() {
+                                    app.clear_time_window().await
+                                } else {
+                                    app.focus_around_selected(app.focus_window).await
+                                };
+                                if let Err(e) = result {
+                                    app.error_message = Some(format!("Refresh failed: {}", e));
+                                }
                             }
                              KeyCode::Char('i') => {
                                  app.switch_index();
@@ -178,6 +229,15 @@ async fn run_app<B: Backend>(
                              KeyCode::Enter => {
                                  app.enter_details_mode();
                              }
+                             KeyCode::Char(' ') => {
+                                 app.mark_or_diff();
+                             }
+                             KeyCode::Char('b') => {
+                                 app.toggle_bookmark();
+                             }
+                             KeyCode::Char('\'') => {
+                                 app.enter_bookmarks_mode();
+                             }
                             _ => {}
                         }
                     }
@@ -186,6 +246,43 @@ async fn run_app<B: Backend>(
                                 KeyCode::Esc | KeyCode::Enter => {
                                     app.exit_mode();
                                 }
+                                KeyCode::Char('e') => {
+                                    if let Err(e) = app.edit_selected() {
+                                        app.error_message = Some(format!("Editor failed: {}", e));
+                                    }
+                                    // The editor drew over the alternate screen => redraw everything
+                                    terminal.clear()?;
+                                }
+                                KeyCode::Char('j') => {
+                                    app.toggle_details_json();
+                                }
+                                KeyCode::Char('w') => {
+                                    app.exit_mode();
+                                    if let Err(e) = app.focus_around_selected(app.focus_window).await {
+                                        app.error_message = Some(format!("Refresh failed: {}", e));
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        Mode::Diff => {
+                            match key.code {
+                                KeyCode::Esc | KeyCode::Enter => {
+                                    app.exit_mode();
+                                }
+                                KeyCode::Char(' ') => {
+                                    app.clear_mark();
+                                }
+                                _ => {}
+                            }
+                        }
+                        Mode::Bookmarks => {
+                            match key.code {
+                                KeyCode::Esc => app.exit_mode(),
+                                KeyCode::Up => app.move_bookmark_selection(true),
+                                KeyCode::Down => app.move_bookmark_selection(false),
+                                KeyCode::Enter => app.jump_to_bookmark(),
+                                KeyCode::Char('d') => app.remove_selected_bookmark(),
                                 _ => {}
                             }
                         }
@@ -1,10 +1,12 @@
 use crate::app::{App, Mode, SortDirection, SortField, IndexType, LogEntryType};
+use chrono::{DateTime, Utc};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{
-        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap,
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
     },
     Frame,
 };
@@ -22,7 +24,7 @@ use ratatui::{
 /// - Header (3 lines): Title, status, sort info, refresh indicators
 /// - Content (flexible): Log list with syntax highlighting and selection
 /// - Footer (3 lines): Context-sensitive help text
-/// - Overlays: Input popups for search/limit, detail view for selected logs
+/// - Overlays: Input popups for search/limit, detail view for selected logs, diff view against marked log, bookmark list
 ///
 /// **Auth Mode:**
 /// - Full-screen authentication interface with centered input form
@@ -52,6 +54,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             draw_input_popup(f, app);
         } else if app.mode == Mode::Details {
             draw_detail_popup(f, app);
+        } else if app.mode == Mode::Diff {
+            draw_diff_popup(f, app);
+        } else if app.mode == Mode::Bookmarks {
+            draw_bookmarks_popup(f, app);
         }
     }
 }
@@ -65,7 +71,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 /// # Header Elements
 ///
 /// - **Title**: Current mode or index type name
-/// - **Log count**: Current/limit display (e.g., "50/100 logs")
+/// - **Log count**: Current/total display (e.g., "100/4823 logs"), current/limit if the total is unknown
 /// - **Sort info**: Active sort field and direction with arrows
 /// - **Status**: Loading, error, or auto-refresh state
 /// - **Last refresh**: Time elapsed since last data fetch
@@ -91,12 +97,18 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
         Mode::Search => "Search Mode",
         Mode::Limit => "Limit Mode",
         Mode::Details => "Log Details",
+        Mode::Diff => "Log Diff",
+        Mode::Bookmarks => "Bookmarks",
     };
 
     let status_text = if app.loading {
         " [Loading...] ".to_string()
     } else if let Some(ref error) = app.error_message {
         format!(" [Error: {}] ", error)
+    } else if app.marked_log.is_some() {
+        " [Log marked] ".to_string()
+    } else if app.auto_refresh && app.is_following_newest() {
+        " [Auto-refresh ON | Following newest] ".to_string()
     } else if app.auto_refresh {
         " [Auto-refresh ON] ".to_string()
     } else {
@@ -145,18 +157,32 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
         }
     };
 
-    let header = Paragraph::new(Line::from(vec![
+    let mut header_spans = vec![
         Span::styled(title, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         Span::raw(" | "),
         Span::styled(
-            format!("{}/{} logs", app.logs.len(), app.log_limit),
+            // True total if the API reports it, otherwise the configured limit
+            format!("{}/{} logs", app.logs.len(), app.total_logs.unwrap_or(app.log_limit as u64)),
             Style::default().fg(Color::Green),
         ),
         Span::raw(" | "),
         Span::styled(sort_text, Style::default().fg(Color::Magenta)),
-        Span::styled(status_text, Style::default().fg(Color::Yellow)),
-        Span::styled(last_refresh_display, Style::default().fg(Color::LightBlue)),
-    ]))
+    ];
+    if app.exceeded_only && app.current_index_type == IndexType::Logs {
+        header_spans.push(Span::raw(" | "));
+        header_spans.push(Span::styled("Filter: exceeded", Style::default().fg(Color::Red)));
+    }
+    if let Some((from, to)) = app.time_window {
+        header_spans.push(Span::raw(" | "));
+        header_spans.push(Span::styled(
+            format!("Window: {} - {}", from.format("%Y-%m-%d %H:%M:%S"), to.format("%Y-%m-%d %H:%M:%S")),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    header_spans.push(Span::styled(status_text, Style::default().fg(Color::Yellow)));
+    header_spans.push(Span::styled(last_refresh_display, Style::default().fg(Color::LightBlue)));
+
+    let header = Paragraph::new(Line::from(header_spans))
     .block(Block::default().borders(Borders::ALL))
     .alignment(Alignment::Left);
 
@@ -180,11 +206,14 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
 /// # Visual Features
 ///
 /// - **Timestamps**: Gray color for consistent visual hierarchy
-/// - **Log levels**: Color-coded (Critical=Red, Warn=Yellow, Info=Blue)
+/// - **Log levels**: Color-coded (Critical=Red, Warn=Yellow, Info=Blue, Debug=Gray)
 /// - **Device/Container names**: Magenta for easy identification
 /// - **Temperature/Humidity**: Blue for sensor data
 /// - **Selection**: Dark gray background highlight
+/// - **Scrollbar**: Position of the selected row, shown if the rows exceed the visible height
 /// - **Search context**: Title shows active search query
+/// - **Bookmarks**: Yellow `★` in front of bookmarked rows
+/// - **Duplicates**: If collapsed, consecutive duplicates form one row with a yellow `(xN)` count
 ///
 /// # Empty States
 ///
@@ -216,12 +245,13 @@ fn draw_logs(f: &mut Frame, area: Rect, app: &mut App) {
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .logs
+    let rows = app.visible_rows();
+    let items: Vec<ListItem> = rows
         .iter()
-        .enumerate()
-        .map(|(i, log)| {
-            let content = match log {
+        .map(|group| {
+            let log = &app.logs[group.start];
+            let bookmarked = app.is_bookmarked(log);
+            let mut content = match log {
                 LogEntryType::Regular(log_entry) => {
                     let level_color = app.get_log_level_color(&log_entry.level);
                     let timestamp = log_entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -272,7 +302,20 @@ fn draw_logs(f: &mut Frame, area: Rect, app: &mut App) {
                 }
             };
 
-            let style = if i == app.selected_index {
+            // Bookmarked rows are prefixed with a marker
+            if bookmarked {
+                content.spans.insert(0, Span::styled("★ ", Style::default().fg(Color::Yellow)));
+            }
+
+            // Collapsed duplicates are shown once with their count, like `uniq -c`
+            if group.count > 1 {
+                content.spans.push(Span::styled(
+                    format!(" (x{})", group.count),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            let style = if group.contains(app.selected_index) {
                 Style::default().bg(Color::DarkGray)
             } else {
                 Style::default()
@@ -282,20 +325,39 @@ fn draw_logs(f: &mut Frame, area: Rect, app: &mut App) {
         })
         .collect();
 
-    let title = if !app.search_query.is_empty() {
+    let mut title = if !app.search_query.is_empty() {
         format!("Logs (Search: '{}')", app.search_query)
     } else {
         "Logs".to_string()
     };
+    if app.collapse_duplicates {
+        title.push_str(" [Duplicates collapsed]");
+    }
 
     let logs_list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().bg(Color::DarkGray));
 
+    let selected_row = rows.iter().position(|group| group.contains(app.selected_index));
     let mut list_state = ListState::default();
-    list_state.select(Some(app.selected_index));
+    list_state.select(selected_row);
 
     f.render_stateful_widget(logs_list, area, &mut list_state);
+
+    // Scrollbar only if the rows don't fit into the list (area minus borders)
+    if rows.len() > area.height.saturating_sub(2) as usize {
+        let scrollbar = Scrollbar::default()
+            .orientation(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        let mut scrollbar_state = ScrollbarState::new(rows.len()).position(selected_row.unwrap_or(0));
+
+        f.render_stateful_widget(
+            scrollbar,
+            area.inner(&Margin { vertical: 1, horizontal: 0 }),
+            &mut scrollbar_state,
+        );
+    }
 }
 
 /// Renders the footer with context-sensitive help text based on current mode.
@@ -311,6 +373,8 @@ fn draw_logs(f: &mut Frame, area: Rect, app: &mut App) {
 /// - **Search**: Search query input and execution instructions
 /// - **Limit**: Log limit setting instructions with current value
 /// - **Details**: Detail view navigation instructions
+/// - **Diff**: Diff view navigation instructions
+/// - **Bookmarks**: Bookmark list navigation instructions
 ///
 /// # Styling
 ///
@@ -329,7 +393,7 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
             "Enter your API key | Enter: Authenticate | q: Quit"
         }
         Mode::Normal => {
-            "↑/↓: Navigate | Enter: Details | /: Search | f: Sort field | o: Sort order | l: Limit | r: Refresh | a: Auto-refresh | c: Clear | i: Switch index | q: Quit"
+            "↑/↓: Navigate | Enter: Details | Space: Mark/Diff | b: Bookmark | ': Bookmarks | /: Search | f: Sort field | o: Sort order | l: Limit | r: Refresh | a: Auto-refresh | t: Auto-follow | c: Clear | u: Collapse duplicates | x: Exceeded only | w: Time window | i: Switch index | q: Quit"
         }
         Mode::Search => {
             "Type search query | Enter: Execute search | Esc: Cancel"
@@ -338,7 +402,13 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
             "Enter number of logs to fetch (current: {}) | Enter: Apply | Esc: Cancel"
         }
         Mode::Details => {
-            "Enter/Esc: Close details"
+            "Enter/Esc: Close details | j: Toggle JSON | w: Logs around | e: Open in $EDITOR"
+        }
+        Mode::Diff => {
+            "Enter/Esc: Close diff | Space: Clear mark"
+        }
+        Mode::Bookmarks => {
+            "↑/↓: Navigate | Enter: Jump to log | d: Remove bookmark | Esc: Close"
         }
     };
     
@@ -411,7 +481,7 @@ fn draw_input_popup(f: &mut Frame, app: &App) {
 /// # Sensor Log Details
 ///
 /// - **Timestamp**: Full date/time with UTC designation
-/// - **Level**: Color-coded log level (Critical/Warn/Info)
+/// - **Level**: Color-coded log level (Critical/Warn/Info/Debug)
 /// - **Device**: Source device name in magenta
 /// - **Temperature**: Precise temperature reading in °C
 /// - **Humidity**: Precise humidity percentage
@@ -424,6 +494,11 @@ fn draw_input_popup(f: &mut Frame, app: &App) {
 /// - **Container**: Container name in magenta
 /// - **Message**: Full log message content
 ///
+/// # JSON View
+///
+/// With `app.details_json` set (`j`), the entry is shown as pretty printed JSON instead,
+/// including fields the formatted view omits.
+///
 /// # Visual Features
 ///
 /// - Large centered overlay (80% width, 50% height)
@@ -442,13 +517,26 @@ fn draw_detail_popup(f: &mut Frame, app: &App) {
         let area = centered_rect(80, 50, f.size());
         f.render_widget(Clear, area);
 
+        if app.details_json {
+            let content = log
+                .to_json()
+                .unwrap_or_else(|e| format!("Failed to serialize log: {}", e));
+            // No trimming, the indentation is part of the JSON view
+            let detail = Paragraph::new(content)
+                .block(Block::default().borders(Borders::ALL).title("Log Details (JSON)"))
+                .wrap(Wrap { trim: false });
+
+            f.render_widget(detail, area);
+            return;
+        }
+
         let content = match log {
             LogEntryType::Regular(log_entry) => {
                 let timestamp = log_entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string();
                 let level_str = format!("{:?}", log_entry.level);
                 let level_color = app.get_log_level_color(&log_entry.level);
 
-                Text::from(vec![
+                let mut lines = vec![
                     Line::from(vec![
                         Span::styled("Timestamp: ", Style::default().add_modifier(Modifier::BOLD)),
                         Span::raw(timestamp),
@@ -475,14 +563,18 @@ fn draw_detail_popup(f: &mut Frame, app: &App) {
                     ]),
                     Line::from(vec![
                         Span::styled("Exceeded Values: ", Style::default().add_modifier(Modifier::BOLD)),
-                        Span::raw(format!("{:?}", log_entry.msg.exceeded_values)),
+                        Span::raw(log_entry.msg.exceeded_summary()),
                     ]),
-                ])
+                ];
+                if let Some(line) = ingest_delay_line(log_entry.timestamp, log_entry.ingested_at) {
+                    lines.insert(1, line);
+                }
+                Text::from(lines)
             }
             LogEntryType::Container(log_entry) => {
                 let timestamp = log_entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string();
 
-                Text::from(vec![
+                let mut lines = vec![
                     Line::from(vec![
                         Span::styled("Timestamp: ", Style::default().add_modifier(Modifier::BOLD)),
                         Span::raw(timestamp),
@@ -495,7 +587,11 @@ fn draw_detail_popup(f: &mut Frame, app: &App) {
                         Span::styled("Message: ", Style::default().add_modifier(Modifier::BOLD)),
                         Span::raw(log_entry.log_message.clone()),
                     ]),
-                ])
+                ];
+                if let Some(line) = ingest_delay_line(log_entry.timestamp, log_entry.ingested_at) {
+                    lines.insert(1, line);
+                }
+                Text::from(lines)
             }
         };
 
@@ -507,6 +603,119 @@ fn draw_detail_popup(f: &mut Frame, app: &App) {
     }
 }
 
+/// Builds the detail line showing when a log was ingested and how long after its event time.
+///
+/// # Arguments
+///
+/// * `timestamp` - Event time of the log
+/// * `ingested_at` - Time the API indexed the log, missing for logs indexed before it was stamped
+///
+/// # Returns
+///
+/// Line like "Ingested: 2025-01-01 12:00:01 UTC (+1.250s)" or None without ingest time
+fn ingest_delay_line(timestamp: DateTime<Utc>, ingested_at: Option<DateTime<Utc>>) -> Option<Line<'static>> {
+    let ingested_at = ingested_at?;
+    let delay_ms = (ingested_at - timestamp).num_milliseconds();
+
+    Some(Line::from(vec![
+        Span::styled("Ingested: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(ingested_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        Span::styled(
+            format!(" ({:+.3}s)", delay_ms as f64 / 1000.0),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]))
+}
+
+/// Renders a field diff popup comparing the marked log with the selected log.
+///
+/// Each field is rendered as one line containing the field name, the value of
+/// the marked log and the value of the selected log. Differing fields are
+/// highlighted so changes between two similar logs are easy to spot.
+///
+/// # Visual Features
+///
+/// - Large centered overlay (80% width, 50% height)
+/// - Bordered container with "Log Diff (marked → selected)" title
+/// - Differing values in yellow/bold, equal values in gray
+/// - Fields missing on one side (different log types) shown as `-`
+///
+/// # Arguments
+///
+/// * `f` - Mutable reference to the terminal frame
+/// * `app` - Reference to the application state
+fn draw_diff_popup(f: &mut Frame, app: &App) {
+    if let Some(diff) = app.get_diff() {
+        let area = centered_rect(80, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let lines: Vec<Line> = diff
+            .iter()
+            .map(|field| {
+                let value_style = if field.differs() {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<17}", format!("{}:", field.name)),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(field.marked.clone(), value_style),
+                    Span::raw(" → "),
+                    Span::styled(field.selected.clone(), value_style),
+                ])
+            })
+            .collect();
+
+        let diff_view = Paragraph::new(Text::from(lines))
+            .block(Block::default().borders(Borders::ALL).title("Log Diff (marked → selected)"))
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(diff_view, area);
+    }
+}
+
+/// Renders the bookmark list as centered popup.
+///
+/// Each bookmark shows its timestamp and device (or container), the selected
+/// bookmark is highlighted.
+///
+/// # Arguments
+///
+/// * `f` - Mutable reference to the terminal frame
+/// * `app` - Reference to the application state
+fn draw_bookmarks_popup(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, f.size());
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app
+        .bookmarks
+        .iter()
+        .map(|bookmark| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<19}", bookmark.timestamp.format("%Y-%m-%d %H:%M:%S")),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(" "),
+                Span::styled(bookmark.source.clone(), Style::default().fg(Color::Magenta)),
+            ]))
+        })
+        .collect();
+
+    let bookmark_list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Bookmarks"))
+        .highlight_style(Style::default().bg(Color::DarkGray));
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected_bookmark));
+
+    f.render_stateful_widget(bookmark_list, area, &mut list_state);
+}
+
 /// Renders the full-screen authentication interface for API key entry.
 ///
 /// This function creates a centered authentication form that takes over the